@@ -0,0 +1,138 @@
+use auriumchain::blockchain::Blockchain;
+use auriumchain::storage::ChainScan;
+use auriumchain::utils::config::Network;
+use clap::{Parser, Subcommand};
+use std::collections::{HashMap, HashSet};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "chain_tool")]
+#[command(about = "AuriumChain local chain maintenance", long_about = None)]
+struct Cli {
+    /// Fichier JSON de la chaîne (celui de `auriumchain --data-file`)
+    #[arg(long, default_value = "/tmp/auriumchain.json")]
+    data_file: String,
+
+    /// Réseau dont les paramètres de consensus s'appliquent
+    #[arg(long, default_value = "mainnet")]
+    network: Network,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Vérifier hashes, chaînage et preuve de travail ; signaler le premier bloc invalide
+    Verify,
+    /// Tronquer la chaîne au dernier bloc valide (l'original est gardé en `.bak`)
+    Repair,
+    /// Hauteur, émission totale et nombre d'UTXO
+    Stats,
+    /// Reconstruire les index txid et UTXO depuis les blocs et signaler les incohérences
+    Reindex,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let params = cli.network.genesis_config().consensus_params();
+
+    let scan = match Blockchain::scan_file(&cli.data_file, params) {
+        Ok(scan) => scan,
+        Err(e) => {
+            eprintln!("❌ Cannot read {}: {}", cli.data_file, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let ok = match cli.command {
+        Commands::Verify => verify(&scan),
+        Commands::Repair => repair(&scan, &cli.data_file),
+        Commands::Stats => stats(&scan),
+        Commands::Reindex => reindex(&scan.valid),
+    };
+    if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn verify(scan: &ChainScan) -> bool {
+    match &scan.first_invalid {
+        None => {
+            println!("✅ Chain valid: {} blocks", scan.stored_blocks);
+            true
+        }
+        Some((height, reason)) => {
+            println!("❌ Invalid block at height {}: {}", height, reason);
+            println!("   {} valid blocks before it, {} stored", scan.valid.chain.len(), scan.stored_blocks);
+            false
+        }
+    }
+}
+
+fn repair(scan: &ChainScan, data_file: &str) -> bool {
+    let Some((height, reason)) = &scan.first_invalid else {
+        println!("✅ Nothing to repair: {} valid blocks", scan.stored_blocks);
+        return true;
+    };
+    if scan.valid.chain.is_empty() {
+        println!("❌ Genesis is invalid ({}), nothing to keep", reason);
+        return false;
+    }
+
+    let backup = format!("{}.bak", data_file);
+    if let Err(e) = std::fs::copy(data_file, &backup) {
+        eprintln!("❌ Cannot back up {} to {}: {}", data_file, backup, e);
+        return false;
+    }
+    if let Err(e) = scan.valid.save_to_file(data_file) {
+        eprintln!("❌ Cannot write {}: {}", data_file, e);
+        return false;
+    }
+    println!(
+        "🔧 Truncated at height {} ({}): {} of {} blocks kept, original saved to {}",
+        height, reason, scan.valid.chain.len(), scan.stored_blocks, backup
+    );
+    true
+}
+
+fn stats(scan: &ChainScan) -> bool {
+    let chain = &scan.valid;
+    let transactions: usize = chain.chain.iter().map(|block| block.transactions.len()).sum();
+    println!("Height:       {}", chain.chain.len().saturating_sub(1));
+    println!("Blocks:       {}", chain.chain.len());
+    println!("Transactions: {}", transactions);
+    println!("Supply:       {:.8} AUR", chain.total_issued() as f64 / 1e8);
+    println!("UTXOs:        {}", chain.utxo_set().len());
+    if let Some((height, reason)) = &scan.first_invalid {
+        println!("⚠️  Stats cover the valid prefix only: block {} is invalid ({})", height, reason);
+    }
+    true
+}
+
+fn reindex(chain: &Blockchain) -> bool {
+    let mut txids: HashMap<&str, u64> = HashMap::new();
+    let mut unspent: HashSet<(&str, usize)> = HashSet::new();
+    let mut problems = Vec::new();
+
+    for block in &chain.chain {
+        for tx in &block.transactions {
+            if let Some(first) = txids.insert(&tx.id, block.index) {
+                problems.push(format!("tx {} in block {} already confirmed in block {}", tx.id, block.index, first));
+            }
+            for input in &tx.inputs {
+                if !unspent.remove(&(input.prev_tx_id.as_str(), input.output_index)) {
+                    problems.push(format!(
+                        "block {} spends {}:{} which is unknown or already spent",
+                        block.index, input.prev_tx_id, input.output_index
+                    ));
+                }
+            }
+            unspent.extend((0..tx.outputs.len()).map(|index| (tx.id.as_str(), index)));
+        }
+    }
+
+    println!("Indexed {} transactions, {} UTXOs over {} blocks", txids.len(), unspent.len(), chain.chain.len());
+    for problem in &problems {
+        println!("❌ {}", problem);
+    }
+    problems.is_empty()
+}
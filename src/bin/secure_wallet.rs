@@ -1,4 +1,6 @@
+use auriumchain::utils::display;
 use auriumchain::wallet::secure_wallet::SecureWallet;
+use auriumchain::wallet::WalletError;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -30,6 +32,22 @@ enum Commands {
         #[arg(short, long)]
         name: String,
     },
+    /// Ajouter un contact au carnet d'adresses chiffré
+    AddContact {
+        #[arg(short, long)]
+        name: String,
+        
+        #[arg(short, long)]
+        label: String,
+        
+        #[arg(short, long)]
+        address: String,
+    },
+    /// Lister les contacts et les libellés de transactions
+    Contacts {
+        #[arg(short, long)]
+        name: String,
+    },
 }
 
 fn main() {
@@ -48,6 +66,12 @@ fn main() {
         Commands::Verify { name } => {
             verify_wallet(name);
         }
+        Commands::AddContact { name, label, address } => {
+            add_contact(name, label, address);
+        }
+        Commands::Contacts { name } => {
+            list_contacts(name);
+        }
     }
 }
 
@@ -117,11 +141,12 @@ fn show_wallet(name: &str) {
     println!("╠════════════════════════════════════════════════╣");
     println!("║  Name    : {:<38} ║", wallet.name);
     println!("║  Type    : {:<38} ║", wallet.address_type);
-    println!("║  Address : {:<38} ║", &wallet.address[..40.min(wallet.address.len())]);
-    if wallet.address.len() > 40 {
-        println!("║            {:<38} ║", &wallet.address[40..]);
+    let (address_head, address_tail) = display::split_at_chars(&wallet.address, 40);
+    println!("║  Address : {:<38} ║", address_head);
+    if !address_tail.is_empty() {
+        println!("║            {:<38} ║", address_tail);
     }
-    println!("║  Created : {:<38} ║", &wallet.created_at[..19]);
+    println!("║  Created : {:<38} ║", display::truncate(&wallet.created_at, 19));
     println!("╚════════════════════════════════════════════════╝\n");
     
     wallet.security_info();
@@ -142,7 +167,7 @@ fn export_seed(name: &str) {
     }
 
     let filename = format!("wallets/{}.secure.wallet", name);
-    let wallet = match SecureWallet::load(&filename) {
+    let mut wallet = match SecureWallet::load(&filename) {
         Ok(w) => w,
         Err(e) => {
             println!("❌ Error: {}", e);
@@ -151,31 +176,47 @@ fn export_seed(name: &str) {
     };
 
     println!("\n🔑 Enter wallet password:");
-    let password = rpassword::prompt_password("Password: ").unwrap();
-
-    match wallet.decrypt_seed(&password) {
-        Ok(seed) => {
-            println!("\n╔════════════════════════════════════════════════╗");
-            println!("║              🔐 SEED PHRASE 🔐                 ║");
-            println!("╠════════════════════════════════════════════════╣");
-            println!("║                                                ║");
-            
-            let words: Vec<&str> = seed.split_whitespace().collect();
-            for (i, word) in words.iter().enumerate() {
-                if i % 4 == 0 {
-                    print!("║  ");
-                }
-                print!("{:2}. {:<10} ", i + 1, word);
-                if (i + 1) % 4 == 0 {
-                    println!("║");
-                }
+    let mut attempts = 1;
+    let (password, seed) = loop {
+        let password = rpassword::prompt_password("Password: ").unwrap();
+        match wallet.decrypt_seed(&password) {
+            Ok(seed) => break (password, seed),
+            Err(WalletError::WrongPassword) if attempts < 3 => {
+                attempts += 1;
+                println!("❌ Wrong password, try again:");
+            }
+            Err(e) => {
+                println!("❌ Error: {}", e);
+                return;
             }
-            
-            println!("║                                                ║");
-            println!("╚════════════════════════════════════════════════╝\n");
         }
-        Err(e) => println!("❌ Error: {}", e),
+    };
+
+    if wallet.needs_migration() {
+        match wallet.migrate(&password).and_then(|_| wallet.save("wallets")) {
+            Ok(_) => println!("✅ Wallet upgraded to format {}", wallet.version),
+            Err(e) => println!("⚠️  Wallet upgrade skipped: {}", e),
+        }
+    }
+
+    println!("\n╔════════════════════════════════════════════════╗");
+    println!("║              🔐 SEED PHRASE 🔐                 ║");
+    println!("╠════════════════════════════════════════════════╣");
+    println!("║                                                ║");
+    
+    let words: Vec<&str> = seed.split_whitespace().collect();
+    for (i, word) in words.iter().enumerate() {
+        if i % 4 == 0 {
+            print!("║  ");
+        }
+        print!("{:2}. {:<10} ", i + 1, word);
+        if (i + 1) % 4 == 0 {
+            println!("║");
+        }
     }
+    
+    println!("║                                                ║");
+    println!("╚════════════════════════════════════════════════╝\n");
 }
 
 fn verify_wallet(name: &str) {
@@ -198,3 +239,50 @@ fn verify_wallet(name: &str) {
         println!("⚠️  DO NOT USE THIS WALLET!");
     }
 }
+
+fn add_contact(name: &str, label: &str, address: &str) {
+    let filename = format!("wallets/{}.secure.wallet", name);
+    let mut wallet = match SecureWallet::load(&filename) {
+        Ok(w) => w,
+        Err(e) => {
+            println!("❌ Error: {}", e);
+            return;
+        }
+    };
+
+    let password = rpassword::prompt_password("Password: ").unwrap();
+    match wallet.add_contact(label, address, &password).and_then(|_| wallet.save("wallets")) {
+        Ok(_) => println!("✅ Contact {} saved: {}", label, address),
+        Err(e) => println!("❌ Error: {}", e),
+    }
+}
+
+fn list_contacts(name: &str) {
+    let filename = format!("wallets/{}.secure.wallet", name);
+    let wallet = match SecureWallet::load(&filename) {
+        Ok(w) => w,
+        Err(e) => {
+            println!("❌ Error: {}", e);
+            return;
+        }
+    };
+
+    let password = rpassword::prompt_password("Password: ").unwrap();
+    let book = match wallet.address_book(&password) {
+        Ok(book) => book,
+        Err(e) => {
+            println!("❌ Error: {}", e);
+            return;
+        }
+    };
+
+    if book.contacts.is_empty() {
+        println!("📒 No contacts yet");
+    }
+    for (label, address) in &book.contacts {
+        println!("📒 {:<20} {}", label, address);
+    }
+    for (txid, label) in &book.tx_labels {
+        println!("🏷️  {} {}", display::truncate(txid, 16), label);
+    }
+}
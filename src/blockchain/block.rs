@@ -1,8 +1,13 @@
+use bincode::Options;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use hex;
 use chrono::Utc;
 use std::time::Instant;
+use crate::blockchain::sighash::{SigHashBase, SigHashType};
+use crate::mining::pow::{PowAlgorithm, ProofOfWork};
+use crate::utils::config::ConsensusParams;
+use crate::wallet::keys::KeyPair;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Block {
@@ -15,8 +20,37 @@ pub struct Block {
     pub difficulty: u32,
     pub miner_address: String,
     pub merkle_root: String,
+    /// Cible numérique en bits (les blocs sans cette valeur gardent la difficulté hexadécimale)
+    #[serde(default)]
+    pub target_bits: Option<u32>,
+    /// Fonction de hash de la preuve de travail (double SHA-256 pour les blocs existants)
+    #[serde(default)]
+    pub pow_algorithm: PowAlgorithm,
 }
 
+/// En-tête d'un bloc sans ses transactions, suffisant pour vérifier la preuve de travail.
+/// `miner_address` en fait partie car il entre dans le hash, et `pow_algorithm` choisit la
+/// fonction qui le calcule. `difficulty` et `target_bits` n'entrent pas dans le hash : ils
+/// doivent égaler ceux du consensus (voir `verify_consensus_pow`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlockHeader {
+    pub index: u64,
+    pub timestamp: i64,
+    pub previous_hash: String,
+    pub merkle_root: String,
+    pub nonce: u64,
+    pub difficulty: u32,
+    pub miner_address: String,
+    #[serde(default)]
+    pub target_bits: Option<u32>,
+    #[serde(default)]
+    pub pow_algorithm: PowAlgorithm,
+    pub hash: String,
+}
+
+/// En dessous, `Transaction::locktime` est une hauteur de bloc ; au-delà, un timestamp Unix
+pub const LOCKTIME_THRESHOLD: u64 = 500_000_000;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Transaction {
     pub id: String,
@@ -24,6 +58,10 @@ pub struct Transaction {
     pub outputs: Vec<TxOutput>,
     pub timestamp: i64,
     pub signature: String,
+    /// Hauteur ou timestamp (voir `LOCKTIME_THRESHOLD`) avant lequel la transaction ne peut
+    /// pas entrer dans un bloc ; 0 pour aucun verrou
+    #[serde(default)]
+    pub locktime: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -60,27 +98,49 @@ impl Block {
             difficulty,
             miner_address,
             merkle_root,
+            target_bits: None,
+            pow_algorithm: PowAlgorithm::default(),
         }
     }
 
     pub fn calculate_hash(&self) -> String {
-        let data = format!(
-            "{}{}{}{}{}{}",
-            self.index,
-            self.timestamp,
-            self.merkle_root,
-            self.previous_hash,
-            self.nonce,
-            self.miner_address
-        );
-        
-        let hash1 = Sha256::digest(data.as_bytes());
-        let hash2 = Sha256::digest(&hash1);
-        hex::encode(hash2)
+        self.pow_algorithm.block_hash(
+            header_hash(self.index, self.timestamp, &self.merkle_root, &self.previous_hash, self.nonce, &self.miner_address)
+        )
+    }
+
+    /// Vérifier le hash contre la cible numérique si présente, sinon contre les zéros de tête
+    pub fn meets_difficulty(&self) -> bool {
+        meets_difficulty(&self.hash, self.difficulty, self.target_bits)
+    }
+
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
+            index: self.index,
+            timestamp: self.timestamp,
+            previous_hash: self.previous_hash.clone(),
+            merkle_root: self.merkle_root.clone(),
+            nonce: self.nonce,
+            difficulty: self.difficulty,
+            miner_address: self.miner_address.clone(),
+            target_bits: self.target_bits,
+            pow_algorithm: self.pow_algorithm,
+            hash: self.hash.clone(),
+        }
+    }
+
+    /// Voir `BlockHeader::verify_consensus_pow`
+    pub fn verify_consensus_pow(&self, params: &ConsensusParams) -> anyhow::Result<()> {
+        self.header().verify_consensus_pow(params)
+    }
+
+    /// Le corps reçu correspond à un en-tête déjà validé : même en-tête et
+    /// racine de Merkle recalculée à partir des transactions
+    pub fn matches_header(&self, header: &BlockHeader) -> bool {
+        self.header() == *header && Self::calculate_merkle_root(&self.transactions) == header.merkle_root
     }
 
     pub fn mine(&mut self) {
-        let target = "0".repeat(self.difficulty as usize);
         let start = Instant::now();
         
         println!("⛏️  Mining block {} (difficulty {})...", self.index, self.difficulty);
@@ -88,7 +148,7 @@ impl Block {
         loop {
             self.hash = self.calculate_hash();
             
-            if self.hash.starts_with(&target) {
+            if self.meets_difficulty() {
                 let duration = start.elapsed();
                 println!("✅ Block {} mined in {}s!", self.index, duration.as_secs());
                 println!("   Hash: {}", self.hash);
@@ -113,8 +173,7 @@ impl Block {
             return false;
         }
 
-        let target = "0".repeat(self.difficulty as usize);
-        if !self.hash.starts_with(&target) {
+        if !self.meets_difficulty() {
             return false;
         }
 
@@ -126,40 +185,226 @@ impl Block {
             return "0".repeat(64);
         }
 
-        let mut hashes: Vec<String> = transactions
-            .iter()
-            .map(|tx| {
-                let data = serde_json::to_string(tx).unwrap();
-                let hash = Sha256::digest(data.as_bytes());
-                hex::encode(hash)
-            })
-            .collect();
+        let mut hashes: Vec<String> = transactions.iter().map(Self::merkle_leaf).collect();
 
         while hashes.len() > 1 {
-            let mut new_hashes = Vec::new();
+            hashes = hashes
+                .chunks(2)
+                .map(|chunk| Self::merkle_parent(&chunk[0], chunk.get(1).unwrap_or(&chunk[0])))
+                .collect();
+        }
 
-            for chunk in hashes.chunks(2) {
-                let combined = if chunk.len() == 2 {
-                    format!("{}{}", chunk[0], chunk[1])
-                } else {
-                    format!("{}{}", chunk[0], chunk[0])
-                };
+        hashes[0].clone()
+    }
 
-                let hash = Sha256::digest(combined.as_bytes());
-                new_hashes.push(hex::encode(hash));
-            }
+    /// Feuille de l'arbre de Merkle pour une transaction (encodage canonique complet)
+    pub fn merkle_leaf(tx: &Transaction) -> String {
+        hex::encode(Sha256::digest(tx.canonical_bytes()))
+    }
+
+    fn merkle_parent(left: &str, right: &str) -> String {
+        hex::encode(Sha256::digest(format!("{}{}", left, right).as_bytes()))
+    }
+
+    /// Branche de Merkle de la transaction `index` : hashes frères, de la feuille vers la racine
+    pub fn merkle_branch(transactions: &[Transaction], index: usize) -> Vec<String> {
+        let mut branch = Vec::new();
+        let mut hashes: Vec<String> = transactions.iter().map(Self::merkle_leaf).collect();
+        let mut position = index;
 
-            hashes = new_hashes;
+        while hashes.len() > 1 {
+            // Un nœud sans frère est apparié avec lui-même, comme dans `calculate_merkle_root`
+            let sibling = hashes.get(position ^ 1).unwrap_or(&hashes[position]);
+            branch.push(sibling.clone());
+
+            hashes = hashes
+                .chunks(2)
+                .map(|chunk| Self::merkle_parent(&chunk[0], chunk.get(1).unwrap_or(&chunk[0])))
+                .collect();
+            position /= 2;
         }
 
-        hashes[0].clone()
+        branch
+    }
+
+    /// Vérifier qu'une feuille en position `index` remonte jusqu'à `merkle_root` via `branch`
+    pub fn verify_merkle_branch(leaf: &str, index: usize, branch: &[String], merkle_root: &str) -> bool {
+        let mut hash = leaf.to_string();
+        let mut position = index;
+
+        for sibling in branch {
+            hash = if position.is_multiple_of(2) {
+                Self::merkle_parent(&hash, sibling)
+            } else {
+                Self::merkle_parent(sibling, &hash)
+            };
+            position /= 2;
+        }
+
+        hash == merkle_root
+    }
+}
+
+/// Forme compacte d'un bloc pour le stockage : le hash, la racine de Merkle et les ids
+/// de transactions sont omis et recalculés au chargement
+#[derive(Serialize, Deserialize)]
+struct CompactBlock {
+    index: u64,
+    timestamp: i64,
+    transactions: Vec<CompactTransaction>,
+    previous_hash: String,
+    nonce: u64,
+    difficulty: u32,
+    miner_address: String,
+    target_bits: Option<u32>,
+    pow_algorithm: PowAlgorithm,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompactTransaction {
+    /// Présent seulement si l'id n'est pas recalculable (l'id littéral du genesis)
+    id: Option<String>,
+    inputs: Vec<TxInput>,
+    outputs: Vec<TxOutput>,
+    timestamp: i64,
+    signature: String,
+    locktime: u64,
+}
+
+impl Block {
+    /// Encodage compact (bincode) sans les champs recalculables
+    pub fn to_compact(&self) -> anyhow::Result<Vec<u8>> {
+        let compact = CompactBlock {
+            index: self.index,
+            timestamp: self.timestamp,
+            transactions: self.transactions.iter().map(|tx| CompactTransaction {
+                id: (tx.id != Transaction::calculate_id(&tx.inputs, &tx.outputs, tx.timestamp, tx.locktime))
+                    .then(|| tx.id.clone()),
+                inputs: tx.inputs.clone(),
+                outputs: tx.outputs.clone(),
+                timestamp: tx.timestamp,
+                signature: tx.signature.clone(),
+                locktime: tx.locktime,
+            }).collect(),
+            previous_hash: self.previous_hash.clone(),
+            nonce: self.nonce,
+            difficulty: self.difficulty,
+            miner_address: self.miner_address.clone(),
+            target_bits: self.target_bits,
+            pow_algorithm: self.pow_algorithm,
+        };
+        Ok(bincode::serialize(&compact)?)
+    }
+
+    /// Reconstruire un bloc depuis `to_compact` : ids, racine de Merkle et hash sont
+    /// recalculés, et le hash obtenu doit satisfaire la difficulté du bloc (un bloc
+    /// altéré sur disque ne retrouve pas sa preuve de travail)
+    pub fn from_compact(data: &[u8]) -> anyhow::Result<Block> {
+        // Longueurs annoncées bornées par la taille des données : pas d'allocation démesurée
+        let compact: CompactBlock = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .with_limit(data.len() as u64)
+            .deserialize(data)?;
+        let transactions: Vec<Transaction> = compact.transactions.into_iter().map(|tx| Transaction {
+            id: tx.id.unwrap_or_else(|| Transaction::calculate_id(&tx.inputs, &tx.outputs, tx.timestamp, tx.locktime)),
+            inputs: tx.inputs,
+            outputs: tx.outputs,
+            timestamp: tx.timestamp,
+            signature: tx.signature,
+            locktime: tx.locktime,
+        }).collect();
+
+        let mut block = Block {
+            index: compact.index,
+            timestamp: compact.timestamp,
+            merkle_root: Self::calculate_merkle_root(&transactions),
+            transactions,
+            previous_hash: compact.previous_hash,
+            hash: String::new(),
+            nonce: compact.nonce,
+            difficulty: compact.difficulty,
+            miner_address: compact.miner_address,
+            target_bits: compact.target_bits,
+            pow_algorithm: compact.pow_algorithm,
+        };
+        block.hash = block.calculate_hash();
+        if !block.meets_difficulty() {
+            anyhow::bail!("Compact block {}: recomputed hash {} does not meet its difficulty", block.index, block.hash);
+        }
+        Ok(block)
+    }
+}
+
+impl BlockHeader {
+    pub fn calculate_hash(&self) -> String {
+        self.pow_algorithm.block_hash(
+            header_hash(self.index, self.timestamp, &self.merkle_root, &self.previous_hash, self.nonce, &self.miner_address)
+        )
+    }
+
+    pub fn meets_difficulty(&self) -> bool {
+        meets_difficulty(&self.hash, self.difficulty, self.target_bits)
+    }
+
+    /// Preuve de travail contrôlée contre la difficulté exigée par le consensus et non
+    /// contre celle que l'en-tête annonce : la difficulté déclarée doit seulement lui être
+    /// égale, et c'est le hash recalculé qui est comparé à la cible du consensus.
+    /// La difficulté d'un réseau est fixe (voir `ConsensusParams::required_difficulty`).
+    pub fn verify_consensus_pow(&self, params: &ConsensusParams) -> anyhow::Result<()> {
+        if self.pow_algorithm != params.pow_algorithm {
+            anyhow::bail!(
+                "Block {} uses {:?} proof of work, network expects {:?}",
+                self.index, self.pow_algorithm, params.pow_algorithm
+            );
+        }
+
+        let (difficulty, target_bits) = params.required_difficulty();
+        if (self.difficulty, self.target_bits) != (difficulty, target_bits) {
+            anyhow::bail!(
+                "Block {} difficulty {} (target {:?}) does not match required {} (target {:?})",
+                self.index, self.difficulty, self.target_bits, difficulty, target_bits
+            );
+        }
+
+        let hash = self.calculate_hash();
+        if hash != self.hash {
+            anyhow::bail!("Block {} hash mismatch", self.index);
+        }
+        if !meets_difficulty(&hash, difficulty, target_bits) {
+            anyhow::bail!("Block {} hash does not meet the consensus difficulty", self.index);
+        }
+        Ok(())
+    }
+
+    /// Même règles que `Block::is_valid`, sans les transactions
+    pub fn is_valid(&self, previous: &BlockHeader) -> bool {
+        self.index == previous.index + 1
+            && self.previous_hash == previous.hash
+            && self.hash == self.calculate_hash()
+            && self.meets_difficulty()
+    }
+}
+
+fn header_hash(index: u64, timestamp: i64, merkle_root: &str, previous_hash: &str, nonce: u64, miner_address: &str) -> String {
+    let data = format!("{}{}{}{}{}{}", index, timestamp, merkle_root, previous_hash, nonce, miner_address);
+
+    let hash1 = Sha256::digest(data.as_bytes());
+    let hash2 = Sha256::digest(hash1);
+    hex::encode(hash2)
+}
+
+fn meets_difficulty(hash: &str, difficulty: u32, target_bits: Option<u32>) -> bool {
+    match target_bits {
+        Some(bits) => ProofOfWork::meets_target(hash, &ProofOfWork::difficulty_to_target(bits)),
+        None => hash.starts_with(&"0".repeat(difficulty as usize)),
     }
 }
 
 impl Transaction {
     pub fn new(inputs: Vec<TxInput>, outputs: Vec<TxOutput>) -> Self {
         let timestamp = Utc::now().timestamp();
-        let id = Self::calculate_id(&inputs, &outputs, timestamp);
+        let id = Self::calculate_id(&inputs, &outputs, timestamp, 0);
 
         Transaction {
             id,
@@ -167,24 +412,273 @@ impl Transaction {
             outputs,
             timestamp,
             signature: String::new(),
+            locktime: 0,
+        }
+    }
+
+    /// Verrouiller la transaction jusqu'à `locktime` (l'id est recalculé ; à faire avant de signer)
+    pub fn with_locktime(mut self, locktime: u64) -> Self {
+        self.locktime = locktime;
+        self.id = Self::calculate_id(&self.inputs, &self.outputs, self.timestamp, locktime);
+        self
+    }
+
+    /// La transaction peut entrer dans un bloc de hauteur `height` et de timestamp `timestamp`
+    pub fn is_final(&self, height: u64, timestamp: i64) -> bool {
+        match self.locktime {
+            0 => true,
+            locktime if locktime < LOCKTIME_THRESHOLD => height >= locktime,
+            locktime => timestamp >= 0 && timestamp as u64 >= locktime,
+        }
+    }
+
+    /// Transaction coinbase (sans inputs) créditant la récompense du mineur
+    pub fn coinbase(miner_address: String, value: u64, timestamp: i64) -> Self {
+        let outputs = vec![TxOutput {
+            value,
+            address: miner_address,
+        }];
+        let id = Self::calculate_id(&[], &outputs, timestamp, 0);
+
+        Transaction {
+            id,
+            inputs: vec![],
+            outputs,
+            timestamp,
+            signature: String::new(),
+            locktime: 0,
+        }
+    }
+
+    /// Id : SHA-256 de l'encodage canonique des inputs (outpoints seulement), outputs, timestamp
+    /// et locktime. Signatures et clés publiques en sont exclues : signer ou ré-encoder une
+    /// signature ne change pas l'id (pas de malléabilité). Le genesis garde son id littéral "genesis".
+    fn calculate_id(inputs: &[TxInput], outputs: &[TxOutput], timestamp: i64, locktime: u64) -> String {
+        let mut data = Vec::new();
+        Self::encode_body(&mut data, inputs, outputs, timestamp, locktime);
+        hex::encode(Sha256::digest(&data))
+    }
+
+    /// Vrai si l'id correspond au contenu de la transaction
+    pub fn has_valid_id(&self) -> bool {
+        self.id == Self::calculate_id(&self.inputs, &self.outputs, self.timestamp, self.locktime)
+    }
+
+    /// Encodage canonique champ par champ : entiers little-endian, chaînes et listes
+    /// préfixées par leur longueur (`u32`). Le locktime n'est ajouté que s'il est non nul,
+    /// ce qui laisse inchangés les ids des transactions sans verrou.
+    fn encode_body(data: &mut Vec<u8>, inputs: &[TxInput], outputs: &[TxOutput], timestamp: i64, locktime: u64) {
+        data.extend_from_slice(&(inputs.len() as u32).to_le_bytes());
+        for input in inputs {
+            encode_str(data, &input.prev_tx_id);
+            data.extend_from_slice(&(input.output_index as u64).to_le_bytes());
+        }
+
+        data.extend_from_slice(&(outputs.len() as u32).to_le_bytes());
+        for output in outputs {
+            data.extend_from_slice(&output.value.to_le_bytes());
+            encode_str(data, &output.address);
+        }
+
+        data.extend_from_slice(&timestamp.to_le_bytes());
+        if locktime != 0 {
+            data.extend_from_slice(&locktime.to_le_bytes());
         }
     }
 
-    fn calculate_id(inputs: &[TxInput], outputs: &[TxOutput], timestamp: i64) -> String {
-        let data = format!("{:?}{:?}{}", inputs, outputs, timestamp);
-        let hash = Sha256::digest(data.as_bytes());
-        hex::encode(hash)
+    /// Encodage canonique de la transaction complète (id, corps, signatures), utilisé pour le Merkle
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        encode_str(&mut data, &self.id);
+        Self::encode_body(&mut data, &self.inputs, &self.outputs, self.timestamp, self.locktime);
+        for input in &self.inputs {
+            encode_str(&mut data, &input.signature);
+            encode_str(&mut data, &input.public_key);
+        }
+        encode_str(&mut data, &self.signature);
+        data
     }
 
-    pub fn total_input(&self) -> u64 {
-        self.inputs.iter().map(|_| 0).sum()
+    /// Somme des inputs résolus (voir `Blockchain::resolve_input`), ou `None` si un input
+    /// n'est pas résolvable ou en cas de dépassement
+    pub fn total_input<F>(&self, resolve_input: F) -> Option<u64>
+    where
+        F: Fn(&TxInput) -> Option<u64>,
+    {
+        self.inputs.iter().try_fold(0u64, |acc, input| acc.checked_add(resolve_input(input)?))
     }
 
+    /// Somme des outputs, saturée à `u64::MAX` (voir `checked_total_output` pour la validation)
     pub fn total_output(&self) -> u64 {
-        self.outputs.iter().map(|output| output.value).sum()
+        self.outputs.iter().fold(0u64, |acc, output| acc.saturating_add(output.value))
+    }
+
+    /// Somme des outputs, ou `None` en cas de dépassement
+    pub fn checked_total_output(&self) -> Option<u64> {
+        self.outputs.iter().try_fold(0u64, |acc, output| acc.checked_add(output.value))
+    }
+
+    /// Frais payés (inputs - outputs), ou `None` si un input n'est pas résolvable
+    /// ou si les outputs dépassent les inputs
+    pub fn fee_with<F>(&self, resolve_input: F) -> Option<u64>
+    where
+        F: Fn(&TxInput) -> Option<u64>,
+    {
+        self.total_input(resolve_input)?.checked_sub(self.checked_total_output()?)
+    }
+
+    /// Hash signé par l'input `input_index` : double SHA-256 de l'encodage canonique
+    /// (inputs sans signatures, outputs, timestamp, locktime) suivi de l'index de l'input (`u32` LE).
+    /// Seule définition, partagée par le signataire (`KeyPair`) et `SignatureValidator`.
+    pub fn signing_hash(&self, input_index: usize) -> [u8; 32] {
+        let mut data = Vec::new();
+        Self::encode_body(&mut data, &self.inputs, &self.outputs, self.timestamp, self.locktime);
+        data.extend_from_slice(&(input_index as u32).to_le_bytes());
+
+        Sha256::digest(Sha256::digest(&data)).into()
+    }
+
+    /// Hash signé par l'input `input_index` avec un type SIGHASH explicite : seuls les
+    /// inputs et outputs engagés par `sighash` sont encodés, l'octet de type est ajouté en fin.
+    /// Avec ANYONECANPAY, l'index de l'input n'est pas engagé (d'autres inputs peuvent le
+    /// précéder). `None` si l'input n'existe pas ou, pour SINGLE, sans output de même index.
+    pub fn signing_hash_with(&self, input_index: usize, sighash: SigHashType) -> Option<[u8; 32]> {
+        let input = self.inputs.get(input_index)?;
+        let inputs = if sighash.anyone_can_pay { std::slice::from_ref(input) } else { &self.inputs[..] };
+        let outputs = match sighash.base {
+            SigHashBase::All => &self.outputs[..],
+            SigHashBase::None => &[],
+            SigHashBase::Single => std::slice::from_ref(self.outputs.get(input_index)?),
+        };
+
+        let mut data = Vec::new();
+        Self::encode_body(&mut data, inputs, outputs, self.timestamp, self.locktime);
+        if !sighash.anyone_can_pay {
+            data.extend_from_slice(&(input_index as u32).to_le_bytes());
+        }
+        data.push(sighash.to_byte());
+
+        Some(Sha256::digest(Sha256::digest(&data)).into())
+    }
+
+    /// Signer l'input `index` avec la paire de clés (voir `KeyPair::sign_transaction_input`)
+    pub fn sign_input(&mut self, index: usize, keypair: &KeyPair) -> anyhow::Result<()> {
+        keypair.sign_transaction_input(self, index).map_err(|e| anyhow::anyhow!(e))
     }
 
     pub fn is_coinbase(&self) -> bool {
         self.inputs.is_empty()
     }
-}
\ No newline at end of file
+}
+
+fn encode_str(data: &mut Vec<u8>, value: &str) {
+    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    data.extend_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_transaction() -> Transaction {
+        let inputs = vec![TxInput {
+            prev_tx_id: "ab".repeat(32),
+            output_index: 1,
+            signature: String::new(),
+            public_key: String::new(),
+        }];
+        let outputs = vec![TxOutput { value: 12_5000_0000, address: "AUR1Bob".to_string() }];
+        let id = Transaction::calculate_id(&inputs, &outputs, 1_730_000_000, 0);
+        Transaction { id, inputs, outputs, timestamp: 1_730_000_000, signature: String::new(), locktime: 0 }
+    }
+
+    #[test]
+    fn test_txid_pinned() {
+        let tx = fixed_transaction();
+        assert_eq!(tx.id, "671bb594fd3b226d997add7436d0e9fd6246b43e8e025ea846e496f777f7000a");
+
+        let coinbase = Transaction::coinbase("AUR1Miner".to_string(), 50_0000_0000, 1_730_000_000);
+        assert_eq!(coinbase.id, "dd071b1e98b805558eaffb2a8a3e90c0091d1bd7f50359ed5aa9d82194e013d7");
+    }
+
+    #[test]
+    fn test_signatures_do_not_change_txid() {
+        let mut tx = fixed_transaction();
+        let before = Block::merkle_leaf(&tx);
+        tx.inputs[0].signature = "00".repeat(64);
+
+        assert_eq!(Transaction::calculate_id(&tx.inputs, &tx.outputs, tx.timestamp, tx.locktime), tx.id);
+        assert_ne!(Block::merkle_leaf(&tx), before);
+    }
+
+    #[test]
+    fn test_locktime_covered_by_id_and_signing_hash() {
+        let tx = fixed_transaction();
+        let locked = tx.clone().with_locktime(10);
+
+        assert_ne!(locked.id, tx.id);
+        assert_ne!(locked.signing_hash(0), tx.signing_hash(0));
+        assert!(!locked.is_final(9, i64::MAX));
+        assert!(locked.is_final(10, 0));
+
+        // Au-delà du seuil, le locktime est un timestamp
+        let timelocked = tx.with_locktime(1_800_000_000);
+        assert!(!timelocked.is_final(u64::MAX, 1_799_999_999));
+        assert!(timelocked.is_final(0, 1_800_000_000));
+    }
+
+    #[test]
+    fn test_compact_round_trip_restores_omitted_fields() {
+        let mut genesis_like = fixed_transaction();
+        genesis_like.id = "genesis".to_string();
+        let mut signed = fixed_transaction().with_locktime(42);
+        signed.inputs[0].signature = "11".repeat(65);
+        signed.inputs[0].public_key = "02".repeat(33);
+        let transactions = vec![
+            Transaction::coinbase("AUR1Miner".to_string(), 50_0000_0000, 1_730_000_000),
+            signed,
+            genesis_like,
+        ];
+        let mut block = Block::new(7, transactions, "cd".repeat(32), 1, "AUR1Miner".to_string());
+        block.target_bits = Some(4);
+        block.mine();
+
+        let compact = block.to_compact().unwrap();
+        assert!(compact.len() < bincode::serialize(&block).unwrap().len());
+
+        let restored = Block::from_compact(&compact).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), serde_json::to_value(&block).unwrap());
+        assert_eq!(restored.transactions[2].id, "genesis");
+
+        // Un octet modifié sur disque ne reproduit pas la preuve de travail
+        let mut tampered = Block::new(7, vec![], "cd".repeat(32), 8, "AUR1Miner".to_string());
+        tampered.hash = tampered.calculate_hash();
+        assert!(Block::from_compact(&tampered.to_compact().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_consensus_pow_ignores_declared_difficulty() {
+        let params = ConsensusParams { difficulty: 3, ..ConsensusParams::default() };
+        let mut block = Block::new(1, vec![], "ab".repeat(32), 3, "AUR1Miner".to_string());
+        block.mine();
+        assert!(block.verify_consensus_pow(&params).is_ok());
+
+        // Bloc miné à une difficulté plus basse que celle du consensus : sa propre
+        // vérification passe, celle du consensus non
+        let mut easy = Block::new(1, vec![], "ab".repeat(32), 1, "AUR1Miner".to_string());
+        easy.mine();
+        while easy.hash.starts_with("000") {
+            easy.nonce += 1;
+            easy.mine();
+        }
+        assert!(easy.meets_difficulty());
+        let err = easy.verify_consensus_pow(&params).unwrap_err();
+        assert!(err.to_string().contains("does not match required 3"), "{}", err);
+
+        // Difficulté déclarée corrigée après coup : le hash ne suit pas
+        easy.difficulty = 3;
+        let err = easy.verify_consensus_pow(&params).unwrap_err();
+        assert!(err.to_string().contains("does not meet"), "{}", err);
+    }
+}
+
@@ -1,24 +1,634 @@
 use serde::{Deserialize, Serialize};
-use crate::blockchain::{Block, Transaction};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use crate::blockchain::{Block, Mempool, OrphanPool, Transaction, TxInput, TxOutput};
+use crate::blockchain::genesis::TOTAL_SUPPLY;
+use crate::security::{SecurityValidator, SignatureValidator};
+use crate::storage::encrypted::StoreCipher;
+use crate::utils::config::ConsensusParams;
+
+/// Nombre de blocs récents dont la médiane des timestamps borne le suivant (comme Bitcoin)
+pub const MEDIAN_TIME_SPAN: usize = 11;
+
+/// Nombre maximum de blocs renvoyés par `Blockchain::get_blocks_range` (RPC et P2P)
+pub const MAX_BLOCKS_PER_RANGE: u64 = 500;
+
+/// Blocs trouvés pour une plage demandée à `Blockchain::get_blocks_range`
+#[derive(Debug)]
+pub struct BlockRange<'a> {
+    pub blocks: &'a [Block],
+    /// Blocs demandés, après plafonnement à `MAX_BLOCKS_PER_RANGE`
+    pub requested: u64,
+    /// La plage demandée dépassait `MAX_BLOCKS_PER_RANGE` et a été tronquée
+    pub capped: bool,
+}
+
+impl BlockRange<'_> {
+    pub fn found(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Tous les blocs demandés (après plafonnement) existent
+    pub fn is_complete(&self) -> bool {
+        self.found() as u64 == self.requested
+    }
+}
+
+/// Output non dépensé, identifié par la transaction et l'index qui l'ont créé
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Utxo {
+    pub tx_id: String,
+    pub output_index: usize,
+    pub value: u64,
+    pub address: String,
+}
+
+/// Événements de sommet conservés pour un abonné en retard ; au-delà il en perd
+const TIP_CHANNEL_CAPACITY: usize = 64;
+
+/// Nouveau sommet de la chaîne, diffusé à chaque bloc ajouté (minage, synchronisation, réorganisation)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainTip {
+    pub index: u64,
+    pub hash: String,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Blockchain {
     pub chain: Vec<Block>,
     pub difficulty: usize,
+    #[serde(skip)]
+    pub mempool: Mempool,
+    #[serde(skip)]
+    pub params: ConsensusParams,
+    #[serde(skip)]
+    pub orphans: OrphanPool,
+    #[serde(skip, default = "tip_channel")]
+    tip_sender: broadcast::Sender<ChainTip>,
+    /// Clé de stockage : `save_to_file` chiffre la chaîne si elle est présente
+    /// (voir `encrypt_storage`, `load_from_file_encrypted`)
+    #[serde(skip)]
+    pub(crate) store_cipher: Option<Arc<StoreCipher>>,
+}
+
+fn tip_channel() -> broadcast::Sender<ChainTip> {
+    broadcast::channel(TIP_CHANNEL_CAPACITY).0
+}
+
+/// Sort d'un bloc reçu d'un peer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockOutcome {
+    /// Ajouté, avec le nombre total de blocs raccrochés (orphelins compris)
+    Connected(usize),
+    /// Parent inconnu : mis de côté en attendant celui-ci
+    Orphaned,
+    Rejected,
 }
 
 impl Blockchain {
     pub fn new() -> Self {
+        Self::with_params(ConsensusParams::default())
+    }
+
+    pub fn with_params(params: ConsensusParams) -> Self {
         Blockchain {
             chain: Vec::new(),
-            difficulty: 4,
+            difficulty: params.difficulty as usize,
+            mempool: Mempool::new(),
+            params,
+            orphans: OrphanPool::new(),
+            tip_sender: tip_channel(),
+            store_cipher: None,
         }
     }
 
+    /// S'abonner aux nouveaux sommets (voir `ChainTip`)
+    pub fn subscribe_tips(&self) -> broadcast::Receiver<ChainTip> {
+        self.tip_sender.subscribe()
+    }
+
+    pub fn tip_subscribers(&self) -> usize {
+        self.tip_sender.receiver_count()
+    }
+
+    /// Ajouter `block` au sommet et le signaler aux abonnés
+    fn push_tip(&mut self, block: Block) {
+        let tip = ChainTip { index: block.index, hash: block.hash.clone() };
+        self.evict_confirmed(&block);
+        self.chain.push(block);
+        // Aucun abonné : rien à signaler
+        let _ = self.tip_sender.send(tip);
+    }
+
+    /// Retirer du mempool les transactions confirmées par `block` et celles qui
+    /// dépensent un output qu'il dépense déjà
+    fn evict_confirmed(&mut self, block: &Block) {
+        let confirmed: HashSet<&str> = block.transactions.iter().map(|tx| tx.id.as_str()).collect();
+        let spent: HashSet<(&str, usize)> = block.transactions.iter()
+            .flat_map(|tx| tx.inputs.iter())
+            .map(|input| (input.prev_tx_id.as_str(), input.output_index))
+            .collect();
+        let stale: Vec<String> = self.mempool.entries().iter()
+            .filter(|entry| {
+                confirmed.contains(entry.tx.id.as_str())
+                    || entry.tx.inputs.iter().any(|input| spent.contains(&(input.prev_tx_id.as_str(), input.output_index)))
+            })
+            .map(|entry| entry.tx.id.clone())
+            .collect();
+        for txid in stale {
+            self.mempool.remove(&txid);
+        }
+    }
+
+    /// Assembler le prochain bloc (coinbase en tête) selon les paramètres de consensus, sans le miner.
+    /// La coinbase crédite la subvention plus `total_fees`.
+    pub fn create_block(
+        &self,
+        mut transactions: Vec<Transaction>,
+        total_fees: u64,
+        miner_address: String,
+        timestamp: i64,
+    ) -> Block {
+        let (index, previous_hash) = match self.get_latest_block() {
+            Some(latest) => (latest.index + 1, latest.hash.clone()),
+            None => (0, "0".repeat(64)),
+        };
+
+        let reward = self.params.capped_reward(index, self.total_issued()).saturating_add(total_fees);
+        transactions.insert(0, Transaction::coinbase(miner_address.clone(), reward, timestamp));
+
+        let mut block = Block::new(index, transactions, previous_hash, self.params.difficulty, miner_address);
+        block.timestamp = timestamp;
+        block.target_bits = self.params.target_bits;
+        block.pow_algorithm = self.params.pow_algorithm;
+        block
+    }
+
     pub fn get_latest_block(&self) -> Option<&Block> {
         self.chain.last()
     }
 
+    /// Médiane des timestamps des `MEDIAN_TIME_SPAN` derniers blocs ; le prochain bloc doit être postérieur
+    pub fn median_time_past(&self) -> i64 {
+        let mut timestamps: Vec<i64> = self.chain.iter()
+            .rev()
+            .take(MEDIAN_TIME_SPAN)
+            .map(|block| block.timestamp)
+            .collect();
+        if timestamps.is_empty() {
+            return 0;
+        }
+
+        timestamps.sort_unstable();
+        timestamps[timestamps.len() / 2]
+    }
+
+    /// Timestamp à donner au prochain bloc : maintenant, ou juste après le median-time-past
+    /// si plusieurs blocs ont été produits dans la même seconde
+    pub fn next_block_timestamp(&self) -> i64 {
+        chrono::Utc::now().timestamp().max(self.median_time_past().saturating_add(1))
+    }
+
+    pub fn get_block(&self, index: u64) -> Option<&Block> {
+        usize::try_from(index).ok().and_then(|i| self.chain.get(i))
+    }
+
+    pub fn get_block_by_hash(&self, hash: &str) -> Option<&Block> {
+        self.chain.iter().find(|block| block.hash == hash)
+    }
+
+    /// Transaction confirmée et hauteur du bloc qui la contient
+    pub fn get_transaction(&self, txid: &str) -> Option<(Transaction, u64)> {
+        self.chain.iter().find_map(|block| {
+            block.transactions.iter()
+                .find(|tx| tx.id == txid)
+                .map(|tx| (tx.clone(), block.index))
+        })
+    }
+
+    /// Blocs de `start` à `end` inclus, au plus `MAX_BLOCKS_PER_RANGE` à partir de `start`.
+    /// Les hauteurs au-delà du sommet sont absentes du résultat (voir `BlockRange::found`) ;
+    /// une plage inversée est refusée.
+    pub fn get_blocks_range(&self, start: u64, end: u64) -> anyhow::Result<BlockRange<'_>> {
+        if start > end {
+            anyhow::bail!("Invalid block range: start {} is after end {}", start, end);
+        }
+        let capped_end = end.min(start.saturating_add(MAX_BLOCKS_PER_RANGE - 1));
+
+        let len = self.chain.len();
+        let first = usize::try_from(start).unwrap_or(len).min(len);
+        let last = usize::try_from(capped_end).map_or(len, |end| end.saturating_add(1)).min(len);
+        Ok(BlockRange {
+            blocks: &self.chain[first..last.max(first)],
+            requested: capped_end - start + 1,
+            capped: capped_end < end,
+        })
+    }
+
+    /// Retrouver l'output référencé par un input dans la chaîne
+    pub fn find_output(&self, input: &TxInput) -> Option<&TxOutput> {
+        self.chain.iter()
+            .flat_map(|block| block.transactions.iter())
+            .find(|tx| tx.id == input.prev_tx_id)
+            .and_then(|tx| tx.outputs.get(input.output_index))
+    }
+
+    /// Retrouver la valeur de l'output référencé par un input dans la chaîne
+    pub fn resolve_input(&self, input: &TxInput) -> Option<u64> {
+        self.find_output(input).map(|output| output.value)
+    }
+
+    /// Hauteur du prochain bloc de la chaîne
+    fn next_height(&self) -> u64 {
+        self.get_latest_block().map_or(0, |latest| latest.index + 1)
+    }
+
+    /// Coinbases encore immatures pour une dépense incluse à `spend_height`.
+    /// Le genesis (premine) est dépensable immédiatement.
+    fn immature_coinbases(&self, spend_height: u64) -> HashSet<&str> {
+        self.chain.iter()
+            .filter(|block| block.index > 0
+                && spend_height.saturating_sub(block.index) < self.params.coinbase_maturity)
+            .filter_map(|block| block.transactions.first())
+            .filter(|tx| tx.is_coinbase())
+            .map(|tx| tx.id.as_str())
+            .collect()
+    }
+
+    /// Vrai si la transaction dépense une coinbase immature à `spend_height`
+    fn spends_immature_coinbase(&self, tx: &Transaction, spend_height: u64) -> bool {
+        let immature = self.immature_coinbases(spend_height);
+        tx.inputs.iter().any(|input| immature.contains(input.prev_tx_id.as_str()))
+    }
+
+    /// Vrai si la transaction peut entrer dans un bloc à `height` / `timestamp` :
+    /// coinbases dépensées arrivées à maturité et locktime atteint
+    fn can_include(&self, tx: &Transaction, height: u64, timestamp: i64) -> bool {
+        !self.spends_immature_coinbase(tx, height) && tx.is_final(height, timestamp)
+    }
+
+    /// Vérifier qu'une transaction est dépensable dans le prochain bloc :
+    /// locktime atteint, inputs connus, non dépensés, coinbases référencées arrivées à maturité,
+    /// signés par le propriétaire de chaque output dépensé, et outputs couverts par les inputs
+    /// (la différence est le frais).
+    pub fn validate_transaction(&self, tx: &Transaction) -> anyhow::Result<()> {
+        if !tx.is_final(self.next_height(), self.next_block_timestamp()) {
+            anyhow::bail!("Transaction {} is locked until {}", tx.id, tx.locktime);
+        }
+
+        let spent = self.spent_outputs();
+        let immature = self.immature_coinbases(self.next_height());
+
+        for input in &tx.inputs {
+            if self.find_output(input).is_none() {
+                anyhow::bail!("Unknown input {}:{}", input.prev_tx_id, input.output_index);
+            }
+            if spent.contains(&(input.prev_tx_id.as_str(), input.output_index)) {
+                anyhow::bail!("Input {}:{} already spent", input.prev_tx_id, input.output_index);
+            }
+            if immature.contains(input.prev_tx_id.as_str()) {
+                anyhow::bail!(
+                    "Input {} spends an immature coinbase ({} confirmations required)",
+                    input.prev_tx_id,
+                    self.params.coinbase_maturity
+                );
+            }
+        }
+        SignatureValidator::verify_transaction_spends(tx, |input| self.find_output(input), self.params.network)?;
+
+        let total_input = tx.total_input(|input| self.resolve_input(input)).unwrap_or(0);
+        let total_output = tx.checked_total_output()
+            .ok_or_else(|| anyhow::anyhow!("Transaction {} output sum overflows", tx.id))?;
+        if total_output > total_input {
+            anyhow::bail!(
+                "Transaction {} spends {} but its inputs only hold {}",
+                tx.id, total_output, total_input
+            );
+        }
+        Ok(())
+    }
+
+    /// Valider une transaction (voir `validate_transaction`) et l'ajouter au mempool ;
+    /// retourne les frais payés
+    pub fn accept_transaction(&mut self, tx: Transaction) -> anyhow::Result<u64> {
+        self.validate_transaction(&tx)?;
+        self.mempool.expire(self.mempool.max_age_secs);
+        let fee = tx.fee_with(|input| self.resolve_input(input)).unwrap_or(0);
+        self.mempool.add_transaction(tx, fee)?;
+        Ok(fee)
+    }
+
+    /// Conflits d'un bloc candidat avec l'ensemble des UTXO de la chaîne et avec lui-même :
+    /// un txid déjà confirmé ou répété dans le bloc ne peut pas être rejoué, ni un output
+    /// dépensé dans un bloc antérieur ou deux fois dans le bloc. Seule la première transaction
+    /// est une coinbase (sans inputs), et chaque id doit correspondre au contenu de sa transaction.
+    pub fn check_utxo_conflicts(&self, block: &Block) -> anyhow::Result<()> {
+        let confirmed: HashSet<&str> = self.chain.iter()
+            .flat_map(|block| block.transactions.iter())
+            .map(|tx| tx.id.as_str())
+            .collect();
+        let spent = self.spent_outputs();
+        let mut block_txids = HashSet::new();
+        let mut block_spent = HashSet::new();
+
+        for (position, tx) in block.transactions.iter().enumerate() {
+            if (position == 0) != tx.is_coinbase() {
+                anyhow::bail!("Transaction {} at position {}: only the first transaction may be a coinbase", tx.id, position);
+            }
+            if !tx.has_valid_id() {
+                anyhow::bail!("Transaction {} id does not match its content", tx.id);
+            }
+            if confirmed.contains(tx.id.as_str()) {
+                anyhow::bail!("Transaction {} already confirmed", tx.id);
+            }
+            if !block_txids.insert(tx.id.as_str()) {
+                anyhow::bail!("Transaction {} appears twice in the block", tx.id);
+            }
+            for input in &tx.inputs {
+                let outpoint = (input.prev_tx_id.as_str(), input.output_index);
+                if spent.contains(&outpoint) {
+                    anyhow::bail!("Input {}:{} already spent in an earlier block", input.prev_tx_id, input.output_index);
+                }
+                if !block_spent.insert(outpoint) {
+                    anyhow::bail!("Input {}:{} spent twice in the block", input.prev_tx_id, input.output_index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Outputs (tx_id, index) dépensés par une transaction de la chaîne
+    fn spent_outputs(&self) -> HashSet<(&str, usize)> {
+        self.chain.iter()
+            .flat_map(|block| block.transactions.iter())
+            .flat_map(|tx| tx.inputs.iter())
+            .map(|input| (input.prev_tx_id.as_str(), input.output_index))
+            .collect()
+    }
+
+    /// Outputs confirmés et dépensables d'une adresse : non dépensés par la chaîne,
+    /// coinbases immatures exclues
+    pub fn get_utxos(&self, address: &str) -> Vec<Utxo> {
+        let transactions = || self.chain.iter().flat_map(|block| block.transactions.iter());
+        let spent = self.spent_outputs();
+        let immature = self.immature_coinbases(self.next_height());
+
+        transactions()
+            .filter(|tx| !immature.contains(tx.id.as_str()))
+            .flat_map(|tx| {
+                tx.outputs.iter().enumerate().map(move |(index, output)| (tx, index, output))
+            })
+            .filter(|(tx, index, output)| {
+                output.address == address && !spent.contains(&(tx.id.as_str(), *index))
+            })
+            .map(|(tx, index, output)| Utxo {
+                tx_id: tx.id.clone(),
+                output_index: index,
+                value: output.value,
+                address: output.address.clone(),
+            })
+            .collect()
+    }
+
+    /// Ensemble complet des outputs non dépensés de la chaîne, coinbases immatures comprises,
+    /// dans l'ordre où ils ont été créés
+    pub fn utxo_set(&self) -> Vec<Utxo> {
+        let spent = self.spent_outputs();
+        self.chain.iter()
+            .flat_map(|block| block.transactions.iter())
+            .flat_map(|tx| {
+                tx.outputs.iter().enumerate().map(move |(index, output)| (tx, index, output))
+            })
+            .filter(|(tx, index, _)| !spent.contains(&(tx.id.as_str(), *index)))
+            .map(|(tx, index, output)| Utxo {
+                tx_id: tx.id.clone(),
+                output_index: index,
+                value: output.value,
+                address: output.address.clone(),
+            })
+            .collect()
+    }
+
+    /// Engagement sur l'ensemble des UTXO : SHA-256 de `utxo_set` trié par (txid, index)
+    /// et encodé de façon canonique. Deux nœuds au même état obtiennent le même hash,
+    /// ce qui permet de vérifier qu'un snapshot téléchargé correspond à la chaîne.
+    pub fn utxo_set_hash(&self) -> String {
+        utxo_commitment(self.utxo_set())
+    }
+
+    /// Restaurer le mempool sauvegardé en écartant les transactions devenues obsolètes :
+    /// déjà minées, inputs inconnus, déjà dépensés dans la chaîne ou par une autre transaction restaurée.
+    /// Retourne le nombre de transactions conservées.
+    pub fn load_mempool(&mut self, path: &str) -> anyhow::Result<usize> {
+        let saved = Mempool::load(path)?;
+
+        let mined: HashSet<&str> = self.chain.iter()
+            .flat_map(|block| block.transactions.iter())
+            .map(|tx| tx.id.as_str())
+            .collect();
+        let mut spent: HashSet<(String, usize)> = self.spent_outputs()
+            .into_iter()
+            .map(|(tx_id, index)| (tx_id.to_string(), index))
+            .collect();
+
+        let mut mempool = self.mempool.clone();
+        mempool.clear();
+        for entry in saved.entries() {
+            let tx = &entry.tx;
+            let stale = mined.contains(tx.id.as_str())
+                || tx.inputs.iter().any(|input| {
+                    self.find_output(input).is_none()
+                        || spent.contains(&(input.prev_tx_id.clone(), input.output_index))
+                });
+            if stale {
+                println!("🗑️  Dropping stale mempool tx {}", tx.id);
+                continue;
+            }
+
+            match mempool.add_transaction_at(tx.clone(), entry.fee, entry.added_at) {
+                Ok(()) => spent.extend(tx.inputs.iter().map(|input| (input.prev_tx_id.clone(), input.output_index))),
+                Err(e) => println!("🗑️  Dropping mempool tx {}: {}", tx.id, e),
+            }
+        }
+
+        mempool.expire(mempool.max_age_secs);
+        let kept = mempool.len();
+        self.mempool = mempool;
+        Ok(kept)
+    }
+
+    /// Vrai si la transaction dépense au moins un output confirmé de `address`
+    fn is_sent_by(&self, tx: &Transaction, address: &str) -> bool {
+        tx.inputs.iter()
+            .filter_map(|input| self.find_output(input))
+            .any(|output| output.address == address)
+    }
+
+    /// Transactions envoyées par `address` : (confirmées, en attente dans le mempool).
+    /// Sert de numéro de séquence aux wallets ; il n'est pas imposé par le consensus,
+    /// le rejeu étant déjà empêché par la dépense des UTXO.
+    pub fn sent_transaction_counts(&self, address: &str) -> (u64, u64) {
+        let confirmed = self.chain.iter()
+            .flat_map(|block| block.transactions.iter())
+            .filter(|tx| !tx.is_coinbase() && self.is_sent_by(tx, address))
+            .count();
+        let pending = self.mempool.entries().iter()
+            .filter(|entry| self.is_sent_by(&entry.tx, address))
+            .count();
+        (confirmed as u64, pending as u64)
+    }
+
+    /// Variation de solde d'une adresse si les transactions du mempool étaient confirmées
+    pub fn get_pending_balance_change(&self, address: &str) -> i64 {
+        self.mempool.entries().iter()
+            .map(|entry| {
+                let received: i128 = entry.tx.outputs.iter()
+                    .filter(|output| output.address == address)
+                    .map(|output| output.value as i128)
+                    .sum();
+                let spent: i128 = entry.tx.inputs.iter()
+                    .filter_map(|input| self.find_output(input))
+                    .filter(|output| output.address == address)
+                    .map(|output| output.value as i128)
+                    .sum();
+                received - spent
+            })
+            .sum::<i128>()
+            .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// Prochain bloc à miner avec les transactions du mempool ; la coinbase réclame la subvention et les frais.
+    /// Une transaction dont les inputs ne sont pas encore résolvables est incluse sans réclamer ses frais.
+    /// Celles qui dépensent une coinbase immature ou dont le locktime n'est pas atteint sont laissées de côté.
+    /// Transactions par taux de frais décroissant ; nonce à 0, le bloc reste à miner.
+    pub fn block_template(&self, miner_address: String) -> Block {
+        let height = self.next_height();
+        let timestamp = self.next_block_timestamp();
+        let transactions: Vec<Transaction> = self.mempool.entries_by_fee_rate().into_iter()
+            .filter(|entry| self.can_include(&entry.tx, height, timestamp))
+            .map(|entry| entry.tx.clone())
+            .collect();
+        let total_fees = transactions.iter()
+            .map(|tx| match tx.fee_with(|input| self.resolve_input(input)) {
+                Some(fee) => fee,
+                None => {
+                    println!("⚠️  Unresolved inputs for tx {}, fee not claimed", tx.id);
+                    0
+                }
+            })
+            .fold(0u64, |acc, fee| acc.saturating_add(fee));
+
+        self.create_block(transactions, total_fees, miner_address, timestamp)
+    }
+
+    /// Miner `block_template` et l'ajouter ; les transactions qui dépensent une coinbase
+    /// immature ou dont le locktime n'est pas atteint restent dans le mempool
+    pub fn mine_pending_transactions(&mut self, miner_address: String) {
+        let mut block = self.block_template(miner_address);
+        let waiting: Vec<_> = self.mempool.entries().iter()
+            .filter(|entry| !self.can_include(&entry.tx, block.index, block.timestamp))
+            .cloned()
+            .collect();
+
+        block.mine();
+
+        self.push_tip(block);
+        self.mempool.clear();
+        for entry in waiting {
+            println!("⏳ Tx {} waits for coinbase maturity or its locktime", entry.tx.id);
+            let _ = self.mempool.add_transaction_at(entry.tx, entry.fee, entry.added_at);
+        }
+    }
+
+    /// Ajouter un bloc miné sur un instantané de la chaîne, seulement si le sommet n'a pas
+    /// changé entre-temps. Sinon le bloc, construit sur un parent obsolète, est écarté.
+    pub fn append_if_tip(&mut self, block: Block) -> bool {
+        let tip = self.get_latest_block().map(|latest| (latest.index + 1, latest.hash.as_str()));
+        if tip != Some((block.index, block.previous_hash.as_str())) {
+            return false;
+        }
+
+        self.push_tip(block);
+        true
+    }
+
+    /// Valider `block` et l'ajouter au sommet en le signalant aux abonnés ; faux s'il est refusé
+    pub(crate) fn connect_block(&mut self, block: Block) -> bool {
+        if !self.validate_new_block(&block) {
+            return false;
+        }
+        self.push_tip(block);
+        true
+    }
+
+    /// Traiter un bloc reçu du réseau. Un bloc dont le parent est absent de la chaîne est
+    /// conservé dans le pool d'orphelins ; chaque bloc accepté y raccroche ses descendants.
+    pub fn receive_block(&mut self, block: Block) -> BlockOutcome {
+        if self.validate_new_block(&block) {
+            let mut connected = 0;
+            let mut next = Some(block);
+            while let Some(block) = next.take() {
+                let hash = block.hash.clone();
+                self.push_tip(block);
+                connected += 1;
+                // Un seul enfant prolonge le sommet ; ses frères restent en attente dans le pool
+                for child in self.orphans.take_children(&hash) {
+                    if next.is_none() && self.validate_new_block(&child) {
+                        next = Some(child);
+                    } else {
+                        self.orphans.add(child);
+                    }
+                }
+            }
+            return BlockOutcome::Connected(connected);
+        }
+
+        let parent_known = self.chain.iter().any(|b| b.hash == block.previous_hash);
+        // Sans parent, seule la preuve de travail peut être vérifiée : elle borne le coût du pool
+        if !parent_known && block.verify_consensus_pow(&self.params).is_ok() && self.orphans.add(block) {
+            return BlockOutcome::Orphaned;
+        }
+        BlockOutcome::Rejected
+    }
+
+    /// Remplacer les blocs après le point de fork par `fork`, s'il est plus long et que
+    /// `validator` accepte sa profondeur. Les transactions des blocs annulés retournent
+    /// au mempool quand elles restent valides. Retourne le nombre de blocs annulés.
+    pub fn reorganize(&mut self, fork: Vec<Block>, validator: &crate::security::SecurityValidator) -> anyhow::Result<u64> {
+        let depth = validator.validate_reorg(self, &fork)?;
+        let tip = self.next_height().saturating_sub(1);
+        if fork.last().is_none_or(|last| last.index <= tip) {
+            return Err(anyhow::anyhow!("Fork is not longer than the current chain"));
+        }
+
+        let mut candidate = self.clone();
+        let disconnected = candidate.chain.split_off((tip - depth + 1) as usize);
+        for block in fork {
+            let index = block.index;
+            candidate
+                .check_new_block(&block)
+                .map_err(|e| anyhow::anyhow!("Fork block {} rejected: {}", index, e))?;
+            candidate.chain.push(block);
+        }
+
+        self.chain = candidate.chain;
+        if let Some(tip) = self.get_latest_block() {
+            let _ = self.tip_sender.send(ChainTip { index: tip.index, hash: tip.hash.clone() });
+        }
+        for block in &self.chain[(tip - depth + 1) as usize..] {
+            for tx in &block.transactions {
+                self.mempool.remove(&tx.id);
+            }
+        }
+        for tx in disconnected.into_iter().flat_map(|block| block.transactions).filter(|tx| !tx.is_coinbase()) {
+            let _ = self.accept_transaction(tx);
+        }
+
+        Ok(depth)
+    }
+
     pub fn add_block(&mut self, mut block: Block) {
         if let Some(latest_block) = self.get_latest_block() {
             block.previous_hash = latest_block.hash.clone();
@@ -26,7 +636,7 @@ impl Blockchain {
         }
         
         block.mine();
-        self.chain.push(block);
+        self.push_tip(block);
     }
 
     pub fn is_chain_valid(&self) -> bool {
@@ -45,60 +655,1077 @@ impl Blockchain {
         true
     }
 
+    /// Solde confirmé : somme des outputs non dépensés de l'adresse
     pub fn get_balance(&self, address: &str) -> u64 {
-        self.chain.iter()
-            .filter(|block| block.miner_address == address)
-            .count() as u64 * 50
+        self.get_utxos(address).iter().fold(0u64, |acc, utxo| acc.saturating_add(utxo.value))
     }
 
     pub fn get_chain_length(&self) -> usize {
         self.chain.len()
     }
 
+    /// Offre émise : somme des outputs coinbase, plafonnée à `TOTAL_SUPPLY`
     pub fn get_total_supply(&self) -> u64 {
-        self.chain.len() as u64 * 50
+        self.chain.iter()
+            .filter_map(|block| block.transactions.first())
+            .filter(|tx| tx.is_coinbase())
+            .flat_map(|tx| tx.outputs.iter())
+            .try_fold(0u64, |acc, output| acc.checked_add(output.value))
+            .map_or(TOTAL_SUPPLY, |supply| supply.min(TOTAL_SUPPLY))
+    }
+
+    /// Monnaie émise : premine du genesis plus la part subvention de chaque coinbase
+    /// (l'excédent correspond aux frais, qui ne créent pas de monnaie), bornée par `max_supply`
+    pub fn total_issued(&self) -> u64 {
+        self.chain.iter().fold(0u64, |issued, block| {
+            let minted = block.transactions.first()
+                .filter(|tx| tx.is_coinbase())
+                .map_or(0, |tx| tx.total_output());
+            let minted = if block.index == 0 {
+                minted
+            } else {
+                minted.min(self.params.capped_reward(block.index, issued))
+            };
+            issued.saturating_add(minted).min(self.params.max_supply)
+        })
     }
 
     pub fn get_difficulty(&self) -> usize {
         self.difficulty
     }
 
+    /// Vrai si `block` peut être ajouté au sommet (voir `check_new_block`)
     pub fn validate_new_block(&self, block: &Block) -> bool {
-        if let Some(latest_block) = self.get_latest_block() {
-            if block.index != latest_block.index + 1 {
-                return false;
-            }
-            
-            if block.previous_hash != latest_block.hash {
-                return false;
+        self.check_new_block(block).is_ok()
+    }
+
+    /// Seule règle de validité d'un bloc candidat au sommet, quelle que soit sa provenance
+    /// (P2P, RPC, synchronisation, réorganisation). Les règles de consensus de
+    /// `SecurityValidator` (taille, nombre de transactions, timestamp, adresses, récompense,
+    /// preuve de travail...) s'y ajoutent à celles qui demandent l'état de la chaîne :
+    /// maturité des coinbases, inputs connus et signatures. L'erreur donne la raison du refus.
+    pub fn check_new_block(&self, block: &Block) -> anyhow::Result<()> {
+        // Chaîne vide : seul un genesis cohérent peut l'amorcer (comparé à la configuration au chargement)
+        if self.chain.is_empty() {
+            if block.index != 0 || block.hash != block.calculate_hash()
+                || Block::calculate_merkle_root(&block.transactions) != block.merkle_root
+            {
+                anyhow::bail!("Block {} cannot start an empty chain", block.index);
             }
+            return Ok(());
+        }
+
+        SecurityValidator::with_params(&self.params).validate_block_against_chain(block, self)?;
+
+        if let Some(tx) = block.transactions.iter().skip(1).find(|tx| !self.can_include(tx, block.index, block.timestamp)) {
+            anyhow::bail!("Transaction {} spends an immature coinbase or is still locked", tx.id);
+        }
+
+        // Chaque transaction doit dépenser des outputs existants sans créer de valeur
+        if let Some(tx) = block.transactions.iter().skip(1).find(|tx| tx.fee_with(|input| self.resolve_input(input)).is_none()) {
+            anyhow::bail!("Transaction {} spends unknown outputs or creates value", tx.id);
         }
 
-        block.hash == block.calculate_hash()
+        // Signatures et propriété des outputs dépensés, en dernier : c'est le plus coûteux
+        SignatureValidator::verify_block_transactions(block, |input| self.find_output(input), self.params.network)
     }
 }
 
+/// Hash de l'ensemble `utxos`, indépendant de leur ordre. Chaque champ de longueur
+/// variable est préfixé par sa taille pour qu'aucun encodage ne soit ambigu.
+fn utxo_commitment(mut utxos: Vec<Utxo>) -> String {
+    utxos.sort_by(|a, b| (a.tx_id.as_str(), a.output_index).cmp(&(b.tx_id.as_str(), b.output_index)));
+
+    let mut hasher = Sha256::new();
+    for utxo in &utxos {
+        hasher.update((utxo.tx_id.len() as u64).to_le_bytes());
+        hasher.update(utxo.tx_id.as_bytes());
+        hasher.update((utxo.output_index as u64).to_le_bytes());
+        hasher.update(utxo.value.to_le_bytes());
+        hasher.update((utxo.address.len() as u64).to_le_bytes());
+        hasher.update(utxo.address.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Genesis mainnet ; la définition unique est dans `genesis.rs`
 pub fn create_genesis_block() -> Block {
-    use crate::blockchain::{TxInput, TxOutput};
-    
-    let genesis_tx = Transaction::new(
-        vec![TxInput {
-            prev_tx_id: "0".to_string(),
-            output_index: 0,
-            public_key: "genesis".to_string(),
-            signature: "genesis_sig".to_string(),
-        }],
-        vec![TxOutput {
-            address: "AUR3ZnxihprBGetUiMoHwRWZbcyU94TzP52Jkk".to_string(),
-            value: 50,
-        }]
-    );
-
-    Block::new(
-        0,
-        vec![genesis_tx],
-        "0".to_string(),
-        4,
-        "AUR3ZnxihprBGetUiMoHwRWZbcyU94TzP52Jkk".to_string(),
-    )
+    crate::blockchain::genesis::create_genesis_block()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::genesis::create_genesis_block_with_params;
+    use crate::mining::Miner;
+    use crate::wallet::keys::KeyPair;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+
+    /// Maturité désactivée : ces tests dépensent les coinbases au bloc suivant
+    fn test_params() -> ConsensusParams {
+        ConsensusParams {
+            difficulty: 1,
+            coinbase_maturity: 0,
+            ..ConsensusParams::default()
+        }
+    }
+
+    fn node_with(params: &ConsensusParams) -> Blockchain {
+        let mut node = Blockchain::with_params(params.clone());
+        node.chain.push(create_genesis_block_with_params(params));
+        node
+    }
+
+    /// Clé de test déterministe, dérivée de `name`
+    fn test_key(name: &str) -> KeyPair {
+        let private_key = secp256k1::SecretKey::from_slice(&sha2::Sha256::digest(name.as_bytes())).unwrap();
+        let public_key = secp256k1::PublicKey::from_secret_key(&secp256k1::Secp256k1::new(), &private_key);
+        KeyPair { private_key, public_key }
+    }
+
+    /// Adresse mainnet de la clé de test `name`
+    fn test_address(name: &str) -> String {
+        crate::wallet::address::address_from_keypair(&test_key(name), crate::utils::config::Network::Mainnet)
+    }
+
+    fn mine_next(node: &Blockchain) -> Block {
+        let template = node.create_block(vec![], 0, test_address("Miner"), 1_730_000_000);
+        Miner::new(test_address("Miner"))
+            .mine_block(template, Arc::new(AtomicBool::new(false)))
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_same_params_same_hashes() {
+        let params = test_params();
+        let node_a = node_with(&params);
+        let node_b = node_with(&params);
+
+        assert_eq!(node_a.chain[0].hash, node_b.chain[0].hash);
+        assert_eq!(mine_next(&node_a).hash, mine_next(&node_b).hash);
+    }
+
+    #[test]
+    fn test_different_params_diverge() {
+        let params_a = test_params();
+        let params_b = ConsensusParams {
+            initial_reward: params_a.initial_reward / 2,
+            ..params_a.clone()
+        };
+
+        let block_a = mine_next(&node_with(&params_a));
+        let block_b = mine_next(&node_with(&params_b));
+
+        assert_ne!(block_a.merkle_root, block_b.merkle_root);
+        assert_ne!(block_a.hash, block_b.hash);
+    }
+
+    #[test]
+    fn test_coinbase_includes_fees() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions("AUR1Alice".to_string());
+
+        let funding = node.chain[1].transactions[0].clone();
+        let subsidy = params.block_reward(2);
+        let fee = 50_000;
+
+        let spend = Transaction::new(
+            vec![TxInput {
+                prev_tx_id: funding.id.clone(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![crate::blockchain::TxOutput {
+                value: funding.total_output() - fee,
+                address: test_address("Bob"),
+            }],
+        );
+        node.mempool.add_transaction(spend, fee).unwrap();
+        node.mine_pending_transactions(test_address("Carol"));
+
+        let block = node.chain[2].clone();
+        assert_eq!(block.transactions.len(), 2);
+        assert_eq!(block.transactions[0].total_output(), subsidy + fee);
+        assert!(node.mempool.is_empty());
+
+        let validator = crate::security::SecurityValidator::with_params(&params);
+        assert!(validator
+            .validate_block_with_inputs(&block, &node.chain[1], |input| node.resolve_input(input))
+            .is_ok());
+
+        // Sans pouvoir résoudre les inputs, les frais réclamés ne sont pas prouvables
+        assert!(validator.validate_block_strict(&block, &node.chain[1]).is_err());
+    }
+
+    #[test]
+    fn test_inputs_resolved_and_fees_computed() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+
+        let funding = node.chain[1].transactions[0].clone();
+        let reward = funding.total_output();
+        let spend = |value: u64| {
+            let mut tx = Transaction::new(
+                vec![TxInput {
+                    prev_tx_id: funding.id.clone(),
+                    output_index: 0,
+                    signature: String::new(),
+                    public_key: String::new(),
+                }],
+                vec![
+                    crate::blockchain::TxOutput { value, address: "AUR1Bob".to_string() },
+                ],
+            );
+            tx.sign_input(0, &test_key("Alice")).unwrap();
+            tx
+        };
+
+        let resolve = |input: &TxInput| node.resolve_input(input);
+        let balanced = spend(reward);
+        assert_eq!(balanced.total_input(resolve), Some(reward));
+        assert_eq!(balanced.fee_with(resolve), Some(0));
+        assert!(node.validate_transaction(&balanced).is_ok());
+
+        let overspend = spend(reward + 1);
+        assert_eq!(overspend.fee_with(resolve), None);
+        let err = node.validate_transaction(&overspend).unwrap_err();
+        assert!(err.to_string().contains("inputs only hold"));
+        assert!(node.accept_transaction(overspend.clone()).is_err());
+
+        // Un bloc contenant la sur-dépense est refusé, même avec une coinbase honnête
+        let forged = node.create_block(vec![overspend], 0, "AUR1Carol".to_string(), node.next_block_timestamp());
+        let forged = Miner::new("AUR1Carol".to_string())
+            .mine_block(forged, Arc::new(AtomicBool::new(false)))
+            .unwrap()
+            .0;
+        assert!(!node.validate_new_block(&forged));
+
+        let paying = spend(reward - 75_000);
+        assert_eq!(node.accept_transaction(paying.clone()).unwrap(), 75_000);
+        node.mine_pending_transactions("AUR1Carol".to_string());
+        assert_eq!(node.chain[2].transactions[0].total_output(), params.block_reward(2) + 75_000);
+
+        // Une fois dépensé, l'output ne peut plus servir
+        assert!(node.validate_transaction(&spend(1)).unwrap_err().to_string().contains("already spent"));
+    }
+
+    /// Bloc miné au sommet de `node` contenant `transactions`, sans frais réclamés.
+    /// Le mineur change à chaque bloc pour que deux coinbases du même horodatage diffèrent.
+    fn block_with(node: &Blockchain, transactions: Vec<Transaction>) -> Block {
+        let miner = test_address(&format!("Miner{}", node.chain.len()));
+        let mut block = node.create_block(transactions, 0, miner, node.next_block_timestamp());
+        block.mine();
+        block
+    }
+
+    #[test]
+    fn test_double_spend_across_blocks_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+
+        let first = spend(&funding, "Alice", &test_address("Bob"), 0);
+        let block = block_with(&node, vec![first]);
+        assert!(node.append_if_tip(block));
+
+        let second = spend(&funding, "Alice", &test_address("Dave"), 1);
+        let block = block_with(&node, vec![second]);
+        assert!(!node.validate_new_block(&block));
+        let err = crate::security::SecurityValidator::with_params(&params)
+            .validate_block_against_chain(&block, &node)
+            .unwrap_err();
+        assert!(err.to_string().contains("already spent in an earlier block"), "{}", err);
+    }
+
+    #[test]
+    fn test_received_block_with_unsigned_spend_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+        let mallory = test_address("Mallory");
+
+        // Dépense non signée de la coinbase d'Alice au profit de l'attaquant
+        let mut unsigned = spend(&funding, "Alice", &mallory, 0);
+        unsigned.inputs[0].signature.clear();
+        unsigned.inputs[0].public_key.clear();
+        assert_eq!(node.receive_block(block_with(&node, vec![unsigned])), BlockOutcome::Rejected);
+
+        // Signée, mais avec la clé de l'attaquant
+        let stolen = spend(&funding, "Mallory", &mallory, 0);
+        assert_eq!(node.receive_block(block_with(&node, vec![stolen])), BlockOutcome::Rejected);
+        assert_eq!(node.chain.len(), 2);
+        assert_eq!(node.get_balance(&mallory), 0);
+
+        let honest = spend(&funding, "Alice", &test_address("Bob"), 0);
+        assert_eq!(node.receive_block(block_with(&node, vec![honest])), BlockOutcome::Connected(1));
+    }
+
+    #[test]
+    fn test_accept_transaction_rejects_foreign_signature() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+
+        let mut unsigned = spend(&funding, "Alice", &test_address("Mallory"), 60_000);
+        unsigned.inputs[0].signature.clear();
+        unsigned.inputs[0].public_key.clear();
+        assert!(node.accept_transaction(unsigned.clone()).is_err());
+
+        let stolen = spend(&funding, "Mallory", &test_address("Mallory"), 60_000);
+        assert!(node.accept_transaction(stolen).is_err());
+        assert!(node.mempool.is_empty());
+        assert!(!node.mempool.is_seen(&unsigned.id));
+
+        let honest = spend(&funding, "Alice", &test_address("Bob"), 60_000);
+        assert_eq!(node.accept_transaction(honest).unwrap(), 60_000);
+    }
+
+    /// Rejet de `block` par `validate_new_block`, avec la raison donnée par `check_utxo_conflicts`
+    fn assert_conflict(node: &Blockchain, block: &Block, reason: &str) {
+        assert!(!node.validate_new_block(block));
+        let err = node.check_utxo_conflicts(block).unwrap_err();
+        assert!(err.to_string().contains(reason), "{}", err);
+    }
+
+    /// Ré-miner `block` après modification de ses transactions
+    fn remine(mut block: Block) -> Block {
+        block.merkle_root = Block::calculate_merkle_root(&block.transactions);
+        block.mine();
+        block
+    }
+
+    #[test]
+    fn test_double_spend_within_block_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+
+        let to_bob = spend(&funding, "Alice", &test_address("Bob"), 0);
+        let to_dave = spend(&funding, "Alice", &test_address("Dave"), 0);
+        assert_conflict(&node, &block_with(&node, vec![to_bob, to_dave]), "spent twice in the block");
+    }
+
+    #[test]
+    fn test_duplicate_txid_within_block_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+
+        let payment = spend(&funding, "Alice", &test_address("Bob"), 0);
+        assert_conflict(&node, &block_with(&node, vec![payment.clone(), payment]), "appears twice in the block");
+    }
+
+    #[test]
+    fn test_transaction_with_forged_id_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+
+        let mut payment = spend(&funding, "Alice", &test_address("Bob"), 0);
+        payment.id = "f".repeat(64);
+        assert_conflict(&node, &block_with(&node, vec![payment]), "does not match its content");
+    }
+
+    #[test]
+    fn test_coinbase_only_at_first_position() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+
+        // Seconde coinbase après celle du mineur
+        let extra = Transaction::coinbase(test_address("Mallory"), 1, node.next_block_timestamp());
+        assert_conflict(&node, &block_with(&node, vec![extra]), "only the first transaction may be a coinbase");
+
+        // Première transaction avec des inputs
+        let mut block = block_with(&node, vec![spend(&funding, "Alice", &test_address("Bob"), 0)]);
+        block.transactions.remove(0);
+        assert_conflict(&node, &remine(block), "only the first transaction may be a coinbase");
+    }
+
+    #[test]
+    fn test_replayed_txid_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+
+        let payment = spend(&funding, "Alice", &test_address("Bob"), 0);
+        assert!(node.append_if_tip(block_with(&node, vec![payment.clone()])));
+
+        let replay = block_with(&node, vec![payment.clone()]);
+        assert!(!node.validate_new_block(&replay));
+        let err = crate::security::SecurityValidator::with_params(&params)
+            .validate_block_against_chain(&replay, &node)
+            .unwrap_err();
+        assert!(err.to_string().contains(&format!("Transaction {} already confirmed", payment.id)), "{}", err);
+
+        // Une coinbase identique à une coinbase confirmée est un rejeu aussi
+        let mut coinbase_replay = replay.clone();
+        coinbase_replay.transactions = vec![node.chain[1].transactions[0].clone()];
+        coinbase_replay.merkle_root = Block::calculate_merkle_root(&coinbase_replay.transactions);
+        assert!(node.check_utxo_conflicts(&coinbase_replay).is_err());
+    }
+
+    #[test]
+    fn test_huge_outputs_rejected_without_overflow() {
+        let params = test_params();
+        let mut node = node_with(&params);
+
+        let mut coinbase = Transaction::coinbase("AUR1Attacker".to_string(), u64::MAX - 1, 1_730_000_000);
+        coinbase.outputs.push(crate::blockchain::TxOutput {
+            value: 10,
+            address: "AUR1Attacker".to_string(),
+        });
+        let block = Block::new(1, vec![coinbase.clone()], node.chain[0].hash.clone(), 1, "AUR1Attacker".to_string());
+
+        let validator = crate::security::SecurityValidator::with_params(&params);
+        let err = validator.validate_block_strict(&block, &node.chain[0]).unwrap_err();
+        assert!(err.to_string().contains("overflows"));
+
+        crate::security::SecurityMonitor::new().check_transaction(&coinbase);
+        assert_eq!(coinbase.checked_total_output(), None);
+        assert_eq!(coinbase.total_output(), u64::MAX);
+
+        // Même si un tel bloc était stocké, l'offre et les soldes restent bornés
+        node.chain.push(block);
+        assert_eq!(node.get_total_supply(), crate::blockchain::genesis::TOTAL_SUPPLY);
+        assert_eq!(node.get_balance("AUR1Attacker"), u64::MAX);
+    }
+
+    /// Dépense du premier output de `funding`, signée par la clé de test `owner`
+    fn spend(funding: &Transaction, owner: &str, to: &str, fee: u64) -> Transaction {
+        let mut tx = Transaction::new(
+            vec![TxInput {
+                prev_tx_id: funding.id.clone(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![crate::blockchain::TxOutput {
+                value: funding.total_output() - fee,
+                address: to.to_string(),
+            }],
+        );
+        tx.sign_input(0, &test_key(owner)).unwrap();
+        tx
+    }
+
+    #[test]
+    fn test_utxo_set_hash_tracks_spends() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let funding = node.chain[1].transactions[0].clone();
+
+        let before = node.utxo_set_hash();
+        assert_eq!(before, node.clone().utxo_set_hash());
+        let mut reversed = node.utxo_set();
+        reversed.reverse();
+        assert_eq!(utxo_commitment(reversed), before);
+
+        let block = block_with(&node, vec![spend(&funding, "Alice", &test_address("Bob"), 0)]);
+        assert!(node.append_if_tip(block));
+
+        // L'output d'Alice est remplacé par celui de Bob (et une nouvelle coinbase)
+        let after = node.utxo_set_hash();
+        assert_ne!(after, before);
+        assert_eq!(after, utxo_commitment(node.utxo_set()));
+        assert!(node.utxo_set().iter().all(|utxo| utxo.tx_id != funding.id));
+    }
+
+    #[test]
+    fn test_block_template_ready_for_external_mining() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        node.mine_pending_transactions(test_address("Alice"));
+
+        let cheap = spend(&node.chain[1].transactions[0], "Alice", &test_address("Bob"), 60_000);
+        let generous = spend(&node.chain[2].transactions[0], "Alice", &test_address("Carol"), 500_000);
+        node.mempool.add_transaction(cheap.clone(), 60_000).unwrap();
+        node.mempool.add_transaction(generous.clone(), 500_000).unwrap();
+
+        let template = node.block_template(test_address("Miner"));
+        let tip = node.get_latest_block().unwrap();
+        assert_eq!(template.index, tip.index + 1);
+        assert_eq!(template.previous_hash, tip.hash);
+        assert_eq!(template.nonce, 0);
+        assert_eq!(template.merkle_root, Block::calculate_merkle_root(&template.transactions));
+
+        // Coinbase d'abord, puis le meilleur taux de frais
+        let ids: Vec<&str> = template.transactions.iter().skip(1).map(|tx| tx.id.as_str()).collect();
+        assert_eq!(ids, vec![generous.id.as_str(), cheap.id.as_str()]);
+        assert_eq!(template.transactions[0].total_output(), params.block_reward(template.index) + 560_000);
+
+        // Le mineur externe n'itère que le nonce
+        let mut block = template.clone();
+        block.mine();
+        assert!(node.validate_new_block(&block));
+        assert!(crate::security::SecurityValidator::with_params(&params)
+            .validate_block_with_inputs(&block, tip, |input| node.resolve_input(input))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_mined_template_confirms_mempool_transactions() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        node.mine_pending_transactions(test_address("Alice"));
+        let payment = spend(&node.chain[1].transactions[0], "Alice", &test_address("Bob"), 60_000);
+        node.accept_transaction(payment.clone()).unwrap();
+
+        // Comme la boucle de minage du nœud : modèle, preuve de travail hors verrou, puis ajout
+        let template = node.block_template(test_address("Miner"));
+        let (block, _) = Miner::new(test_address("Miner"))
+            .mine_block(template, Arc::new(AtomicBool::new(false)))
+            .unwrap();
+        assert!(node.validate_new_block(&block));
+        assert!(node.append_if_tip(block));
+        assert!(node.get_transaction(&payment.id).is_some());
+        assert!(node.mempool.is_empty());
+
+        // Un bloc reçu qui dépense le même output évince la transaction en attente
+        let pending = spend(&node.chain[2].transactions[0], "Alice", &test_address("Carol"), 60_000);
+        node.accept_transaction(pending.clone()).unwrap();
+        let competing = spend(&node.chain[2].transactions[0], "Alice", &test_address("Dave"), 0);
+        assert_eq!(node.receive_block(block_with(&node, vec![competing])), BlockOutcome::Connected(1));
+        assert!(!node.mempool.contains(&pending.id));
+    }
+
+    #[test]
+    fn test_mempool_reload_drops_stale_transactions() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        node.mine_pending_transactions(test_address("Dave"));
+
+        let first = spend(&node.chain[1].transactions[0], "Alice", "AUR1Bob", 50_000);
+        let second = spend(&node.chain[2].transactions[0], "Dave", "AUR1Carol", 50_000);
+        node.mempool.add_transaction(first.clone(), 50_000).unwrap();
+        node.mempool.add_transaction(second.clone(), 50_000).unwrap();
+
+        let path = std::env::temp_dir().join(format!("aur_mempool_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        node.mempool.save(path).unwrap();
+
+        // Seule la première est minée
+        node.mempool.remove(&second.id);
+        node.mine_pending_transactions("AUR1Miner".to_string());
+        assert!(node.mempool.is_empty());
+
+        assert_eq!(node.load_mempool(path).unwrap(), 1);
+        assert!(node.mempool.contains(&second.id));
+        assert!(!node.mempool.contains(&first.id));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_validator_uses_same_params() {
+        let params = test_params();
+        let node = node_with(&params);
+        let block = mine_next(&node);
+
+        let validator = crate::security::SecurityValidator::with_params(&params);
+        assert!(validator.validate_block_strict(&block, &node.chain[0]).is_ok());
+
+        let stricter = ConsensusParams {
+            initial_reward: params.initial_reward - 1,
+            ..params.clone()
+        };
+        let validator = crate::security::SecurityValidator::with_params(&stricter);
+        assert!(validator.validate_block_strict(&block, &node.chain[0]).is_err());
+    }
+
+    #[test]
+    fn test_blocks_range_bounds() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        for _ in 0..5 {
+            node.mine_pending_transactions(test_address("Alice"));
+        }
+
+        let range = node.get_blocks_range(2, 4).unwrap();
+        assert_eq!(range.blocks.iter().map(|block| block.index).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert!(range.is_complete() && !range.capped);
+
+        // Au-delà du sommet (5) : résultat partiel
+        let partial = node.get_blocks_range(4, 9).unwrap();
+        assert_eq!((partial.found(), partial.requested), (2, 6));
+        assert!(!partial.is_complete());
+
+        let err = node.get_blocks_range(4, 2).unwrap_err();
+        assert!(err.to_string().contains("Invalid block range"), "{}", err);
+
+        let huge = node.get_blocks_range(1, u64::MAX).unwrap();
+        assert!(huge.capped);
+        assert_eq!(huge.requested, MAX_BLOCKS_PER_RANGE);
+        assert_eq!(huge.found(), 5);
+        assert!(node.get_blocks_range(u64::MAX, u64::MAX).unwrap().blocks.is_empty());
+    }
+
+    #[test]
+    fn test_lowered_difficulty_rejected() {
+        let params = ConsensusParams { difficulty: 2, ..test_params() };
+        let node = node_with(&params);
+        let validator = crate::security::SecurityValidator::with_params(&params);
+
+        let mut block = node.block_template(test_address("Miner"));
+        block.mine();
+        assert!(validator.validate_block_against_chain(&block, &node).is_ok());
+        assert!(node.validate_new_block(&block));
+
+        // Miné honnêtement, mais à une difficulté que le réseau n'exige pas
+        let mut easy = node.block_template(test_address("Miner"));
+        easy.difficulty = 1;
+        easy.mine();
+        assert!(easy.meets_difficulty());
+        let err = validator.validate_block_against_chain(&easy, &node).unwrap_err();
+        assert!(err.to_string().contains("does not match required"), "{}", err);
+        assert!(!node.validate_new_block(&easy));
+        assert!(crate::p2p::headers::validate_header_chain(&node.chain[0].header(), &[easy.header()], &params).is_err());
+        assert!(crate::p2p::headers::validate_header_chain(&node.chain[0].header(), &[block.header()], &params).is_ok());
+    }
+
+    #[test]
+    fn test_coinbase_spendable_only_after_maturity() {
+        let params = ConsensusParams { coinbase_maturity: 3, ..test_params() };
+        let mut node = node_with(&params);
+        let alice = test_address("Alice");
+        node.mine_pending_transactions(alice.clone());
+
+        let funding = node.chain[1].transactions[0].clone();
+        let early = spend(&funding, "Alice", "AUR1Bob", 50_000);
+        assert_eq!(node.get_balance(&alice), 0);
+        assert!(node.validate_transaction(&early).is_err());
+
+        // Dépense immédiate refusée : la transaction reste en attente dans le mempool
+        node.mempool.add_transaction(early.clone(), 50_000).unwrap();
+        node.mine_pending_transactions("AUR1Miner1".to_string());
+        assert_eq!(node.chain[2].transactions.len(), 1);
+        assert!(node.mempool.contains(&early.id));
+
+        // Un bloc qui l'inclurait quand même est rejeté
+        let mut forced = node.create_block(vec![early.clone()], 0, "AUR1Miner".to_string(), chrono::Utc::now().timestamp());
+        forced.mine();
+        assert!(!node.validate_new_block(&forced));
+
+        node.mine_pending_transactions("AUR1Miner2".to_string());
+        assert_eq!(node.get_balance(&alice), funding.total_output());
+        assert!(node.validate_transaction(&early).is_ok());
+
+        node.mine_pending_transactions("AUR1Miner3".to_string());
+        assert_eq!(node.chain[4].transactions[1].id, early.id);
+        assert!(node.mempool.is_empty());
+        assert_eq!(node.get_balance(&alice), 0);
+    }
+
+    #[test]
+    fn test_locktime_delays_inclusion_until_height() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+
+        let mut locked = spend(&node.chain[1].transactions[0], "Alice", "AUR1Bob", 50_000).with_locktime(4);
+        locked.sign_input(0, &test_key("Alice")).unwrap();
+        let err = node.validate_transaction(&locked).unwrap_err();
+        assert!(err.to_string().contains("locked until 4"), "{}", err);
+
+        // Un bloc de hauteur 2 qui l'inclurait est rejeté
+        let mut early = node.create_block(vec![locked.clone()], 50_000, "AUR1Miner".to_string(), node.next_block_timestamp());
+        early.mine();
+        assert!(!node.validate_new_block(&early));
+        let validator = crate::security::SecurityValidator::with_params(&params);
+        let err = validator.validate_block_with_inputs(&early, &node.chain[1], |input| node.resolve_input(input)).unwrap_err();
+        assert!(err.to_string().contains("locked"), "{}", err);
+
+        // En attente dans le mempool jusqu'à la hauteur 4
+        node.mempool.add_transaction(locked.clone(), 50_000).unwrap();
+        node.mine_pending_transactions("AUR1Miner2".to_string());
+        assert_eq!(node.chain[2].transactions.len(), 1);
+        node.mine_pending_transactions("AUR1Miner3".to_string());
+        assert_eq!(node.chain[3].transactions.len(), 1);
+        assert!(node.mempool.contains(&locked.id));
+        assert!(node.validate_transaction(&locked).is_ok());
+
+        node.mine_pending_transactions("AUR1Miner4".to_string());
+        assert_eq!(node.chain[4].transactions[1].id, locked.id);
+        assert!(node.mempool.is_empty());
+        assert_eq!(node.get_balance("AUR1Bob"), locked.total_output());
+    }
+
+    #[test]
+    fn test_block_below_median_time_past_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        let base = node.chain[0].timestamp;
+
+        let mine_at = |node: &Blockchain, miner: &str, timestamp: i64| {
+            let template = node.create_block(vec![], 0, miner.to_string(), timestamp);
+            Miner::new(miner.to_string())
+                .mine_block(template, Arc::new(AtomicBool::new(false)))
+                .unwrap()
+                .0
+        };
+        for (i, offset) in [100, 200, 300, 300, 300].iter().enumerate() {
+            let block = mine_at(&node, &format!("AUR1Miner{}", i), base + offset);
+            node.chain.push(block);
+        }
+        assert_eq!(node.median_time_past(), base + 300);
+
+        // Égal au bloc précédent (accepté par le lien de chaîne) mais pas postérieur à la médiane
+        let validator = crate::security::SecurityValidator::with_params(&params);
+        let stale = mine_at(&node, &test_address("Late"), base + 300);
+        let err = validator.validate_block_against_chain(&stale, &node).unwrap_err();
+        assert!(err.to_string().contains("median time past"));
+        assert!(!node.validate_new_block(&stale));
+
+        let fresh = mine_at(&node, &test_address("Late"), base + 301);
+        assert!(validator.validate_block_against_chain(&fresh, &node).is_ok());
+        assert!(node.validate_new_block(&fresh));
+    }
+
+    #[test]
+    fn test_malformed_output_address_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let validator = crate::security::SecurityValidator::with_params(&params);
+        let funding = node.chain[1].transactions[0].clone();
+
+        let pay = |address: String| {
+            let spend = Transaction::new(
+                vec![TxInput {
+                    prev_tx_id: funding.id.clone(),
+                    output_index: 0,
+                    signature: String::new(),
+                    public_key: String::new(),
+                }],
+                vec![TxOutput { value: 1_000_000, address }],
+            );
+            let template = node.create_block(vec![spend], 0, test_address("Miner"), node.next_block_timestamp());
+            Miner::new(test_address("Miner"))
+                .mine_block(template, Arc::new(AtomicBool::new(false)))
+                .unwrap()
+                .0
+        };
+
+        let valid = pay(test_address("Bob"));
+        assert!(validator.validate_block_against_chain(&valid, &node).is_ok());
+
+        let garbage = pay("not an address".to_string());
+        let err = validator.validate_block_against_chain(&garbage, &node).unwrap_err();
+        assert!(err.to_string().contains("malformed address"), "{}", err);
+
+        // Adresse d'un autre réseau
+        let testnet = pay(crate::wallet::address::generate_address(b"Bob", crate::utils::config::Network::Testnet));
+        assert!(validator.validate_block_against_chain(&testnet, &node).is_err());
+
+        // Le genesis, qui porte un message en guise d'adresse, reste accepté
+        assert!(!crate::wallet::address::validate_address(&node.chain[0].transactions[0].outputs[0].address, params.network));
+    }
+
+    #[test]
+    fn test_block_with_tampered_transactions_rejected() {
+        let params = test_params();
+        let node = node_with(&params);
+        let validator = crate::security::SecurityValidator::with_params(&params);
+
+        let block = mine_next(&node);
+        assert!(node.validate_new_block(&block));
+        assert!(validator.validate_block_against_chain(&block, &node).is_ok());
+
+        // Hash toujours cohérent avec l'en-tête, mais la coinbase ne correspond plus à la racine
+        let mut tampered = block.clone();
+        tampered.transactions[0].outputs[0].address = "AUR1Thief".to_string();
+        tampered.transactions[0] = tampered.transactions[0].clone().with_locktime(0);
+        assert_eq!(tampered.hash, tampered.calculate_hash());
+        assert!(!node.validate_new_block(&tampered));
+        let err = validator.validate_block_against_chain(&tampered, &node).unwrap_err();
+        assert!(err.to_string().contains("Merkle root"));
+    }
+
+    #[test]
+    fn test_issuance_tapers_to_zero_at_supply_cap() {
+        let params = ConsensusParams {
+            max_supply: 125_0000_0000, // deux subventions et demie
+            ..test_params()
+        };
+        let mut node = node_with(&params);
+
+        let mut rewards = Vec::new();
+        for i in 0..4 {
+            node.mine_pending_transactions(format!("AUR1Miner{}", i));
+            rewards.push(node.get_latest_block().unwrap().transactions[0].total_output());
+            assert!(node.total_issued() <= params.max_supply);
+        }
+        assert_eq!(rewards, vec![50_0000_0000, 50_0000_0000, 25_0000_0000, 0]);
+        assert_eq!(node.total_issued(), params.max_supply);
+
+        // Une coinbase qui réclame encore la subvention pleine est refusée
+        let later = chrono::Utc::now().timestamp() + 60;
+        let greedy = Transaction::coinbase("AUR1Greedy".to_string(), params.initial_reward, later);
+        let mut block = node.create_block(vec![], 0, "AUR1Greedy".to_string(), later);
+        block.transactions[0] = greedy;
+        block.merkle_root = Block::calculate_merkle_root(&block.transactions);
+        block.mine();
+
+        let validator = crate::security::SecurityValidator::with_params(&params);
+        let err = validator.validate_block_against_chain(&block, &node).unwrap_err();
+        assert!(err.to_string().contains("Excessive mining reward"));
+        assert!(!node.validate_new_block(&block));
+    }
+
+    #[test]
+    fn test_block_mined_on_stale_tip_discarded() {
+        let params = test_params();
+        let mut node = node_with(&params);
+
+        // Instantané du sommet, puis un bloc arrive par la synchronisation pendant le minage
+        let template = node.create_block(vec![], 0, "AUR1Slow".to_string(), node.next_block_timestamp());
+        let synced = mine_next(&node);
+        assert!(node.append_if_tip(synced.clone()));
+
+        let (stale, _) = Miner::new("AUR1Slow".to_string())
+            .mine_block(template, Arc::new(AtomicBool::new(false)))
+            .unwrap();
+        assert!(!node.append_if_tip(stale));
+        assert_eq!(node.chain.len(), 2);
+        assert_eq!(node.get_latest_block().unwrap().hash, synced.hash);
+
+        // Reprise sur le nouveau sommet
+        let template = node.create_block(vec![], 0, "AUR1Slow".to_string(), node.next_block_timestamp());
+        let (fresh, _) = Miner::new("AUR1Slow".to_string())
+            .mine_block(template, Arc::new(AtomicBool::new(false)))
+            .unwrap();
+        assert!(node.append_if_tip(fresh));
+        assert_eq!(node.chain.len(), 3);
+    }
+
+    #[test]
+    fn test_child_before_parent_connected_once_parent_arrives() {
+        let params = test_params();
+        let mut node = node_with(&params);
+
+        let mut remote = node.clone();
+        remote.mine_pending_transactions(test_address("Alice"));
+        remote.mine_pending_transactions(test_address("Bob"));
+        let parent = remote.chain[1].clone();
+        let child = remote.chain[2].clone();
+
+        assert_eq!(node.receive_block(child.clone()), BlockOutcome::Orphaned);
+        assert_eq!(node.chain.len(), 1);
+        assert!(node.orphans.contains(&child.hash));
+
+        assert_eq!(node.receive_block(parent), BlockOutcome::Connected(2));
+        assert_eq!(node.chain.len(), 3);
+        assert_eq!(node.get_latest_block().unwrap().hash, child.hash);
+        assert!(node.orphans.is_empty());
+
+        // Un bloc dont le parent est connu mais invalide n'est pas un orphelin
+        let mut forged = mine_next(&node);
+        forged.nonce += 1;
+        assert_eq!(node.receive_block(forged), BlockOutcome::Rejected);
+    }
+
+    #[test]
+    fn test_orphan_without_proof_of_work_rejected() {
+        let params = test_params();
+        let mut node = node_with(&params);
+
+        let mut remote = node.clone();
+        remote.mine_pending_transactions(test_address("Alice"));
+        let mut orphan = Block::new(2, vec![], remote.chain[1].hash.clone(), params.difficulty, test_address("Spam"));
+        orphan.hash = orphan.calculate_hash();
+        while orphan.meets_difficulty() {
+            orphan.nonce += 1;
+            orphan.hash = orphan.calculate_hash();
+        }
+
+        assert_eq!(node.receive_block(orphan), BlockOutcome::Rejected);
+        assert!(node.orphans.is_empty());
+    }
+
+    #[test]
+    fn test_sibling_orphans_kept_when_parent_arrives() {
+        let params = test_params();
+        let mut node = node_with(&params);
+
+        let mut remote = node.clone();
+        remote.mine_pending_transactions(test_address("Alice"));
+        let parent = remote.chain[1].clone();
+        let mut sibling_remote = remote.clone();
+        remote.mine_pending_transactions(test_address("Bob"));
+        sibling_remote.mine_pending_transactions(test_address("Carol"));
+        let child = remote.chain[2].clone();
+        let sibling = sibling_remote.chain[2].clone();
+
+        assert_eq!(node.receive_block(child), BlockOutcome::Orphaned);
+        assert_eq!(node.receive_block(sibling), BlockOutcome::Orphaned);
+
+        assert_eq!(node.receive_block(parent), BlockOutcome::Connected(2));
+        assert_eq!(node.orphans.len(), 1);
+    }
+
+    #[test]
+    fn test_receive_block_enforces_size_count_and_timestamp_limits() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let mine = |node: &Blockchain, transactions: Vec<Transaction>, fees: u64, timestamp: i64| {
+            let template = node.create_block(transactions, fees, test_address("Miner"), timestamp);
+            Miner::new(test_address("Miner"))
+                .mine_block(template, Arc::new(AtomicBool::new(false)))
+                .unwrap()
+                .0
+        };
+        let rejected = |node: &mut Blockchain, block: Block, reason: &str| {
+            let err = node.check_new_block(&block).unwrap_err();
+            assert!(err.to_string().contains(reason), "{}", err);
+            assert_eq!(node.receive_block(block), BlockOutcome::Rejected);
+            assert_eq!(node.chain.len(), 2);
+        };
+
+        // Horodatage au-delà de max_future_timestamp
+        let far = chrono::Utc::now().timestamp() + params.max_future_timestamp + 600;
+        let future = mine(&node, vec![], 0, far);
+        rejected(&mut node, future, "too far in future");
+
+        // Plus de transactions que max_transactions_per_block
+        let payment = spend(&node.chain[1].transactions[0], "Alice", &test_address("Bob"), 10_000);
+        let crowded = mine(&node, vec![payment], 10_000, node.next_block_timestamp());
+        node.params.max_transactions_per_block = 1;
+        rejected(&mut node, crowded.clone(), "Too many transactions");
+        node.params.max_transactions_per_block = params.max_transactions_per_block;
+        assert!(node.check_new_block(&crowded).is_ok());
+
+        // Bloc plus gros que max_block_size
+        let block = mine(&node, vec![], 0, node.next_block_timestamp());
+        node.params.max_block_size = bincode::serialize(&block).unwrap().len() - 1;
+        rejected(&mut node, block, "Block size exceeds maximum");
+    }
+
+    #[test]
+    fn test_receive_block_rejects_malformed_output_address() {
+        let params = test_params();
+        let mut node = node_with(&params);
+        node.mine_pending_transactions(test_address("Alice"));
+        let mine = |node: &Blockchain, transactions: Vec<Transaction>, fees: u64, miner: &str| {
+            let template = node.create_block(transactions, fees, miner.to_string(), node.next_block_timestamp());
+            Miner::new(miner.to_string())
+                .mine_block(template, Arc::new(AtomicBool::new(false)))
+                .unwrap()
+                .0
+        };
+
+        // Paiement vers une adresse sans checksum valide, hors mempool
+        let payment = spend(&node.chain[1].transactions[0], "Alice", "AUR1NotAnAddress", 10_000);
+        let block = mine(&node, vec![payment.clone()], 10_000, &test_address("Miner"));
+        let err = node.check_new_block(&block).unwrap_err();
+        assert!(err.to_string().contains("malformed address"), "{}", err);
+        assert_eq!(node.receive_block(block), BlockOutcome::Rejected);
+
+        // La coinbase est soumise à la même règle
+        let block = mine(&node, vec![], 0, "AUR1NotAMiner");
+        assert_eq!(node.receive_block(block), BlockOutcome::Rejected);
+        assert_eq!(node.chain.len(), 2);
+        assert!(node.find_output(&payment.inputs[0]).is_some());
+    }
+
+    #[test]
+    fn test_reorg_deeper_than_limit_refused() {
+        use crate::security::{EventFilter, SecurityEventType, SecurityLogger, SecurityValidator};
+
+        let params = ConsensusParams { max_reorg_depth: 2, ..test_params() };
+        let mut node = node_with(&params);
+        for miner in ["Alice", "Bob", "Carol", "Dave"] {
+            node.mine_pending_transactions(test_address(miner));
+        }
+
+        fn fork_from(node: &Blockchain, height: usize, miners: &[&str]) -> Vec<Block> {
+            let mut fork = node.clone();
+            fork.chain.truncate(height + 1);
+            for miner in miners {
+                fork.mine_pending_transactions(test_address(miner));
+            }
+            fork.chain.split_off(height + 1)
+        }
+
+        let log_path = std::env::temp_dir().join(format!("aur_reorg_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&log_path);
+        let mut validator = SecurityValidator::with_params(&params);
+        validator.logger = Some(Arc::new(SecurityLogger::with_file(&log_path)));
+
+        // Annuler 4 blocs depuis le genesis : refusé et journalisé
+        let deep = fork_from(&node, 0, &["Eve", "Frank", "Grace", "Heidi", "Ivan"]);
+        let err = node.reorganize(deep, &validator).unwrap_err();
+        assert!(err.to_string().contains("exceeds the limit"), "{}", err);
+        assert_eq!(node.chain.len(), 5);
+        assert_eq!(node.chain[1].miner_address, test_address("Alice"));
+
+        let filter = EventFilter { event_type: Some(SecurityEventType::SuspiciousActivity), ..EventFilter::default() };
+        let events = validator.logger.as_ref().unwrap().query(&filter);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].details.contains("depth 4"));
+
+        // Un fork plus court n'est pas adopté, même peu profond
+        assert!(node.reorganize(fork_from(&node, 2, &["Judy"]), &validator).is_err());
+
+        // Deux blocs annulés : dans la limite
+        let shallow = fork_from(&node, 2, &["Mallory", "Niaj", "Olivia"]);
+        let tip = shallow.last().unwrap().hash.clone();
+        assert_eq!(node.reorganize(shallow, &validator).unwrap(), 2);
+        assert_eq!(node.chain.len(), 6);
+        assert_eq!(node.get_latest_block().unwrap().hash, tip);
+        assert_eq!(node.chain[2].miner_address, test_address("Bob"));
+        let _ = std::fs::remove_file(&log_path);
+    }
+
+    #[test]
+    fn test_reorg_restores_pre_fork_utxo_view() {
+        use crate::security::SecurityValidator;
+
+        let params = test_params();
+        let mut node = node_with(&params);
+        let alice = test_address("Alice");
+        node.mine_pending_transactions(alice.clone());
+        node.mine_pending_transactions(test_address("Bob"));
+
+        let addresses = [alice.clone(), test_address("Bob"), test_address("Zed")];
+        let utxo_view = |node: &Blockchain| -> Vec<Vec<Utxo>> {
+            addresses.iter().map(|address| node.get_utxos(address)).collect()
+        };
+        let pre_fork = utxo_view(&node);
+        let mut fork = node.clone();
+
+        // Bloc annulé ensuite : dépense la coinbase d'Alice et crée un output pour Zed
+        let payment = spend(&node.chain[1].transactions[0], "Alice", &test_address("Zed"), 50_000);
+        node.accept_transaction(payment.clone()).unwrap();
+        node.mine_pending_transactions(test_address("Carol"));
+        assert!(node.get_utxos(&alice).is_empty());
+        assert_eq!(node.get_utxos(&test_address("Zed")).len(), 1);
+
+        for miner in ["Dave", "Erin"] {
+            fork.mine_pending_transactions(test_address(miner));
+        }
+        let fork_blocks = fork.chain.split_off(3);
+        assert_eq!(node.reorganize(fork_blocks, &SecurityValidator::with_params(&params)).unwrap(), 1);
+
+        // Output dépensé restauré, output créé retiré ; la dépense retourne au mempool
+        assert_eq!(utxo_view(&node), pre_fork);
+        assert!(node.mempool.contains(&payment.id));
+    }
 }
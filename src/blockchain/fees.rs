@@ -15,6 +15,7 @@ pub struct FeeEstimate {
     pub per_byte: u64, // Frais par byte
 }
 
+#[derive(Debug, Clone)]
 pub struct TransactionFees {
     pub base_fee: u64,           // Frais minimum (satoshis)
     pub per_byte_fee: u64,       // Frais par byte
@@ -33,9 +34,9 @@ impl TransactionFees {
     /// Calculer les frais d'une transaction
     pub fn calculate_fee(&self, tx_size: usize, priority: Priority) -> u64 {
         let base = self.base_fee;
-        let size_fee = tx_size as u64 * self.per_byte_fee;
+        let size_fee = (tx_size as u64).saturating_mul(self.per_byte_fee);
         
-        let total = base + size_fee;
+        let total = base.saturating_add(size_fee);
         
         // Appliquer le multiplicateur de priorité
         let fee = match priority {
@@ -71,6 +72,16 @@ impl TransactionFees {
         }
     }
 
+    /// Taux minimum (satoshis/byte) accepté au relais selon la congestion
+    pub fn min_fee_rate(&self, pending_tx_count: usize) -> u64 {
+        self.estimate_fees(pending_tx_count).per_byte
+    }
+
+    /// Frais d'une transaction de `tx_size` bytes au taux `fee_rate`, jamais sous le minimum relay
+    pub fn fee_for_rate(&self, tx_size: usize, fee_rate: u64) -> u64 {
+        (tx_size as u64).saturating_mul(fee_rate).max(self.min_relay_fee)
+    }
+
     /// Vérifier si les frais sont suffisants
     pub fn verify_fee(&self, tx_size: usize, paid_fee: u64) -> bool {
         let min_fee = self.calculate_fee(tx_size, Priority::Low);
@@ -1,9 +1,30 @@
 use super::block::{Block, Transaction, TxOutput};
+use crate::utils::config::{ConsensusParams, GenesisConfig};
 
 pub const GENESIS_TIMESTAMP: i64 = 1729382400;
 pub const TOTAL_SUPPLY: u64 = 21_000_000_00000000;
+/// Nombre de blocs à attendre avant de pouvoir dépenser une coinbase (protection contre les reorgs)
+pub const COINBASE_MATURITY: u64 = 100;
+/// Nombre maximal de blocs confirmés qu'une réorganisation peut annuler
+pub const MAX_REORG_DEPTH: u64 = 6;
+/// Genesis mainnet d'avant l'encodage canonique des transactions : une chaîne enregistrée
+/// depuis ce genesis n'est plus compatible et doit être resynchronisée
+pub const LEGACY_GENESIS_HASH: &str = "0000521165d99d6bcd916e3ac5ecc5897084ddd0572b5de740cc55972de500d9";
 
 pub fn create_genesis_block() -> Block {
+    create_genesis_block_with_params(&ConsensusParams::default())
+}
+
+pub fn create_genesis_block_with_params(params: &ConsensusParams) -> Block {
+    let config = GenesisConfig {
+        difficulty: params.difficulty,
+        ..GenesisConfig::default()
+    };
+    create_genesis_block_with_config(&config, params)
+}
+
+/// Construire le genesis décrit par `config` ; `params` fournit la cible numérique éventuelle
+pub fn create_genesis_block_with_config(config: &GenesisConfig, params: &ConsensusParams) -> Block {
     println!("╔════════════════════════════════════════════════╗");
     println!("║          AURIUMCHAIN GENESIS BLOCK             ║");
     println!("║                                                ║");
@@ -21,50 +42,129 @@ pub fn create_genesis_block() -> Block {
     println!("║  Control:   NONE                              ║");
     println!("╚════════════════════════════════════════════════╝");
     
-    let genesis_message = "AuriumChain Genesis - October 20, 2025 - Autonomous & Decentralized";
+    let mut genesis = genesis_template(config, params);
+    genesis.mine();
+    
+    println!("\n🌟 Genesis Block Created!");
+    println!("   Hash: {}", genesis.hash);
+    println!("\n✨ AuriumChain is now ALIVE and AUTONOMOUS!\n");
+
+    genesis
+}
+
+/// Genesis décrit par `config`, avant la recherche du nonce
+fn genesis_template(config: &GenesisConfig, params: &ConsensusParams) -> Block {
+    let mut outputs = vec![TxOutput {
+        value: 0,
+        address: config.message.clone(),
+    }];
+    outputs.extend(config.premine.iter().map(|premine| TxOutput {
+        value: premine.value,
+        address: premine.address.clone(),
+    }));
 
     let genesis_tx = Transaction {
         id: "genesis".to_string(),
         inputs: vec![],
-        outputs: vec![TxOutput {
-            value: 0,
-            address: genesis_message.to_string(),
-        }],
-        timestamp: GENESIS_TIMESTAMP,
+        outputs,
+        timestamp: config.timestamp,
         signature: String::new(),
+        locktime: 0,
     };
 
     let mut genesis = Block {
         index: 0,
-        timestamp: GENESIS_TIMESTAMP,
+        timestamp: config.timestamp,
         transactions: vec![genesis_tx],
         previous_hash: "0".repeat(64),
         hash: String::new(),
         nonce: 0,
-        difficulty: 4,
-        miner_address: "GENESIS_ANONYMOUS".to_string(),
+        difficulty: config.difficulty,
+        miner_address: config.miner_address.clone(),
         merkle_root: String::new(),
+        target_bits: params.target_bits,
+        pow_algorithm: params.pow_algorithm,
     };
 
     genesis.merkle_root = Block::calculate_merkle_root(&genesis.transactions);
-    genesis.mine();
-    
-    println!("\n🌟 Genesis Block Created!");
-    println!("   Hash: {}", genesis.hash);
-    println!("\n✨ AuriumChain is now ALIVE and AUTONOMOUS!\n");
-
     genesis
 }
 
+/// Vérifier qu'un genesis chargé est bien celui décrit par `config`. Les règles des
+/// blocs ordinaires (coinbase bornée, chaînage) ne s'appliquent pas au genesis : il est
+/// comparé champ par champ au bloc attendu, puis son hash est recalculé avec son nonce.
+pub fn validate_genesis(block: &Block, config: &GenesisConfig) -> anyhow::Result<()> {
+    let expected = genesis_template(config, &config.consensus_params());
+
+    if block.index != 0 {
+        anyhow::bail!("Genesis has index {}", block.index);
+    }
+    if block.previous_hash != expected.previous_hash {
+        anyhow::bail!("Genesis previous hash is not all zeros");
+    }
+    let message = block.transactions.first().and_then(|tx| tx.outputs.first()).map(|output| output.address.as_str());
+    if message != Some(config.message.as_str()) {
+        anyhow::bail!("Genesis message {:?} differs from the configured one", message.unwrap_or_default());
+    }
+    if block.merkle_root != expected.merkle_root || block.merkle_root != Block::calculate_merkle_root(&block.transactions) {
+        anyhow::bail!("Genesis transactions differ from the configured ones");
+    }
+    if (block.timestamp, block.difficulty, &block.miner_address, block.target_bits, block.pow_algorithm)
+        != (expected.timestamp, expected.difficulty, &expected.miner_address, expected.target_bits, expected.pow_algorithm)
+    {
+        anyhow::bail!("Genesis header differs from the configured one");
+    }
+
+    let expected_hash = Block { nonce: block.nonce, ..expected }.calculate_hash();
+    if block.hash != expected_hash || !block.meets_difficulty() {
+        anyhow::bail!("Genesis hash {} does not match the expected {}", block.hash, expected_hash);
+    }
+    Ok(())
+}
+
 pub fn calculate_block_reward(block_height: u64) -> u64 {
-    const INITIAL_REWARD: u64 = 50_00000000;
-    const HALVING_INTERVAL: u64 = 4_204_800;
-    
-    let halvings = block_height / HALVING_INTERVAL;
-    
-    if halvings >= 64 {
-        return 0;
+    ConsensusParams::default().block_reward(block_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::config::GenesisOutput;
+
+    #[test]
+    fn test_default_config_matches_mainnet_genesis() {
+        let params = ConsensusParams::default();
+        let from_config = create_genesis_block_with_config(&GenesisConfig::default(), &params);
+
+        assert_eq!(from_config.hash, create_genesis_block().hash);
+        assert_eq!(from_config.timestamp, GENESIS_TIMESTAMP);
+        assert_eq!(from_config.hash, "0000e1c308cbcc657b493c1a08d6c6a7bbea5b0827ab6ca5008b9fff78ad8c3c");
+    }
+
+    #[test]
+    fn test_custom_config_changes_genesis_hash() {
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let base = GenesisConfig { difficulty: 1, ..GenesisConfig::default() };
+        let devnet = GenesisConfig {
+            message: "AuriumChain devnet".to_string(),
+            premine: vec![GenesisOutput { address: "AUR1Faucet".to_string(), value: 1_000_0000_0000 }],
+            ..base.clone()
+        };
+
+        let mainnet_like = create_genesis_block_with_config(&base, &params);
+        let custom = create_genesis_block_with_config(&devnet, &params);
+
+        assert_ne!(custom.hash, mainnet_like.hash);
+        assert_eq!(custom.transactions[0].outputs[1].value, 1_000_0000_0000);
+        assert!(custom.meets_difficulty());
+
+        assert!(validate_genesis(&mainnet_like, &base).is_ok());
+        assert!(validate_genesis(&custom, &devnet).is_ok());
+        let err = validate_genesis(&custom, &base).unwrap_err();
+        assert!(err.to_string().contains("message"), "{}", err);
+
+        let mut renonced = mainnet_like.clone();
+        renonced.nonce += 1;
+        assert!(validate_genesis(&renonced, &base).is_err());
     }
-    
-    INITIAL_REWARD >> halvings
 }
@@ -0,0 +1,461 @@
+use crate::blockchain::fees::TransactionFees;
+use crate::blockchain::Transaction;
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+/// Nombre de txids récemment vus mémorisés pour ignorer les annonces répétées
+const RECENT_TXIDS_CAPACITY: usize = 10_000;
+/// Durée de vie d'une transaction jamais minée (2 semaines)
+pub const MEMPOOL_EXPIRY_SECS: u64 = 14 * 24 * 3600;
+/// Taille totale maximale des transactions en attente
+pub const MAX_MEMPOOL_BYTES: usize = 64 * 1024 * 1024;
+/// Hausse minimale du taux de frais (en %) pour qu'une transaction remplace celles
+/// dont elle dépense les mêmes inputs (replace-by-fee)
+pub const MIN_RBF_FEE_RATE_BUMP_PERCENT: u64 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MempoolEntry {
+    pub tx: Transaction,
+    pub fee: u64,
+    pub size: usize,
+    /// Entrée dans le mempool (secondes Unix) ; un fichier sans ce champ compte comme neuf
+    #[serde(default = "unix_now")]
+    pub added_at: i64,
+}
+
+impl MempoolEntry {
+    /// Comparer les taux de frais (satoshis/byte) sans division
+    pub(crate) fn cmp_fee_rate(&self, other: &MempoolEntry) -> Ordering {
+        (self.fee as u128 * other.size as u128).cmp(&(other.fee as u128 * self.size as u128))
+    }
+
+    /// Taux de frais (satoshis/byte, arrondi à l'inférieur)
+    pub fn fee_rate(&self) -> u64 {
+        self.fee / self.size.max(1) as u64
+    }
+
+    /// Ordre d'inclusion : taux de frais décroissant, puis la plus ancienne, puis le txid.
+    /// Total et déterministe : deux nœuds avec le même mempool construisent le même bloc.
+    pub(crate) fn cmp_priority(&self, other: &MempoolEntry) -> Ordering {
+        other.cmp_fee_rate(self)
+            .then(self.added_at.cmp(&other.added_at))
+            .then_with(|| self.tx.id.cmp(&other.tx.id))
+    }
+
+    /// Vrai si les deux transactions dépensent au moins un même output
+    fn conflicts_with(&self, tx: &Transaction) -> bool {
+        self.tx.inputs.iter().any(|mine| tx.inputs.iter().any(|theirs| {
+            mine.prev_tx_id == theirs.prev_tx_id && mine.output_index == theirs.output_index
+        }))
+    }
+
+    /// Vrai si `self` paie un taux au moins `MIN_RBF_FEE_RATE_BUMP_PERCENT` % supérieur à `replaced`
+    fn outbids(&self, replaced: &MempoolEntry) -> bool {
+        self.fee as u128 * replaced.size as u128 * 100
+            >= replaced.fee as u128 * self.size as u128 * (100 + MIN_RBF_FEE_RATE_BUMP_PERCENT) as u128
+    }
+}
+
+fn unix_now() -> i64 {
+    Utc::now().timestamp()
+}
+
+/// Transactions en attente d'inclusion dans un bloc
+#[derive(Debug, Clone)]
+pub struct Mempool {
+    entries: Vec<MempoolEntry>,
+    fees: TransactionFees,
+    recent_txids: HashSet<String>,
+    recent_order: VecDeque<String>,
+    /// Au-delà, les transactions au plus faible taux de frais sont évincées
+    pub max_size_bytes: usize,
+    /// Âge au-delà duquel `Blockchain` évince une transaction non minée
+    pub max_age_secs: u64,
+}
+
+impl Default for Mempool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mempool {
+    pub fn new() -> Self {
+        Mempool {
+            entries: Vec::new(),
+            fees: TransactionFees::new(),
+            recent_txids: HashSet::new(),
+            recent_order: VecDeque::new(),
+            max_size_bytes: MAX_MEMPOOL_BYTES,
+            max_age_secs: MEMPOOL_EXPIRY_SECS,
+        }
+    }
+
+    /// Taux de frais minimum (satoshis/byte) exigé, qui monte avec le remplissage
+    pub fn min_fee_rate(&self) -> u64 {
+        self.fees.min_fee_rate(self.entries.len())
+    }
+
+    pub fn add_transaction(&mut self, tx: Transaction, fee: u64) -> Result<()> {
+        self.add_transaction_at(tx, fee, unix_now())
+    }
+
+    /// Comme `add_transaction`, en conservant la date d'entrée d'origine
+    /// (transaction remise en attente ou rechargée depuis le disque)
+    pub(crate) fn add_transaction_at(&mut self, tx: Transaction, fee: u64, added_at: i64) -> Result<()> {
+        if self.contains(&tx.id) {
+            return Err(anyhow!("Transaction already in mempool"));
+        }
+
+        let size = bincode::serialize(&tx)
+            .map_err(|e| anyhow!("Failed to serialize transaction: {}", e))?
+            .len();
+
+        if fee < self.fees.min_relay_fee {
+            return Err(anyhow!("Fee below minimum relay fee"));
+        }
+
+        let min_fee = (size as u64).saturating_mul(self.min_fee_rate());
+        if fee < min_fee {
+            return Err(anyhow!(
+                "Fee rate below mempool floor: paid {}, required {} ({} sat/byte)",
+                fee, min_fee, self.min_fee_rate()
+            ));
+        }
+
+        let entry = MempoolEntry { tx, fee, size, added_at };
+        let replaced = self.take_replaced_by(&entry)?;
+        if let Err(e) = self.make_room_for(&entry) {
+            self.entries.extend(replaced);
+            return Err(e);
+        }
+        for old in &replaced {
+            log::info!("🔁 Tx {} replaced by {} (replace-by-fee)", old.tx.id, entry.tx.id);
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Retirer les transactions qui dépensent les mêmes inputs que `entry` (replace-by-fee).
+    /// Refusé, sans rien retirer, si `entry` ne paie pas un taux nettement supérieur à chacune.
+    fn take_replaced_by(&mut self, entry: &MempoolEntry) -> Result<Vec<MempoolEntry>> {
+        if let Some(conflict) = self.entries.iter()
+            .find(|pending| pending.conflicts_with(&entry.tx) && !entry.outbids(pending))
+        {
+            return Err(anyhow!(
+                "Replacement fee rate too low: conflicts with tx {} ({} sat/byte), at least {}% more required",
+                conflict.tx.id, conflict.fee_rate(), MIN_RBF_FEE_RATE_BUMP_PERCENT
+            ));
+        }
+
+        let (replaced, kept) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|pending| pending.conflicts_with(&entry.tx));
+        self.entries = kept;
+        Ok(replaced)
+    }
+
+    /// Évincer les transactions au plus faible taux de frais jusqu'à ce que `entry` tienne
+    /// dans `max_size_bytes` ; refusée si elle paie elle-même moins que les évincées
+    fn make_room_for(&mut self, entry: &MempoolEntry) -> Result<()> {
+        while self.total_size().saturating_add(entry.size) > self.max_size_bytes {
+            let cheapest = self.entries.iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| a.cmp_priority(b))
+                .map(|(position, cheapest)| (position, cheapest.cmp_fee_rate(entry) == Ordering::Less));
+
+            match cheapest {
+                Some((position, true)) => {
+                    let evicted = self.entries.remove(position);
+                    log::info!("🗑️  Mempool full, evicted tx {} ({} bytes)", evicted.tx.id, evicted.size);
+                },
+                _ => return Err(anyhow!("Mempool full: fee rate too low to evict pending transactions")),
+            }
+        }
+        Ok(())
+    }
+
+    /// Retirer les transactions entrées il y a plus de `max_age_secs` ; retourne le nombre évincé
+    pub fn expire(&mut self, max_age_secs: u64) -> usize {
+        let cutoff = unix_now().saturating_sub(max_age_secs as i64);
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.added_at >= cutoff);
+
+        let evicted = before - self.entries.len();
+        if evicted > 0 {
+            log::info!("🗑️  {} expired transactions evicted from the mempool", evicted);
+        }
+        evicted
+    }
+
+    /// Taille cumulée (octets sérialisés) des transactions en attente
+    pub fn total_size(&self) -> usize {
+        self.entries.iter().map(|entry| entry.size).sum()
+    }
+
+    /// Mémoriser le txid d'une transaction acceptée ; faux s'il a déjà été vu récemment.
+    /// Évite de revalider et de relayer en boucle la même transaction. Le txid n'engage
+    /// pas les signatures : une transaction refusée ne doit pas être marquée, sinon une
+    /// copie mal signée diffusée la première masquerait l'originale.
+    pub fn mark_seen(&mut self, txid: &str) -> bool {
+        if !self.recent_txids.insert(txid.to_string()) {
+            return false;
+        }
+
+        self.recent_order.push_back(txid.to_string());
+        if self.recent_order.len() > RECENT_TXIDS_CAPACITY {
+            if let Some(oldest) = self.recent_order.pop_front() {
+                self.recent_txids.remove(&oldest);
+            }
+        }
+        true
+    }
+
+    /// Txid déjà accepté récemment (voir `mark_seen`)
+    pub fn is_seen(&self, txid: &str) -> bool {
+        self.recent_txids.contains(txid)
+    }
+
+    pub fn contains(&self, txid: &str) -> bool {
+        self.entries.iter().any(|e| e.tx.id == txid)
+    }
+
+    pub fn remove(&mut self, txid: &str) -> Option<MempoolEntry> {
+        let position = self.entries.iter().position(|e| e.tx.id == txid)?;
+        Some(self.entries.remove(position))
+    }
+
+    pub fn entries(&self) -> &[MempoolEntry] {
+        &self.entries
+    }
+
+    /// Transactions dans l'ordre d'inclusion (voir `MempoolEntry::cmp_priority`)
+    pub fn entries_by_fee_rate(&self) -> Vec<&MempoolEntry> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|a, b| a.cmp_priority(b));
+        entries
+    }
+
+    pub fn transactions(&self) -> Vec<Transaction> {
+        self.entries.iter().map(|e| e.tx.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Sauvegarder les transactions en attente (JSON)
+    pub fn save(&self, path: &str) -> Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, serde_json::to_string_pretty(&self.entries)?)?;
+        Ok(())
+    }
+
+    /// Recharger un mempool sauvegardé, sans revalidation contre la chaîne
+    /// (voir `Blockchain::load_mempool`). Un fichier absent donne un mempool vide.
+    pub fn load(path: &str) -> Result<Self> {
+        let mut mempool = Mempool::new();
+        if Path::new(path).exists() {
+            mempool.entries = serde_json::from_str(&fs::read_to_string(path)?)?;
+        }
+        Ok(mempool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::{TxInput, TxOutput};
+
+    /// Transaction qui dépense un output propre à `value` : pas de conflit entre elles
+    fn sample_tx(value: u64) -> Transaction {
+        Transaction::new(
+            vec![TxInput {
+                prev_tx_id: format!("{:064x}", value),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![TxOutput {
+                value,
+                address: "AUR1Recipient".to_string(),
+            }],
+        )
+    }
+
+    fn tx_size(tx: &Transaction) -> u64 {
+        bincode::serialize(tx).unwrap().len() as u64
+    }
+
+    #[test]
+    fn test_accepts_fee_above_floor() {
+        let mut mempool = Mempool::new();
+        let tx = sample_tx(1);
+        let fee = tx_size(&tx) * mempool.min_fee_rate();
+
+        assert!(mempool.add_transaction(tx, fee).is_ok());
+        assert_eq!(mempool.len(), 1);
+    }
+
+    #[test]
+    fn test_rejects_duplicate() {
+        let mut mempool = Mempool::new();
+        let tx = sample_tx(1);
+        let fee = tx_size(&tx) * mempool.min_fee_rate();
+
+        mempool.add_transaction(tx.clone(), fee).unwrap();
+        assert!(mempool.add_transaction(tx, fee).is_err());
+    }
+
+    #[test]
+    fn test_floor_rises_with_congestion() {
+        let mut mempool = Mempool::new();
+        let normal_rate = mempool.min_fee_rate();
+
+        // Dépasser le seuil de congestion modérée (1000 transactions)
+        for i in 0..1001 {
+            let tx = sample_tx(i);
+            let fee = tx_size(&tx) * 10 * normal_rate;
+            mempool.add_transaction(tx, fee).unwrap();
+        }
+
+        assert!(mempool.min_fee_rate() > normal_rate);
+
+        // Payer le taux normal ne suffit plus
+        let cheap = sample_tx(1_000_000);
+        let cheap_fee = tx_size(&cheap) * normal_rate;
+        assert!(mempool.add_transaction(cheap, cheap_fee).is_err());
+
+        // Payer le nouveau plancher passe
+        let priced = sample_tx(2_000_000);
+        let priced_fee = tx_size(&priced) * mempool.min_fee_rate();
+        assert!(mempool.add_transaction(priced, priced_fee).is_ok());
+    }
+
+    #[test]
+    fn test_old_transactions_expire() {
+        let mut mempool = Mempool::new();
+        for value in [1, 2, 3] {
+            let tx = sample_tx(value);
+            let fee = tx_size(&tx) * mempool.min_fee_rate();
+            mempool.add_transaction(tx, fee).unwrap();
+        }
+        mempool.entries[0].added_at -= 3 * 3600;
+        mempool.entries[1].added_at -= 3600;
+
+        assert_eq!(mempool.expire(2 * 3600), 1);
+        assert_eq!(mempool.len(), 2);
+        assert!(!mempool.contains(&sample_tx(1).id));
+        assert_eq!(mempool.expire(2 * 3600), 0);
+    }
+
+    #[test]
+    fn test_size_cap_evicts_lowest_fee_rate() {
+        let mut mempool = Mempool::new();
+        let size = tx_size(&sample_tx(1));
+        let rate = mempool.min_fee_rate();
+        mempool.max_size_bytes = 3 * size as usize;
+
+        for (value, multiplier) in [(1, 3), (2, 1), (3, 2)] {
+            mempool.add_transaction(sample_tx(value), size * rate * multiplier).unwrap();
+        }
+        assert_eq!(mempool.total_size(), mempool.max_size_bytes);
+
+        // Mieux payée : prend la place de la moins chère (valeur 2)
+        mempool.add_transaction(sample_tx(4), size * rate * 5).unwrap();
+        assert_eq!(mempool.len(), 3);
+        assert!(!mempool.contains(&sample_tx(2).id));
+
+        // Moins bien payée que toutes celles en attente : refusée
+        let err = mempool.add_transaction(sample_tx(5), size * rate).unwrap_err();
+        assert!(err.to_string().contains("Mempool full"), "{}", err);
+        assert!(mempool.contains(&sample_tx(3).id));
+    }
+
+    /// Dépense de l'output `a…a:0` vers `outputs` destinataires : la taille croît avec `outputs`
+    fn spend_of_shared_input(outputs: usize, value: u64) -> Transaction {
+        Transaction::new(
+            vec![TxInput {
+                prev_tx_id: "a".repeat(64),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            (0..outputs)
+                .map(|i| TxOutput { value: value + i as u64, address: format!("AUR1Recipient{}", i) })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_inclusion_ordered_by_fee_rate_not_fee() {
+        let mut mempool = Mempool::new();
+        let rate = mempool.min_fee_rate();
+
+        // La plus grosse paie le plus de frais mais le plus faible taux
+        let large = Transaction::new(
+            vec![TxInput { prev_tx_id: "b".repeat(64), output_index: 0, signature: String::new(), public_key: String::new() }],
+            (0..20).map(|i| TxOutput { value: i, address: format!("AUR1Many{}", i) }).collect(),
+        );
+        let small = sample_tx(1);
+        let medium = sample_tx(2);
+        let twin = sample_tx(3);
+        assert!(tx_size(&large) > 2 * tx_size(&small));
+
+        mempool.add_transaction(large.clone(), tx_size(&large) * rate * 2).unwrap();
+        mempool.add_transaction(small.clone(), tx_size(&small) * rate * 4).unwrap();
+        mempool.add_transaction(twin.clone(), tx_size(&twin) * rate * 3).unwrap();
+        mempool.add_transaction(medium.clone(), tx_size(&medium) * rate * 3).unwrap();
+        assert!(mempool.entries()[0].fee > mempool.entries()[1].fee);
+
+        // À taux égal, la plus ancienne puis le plus petit txid : ordre déterministe
+        mempool.entries[2].added_at = mempool.entries[3].added_at;
+        let (first_twin, second_twin) = if twin.id < medium.id { (&twin, &medium) } else { (&medium, &twin) };
+        let ids: Vec<&str> = mempool.entries_by_fee_rate().iter().map(|entry| entry.tx.id.as_str()).collect();
+        assert_eq!(ids, vec![small.id.as_str(), first_twin.id.as_str(), second_twin.id.as_str(), large.id.as_str()]);
+    }
+
+    #[test]
+    fn test_replace_by_fee() {
+        let mut mempool = Mempool::new();
+        let rate = mempool.min_fee_rate();
+        let original = spend_of_shared_input(1, 1_000);
+        let original_fee = tx_size(&original) * rate * 2;
+        mempool.add_transaction(original.clone(), original_fee).unwrap();
+
+        // Même taux ou presque : refusée, l'originale reste
+        let timid = spend_of_shared_input(1, 2_000);
+        let err = mempool.add_transaction(timid.clone(), original_fee + 1).unwrap_err();
+        assert!(err.to_string().contains("Replacement fee rate too low"), "{}", err);
+        assert!(mempool.contains(&original.id));
+        assert!(!mempool.contains(&timid.id));
+
+        // Plus grosse et plus de frais en valeur absolue, mais pas assez par byte
+        let bloated = spend_of_shared_input(10, 3_000);
+        assert!(mempool.add_transaction(bloated, original_fee * 2).is_err());
+        assert!(mempool.contains(&original.id));
+
+        // Taux nettement supérieur : remplace l'originale
+        let bumped = spend_of_shared_input(1, 4_000);
+        mempool.add_transaction(bumped.clone(), original_fee * 2).unwrap();
+        assert_eq!(mempool.len(), 1);
+        assert!(mempool.contains(&bumped.id));
+        assert!(!mempool.contains(&original.id));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::blockchain::Block;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Nombre maximal de blocs orphelins conservés
+pub const MAX_ORPHAN_BLOCKS: usize = 100;
+/// Durée de conservation d'un orphelin dont le parent n'arrive pas
+pub const ORPHAN_EXPIRY: Duration = Duration::from_secs(20 * 60);
+
+#[derive(Debug, Clone)]
+struct OrphanEntry {
+    block: Block,
+    received: Instant,
+}
+
+/// Blocs reçus avant leur parent, en attente de pouvoir se raccrocher à la chaîne
+#[derive(Debug, Clone)]
+pub struct OrphanPool {
+    orphans: HashMap<String, OrphanEntry>,
+    pub max_orphans: usize,
+    pub expiry: Duration,
+}
+
+impl Default for OrphanPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrphanPool {
+    pub fn new() -> Self {
+        OrphanPool {
+            orphans: HashMap::new(),
+            max_orphans: MAX_ORPHAN_BLOCKS,
+            expiry: ORPHAN_EXPIRY,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.orphans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orphans.is_empty()
+    }
+
+    pub fn contains(&self, hash: &str) -> bool {
+        self.orphans.contains_key(hash)
+    }
+
+    /// Mettre un bloc de côté ; le plus ancien est évincé quand le pool est plein.
+    /// Retourne `false` si le bloc y était déjà.
+    pub fn add(&mut self, block: Block) -> bool {
+        self.expire();
+        if self.contains(&block.hash) || self.max_orphans == 0 {
+            return false;
+        }
+
+        if self.orphans.len() >= self.max_orphans {
+            let oldest = self.orphans.iter()
+                .min_by_key(|(_, entry)| entry.received)
+                .map(|(hash, _)| hash.clone());
+            if let Some(hash) = oldest {
+                self.orphans.remove(&hash);
+            }
+        }
+
+        self.orphans.insert(block.hash.clone(), OrphanEntry { block, received: Instant::now() });
+        true
+    }
+
+    /// Retirer les orphelins plus vieux que `expiry`
+    pub fn expire(&mut self) {
+        let expiry = self.expiry;
+        self.orphans.retain(|_, entry| entry.received.elapsed() < expiry);
+    }
+
+    /// Extraire les orphelins dont le parent est `parent_hash`
+    pub fn take_children(&mut self, parent_hash: &str) -> Vec<Block> {
+        let hashes: Vec<String> = self.orphans.values()
+            .filter(|entry| entry.block.previous_hash == parent_hash)
+            .map(|entry| entry.block.hash.clone())
+            .collect();
+
+        hashes.iter()
+            .filter_map(|hash| self.orphans.remove(hash))
+            .map(|entry| entry.block)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(index: u64, previous_hash: &str) -> Block {
+        let mut block = Block::new(index, Vec::new(), previous_hash.to_string(), 1, format!("AUR1Miner{}", index));
+        block.hash = block.calculate_hash();
+        block
+    }
+
+    #[test]
+    fn test_pool_capped_and_expired() {
+        let mut pool = OrphanPool::new();
+        pool.max_orphans = 2;
+
+        let first = block(5, "aa");
+        assert!(pool.add(first.clone()));
+        assert!(!pool.add(first.clone()));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(pool.add(block(6, "bb")));
+        assert!(pool.add(block(7, "bb")));
+
+        // Le plus ancien a laissé sa place
+        assert_eq!(pool.len(), 2);
+        assert!(!pool.contains(&first.hash));
+        assert_eq!(pool.take_children("bb").len(), 2);
+        assert!(pool.is_empty());
+
+        pool.add(block(8, "cc"));
+        pool.expiry = Duration::ZERO;
+        pool.expire();
+        assert!(pool.is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+/// Outputs engagés par une signature d'input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigHashBase {
+    /// Tous les outputs
+    All,
+    /// Aucun output : n'importe qui peut les modifier
+    None,
+    /// Seulement l'output de même index que l'input signé
+    Single,
+}
+
+/// Type de hash de signature (SIGHASH), porté par le dernier octet de la signature
+/// d'un input. Une signature compacte sans cet octet reste le format historique :
+/// tout est engagé, sans octet de type dans le hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigHashType {
+    pub base: SigHashBase,
+    /// Seul l'input signé est engagé : d'autres peuvent ajouter leurs inputs
+    pub anyone_can_pay: bool,
+}
+
+pub const SIGHASH_ALL: u8 = 0x01;
+pub const SIGHASH_NONE: u8 = 0x02;
+pub const SIGHASH_SINGLE: u8 = 0x03;
+pub const SIGHASH_ANYONECANPAY: u8 = 0x80;
+
+impl SigHashType {
+    pub const ALL: SigHashType = SigHashType { base: SigHashBase::All, anyone_can_pay: false };
+    pub const NONE: SigHashType = SigHashType { base: SigHashBase::None, anyone_can_pay: false };
+    pub const SINGLE: SigHashType = SigHashType { base: SigHashBase::Single, anyone_can_pay: false };
+
+    pub fn with_anyone_can_pay(self) -> Self {
+        SigHashType { anyone_can_pay: true, ..self }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        let base = match self.base {
+            SigHashBase::All => SIGHASH_ALL,
+            SigHashBase::None => SIGHASH_NONE,
+            SigHashBase::Single => SIGHASH_SINGLE,
+        };
+        if self.anyone_can_pay { base | SIGHASH_ANYONECANPAY } else { base }
+    }
+
+    /// `None` pour un octet qui ne correspond à aucun type connu
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        let base = match byte & !SIGHASH_ANYONECANPAY {
+            SIGHASH_ALL => SigHashBase::All,
+            SIGHASH_NONE => SigHashBase::None,
+            SIGHASH_SINGLE => SigHashBase::Single,
+            _ => return None,
+        };
+        Some(SigHashType { base, anyone_can_pay: byte & SIGHASH_ANYONECANPAY != 0 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_round_trip() {
+        for byte in [0x01, 0x02, 0x03, 0x81, 0x82, 0x83] {
+            assert_eq!(SigHashType::from_byte(byte).unwrap().to_byte(), byte);
+        }
+        assert_eq!(SigHashType::SINGLE.with_anyone_can_pay().to_byte(), 0x83);
+        assert!(SigHashType::from_byte(0x00).is_none());
+        assert!(SigHashType::from_byte(0x84).is_none());
+    }
+}
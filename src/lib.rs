@@ -2,20 +2,35 @@ pub mod blockchain {
     pub mod block;
     pub mod chain;
     pub mod genesis;
+    pub mod fees;
+    pub mod mempool;
+    pub mod orphans;
+    pub mod sighash;
     
-    pub use block::{Block, Transaction, TxInput, TxOutput};
-    pub use chain::Blockchain;
+    pub use block::{Block, BlockHeader, Transaction, TxInput, TxOutput};
+    pub use chain::{BlockOutcome, BlockRange, Blockchain, ChainTip, Utxo};
     pub use genesis::{create_genesis_block, calculate_block_reward};
+    pub use mempool::Mempool;
+    pub use orphans::OrphanPool;
+    pub use sighash::SigHashType;
 }
 
 pub mod security {
     pub mod validator;
     pub mod monitor;
     pub mod protection;
+    pub mod logger;
+    pub mod signature_validator;
+    pub mod rate_limiter;
+    pub mod reputation;
     
     pub use validator::SecurityValidator;
     pub use monitor::SecurityMonitor;
     pub use protection::NetworkProtection;
+    pub use logger::{EventFilter, SecurityEvent, SecurityLogger, SecurityEventType};
+    pub use signature_validator::SignatureValidator;
+    pub use rate_limiter::RateLimiter;
+    pub use reputation::{PeerEvent, PeerReputation};
 }
 
 pub mod network;
@@ -1,19 +1,58 @@
 use tokio::sync::RwLock;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use auriumchain::blockchain::Blockchain;
-use auriumchain::rpc::server::start_rpc_server;
-use auriumchain::p2p::{PeerManager, SyncManager, NetworkSecurity, P2PServer};
+use auriumchain::rpc::server::{start_rpc_server, CorsPolicy, RpcState};
+use auriumchain::mining::{cancel_on_new_tip, Miner, EnergyTracker};
+use auriumchain::utils::config::{GenesisConfig, Network};
+use auriumchain::utils::metrics::Metrics;
+use auriumchain::wallet::{KdfParams, NodeWallet, SecureWallet};
+use auriumchain::blockchain::genesis::create_genesis_block_with_config;
+use auriumchain::security::{SecurityLogger, SecurityEventType, SecurityMonitor};
+use auriumchain::p2p::{PeerDirection, PeerManager, SyncManager, NetworkSecurity, P2PServer, ConnectionTimeouts, Heartbeat, TxRelay, HeaderSync};
 use clap::Parser;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+/// Variable d'environnement lue par `--encrypt-chain`
+const CHAIN_PASSPHRASE_ENV: &str = "AURIUMCHAIN_PASSPHRASE";
 
 #[derive(Parser, Debug)]
 #[command(name = "AuriumChain")]
 struct Args {
-    #[arg(short = 'p', long, default_value = "3001")]
-    port: u16,
+    /// Réseau : mainnet, testnet ou devnet
+    #[arg(long, default_value = "mainnet")]
+    network: Network,
+    
+    /// Port P2P (défaut selon le réseau : 3001 mainnet)
+    #[arg(short = 'p', long)]
+    port: Option<u16>,
+    
+    /// Port RPC (défaut selon le réseau : 8001 mainnet)
+    #[arg(short = 'r', long = "rpc-port")]
+    rpc_port: Option<u16>,
+    
+    /// Adresse d'écoute RPC (loopback par défaut : RPC local uniquement). La
+    /// synchronisation HTTP entre nœuds distants demande une adresse joignable (0.0.0.0)
+    #[arg(long, default_value = "127.0.0.1")]
+    rpc_bind: IpAddr,
     
-    #[arg(short = 'r', long = "rpc-port", default_value = "8001")]
-    rpc_port: u16,
+    /// Origines autorisées à lire le RPC depuis un navigateur, séparées par des virgules
+    /// (toutes par défaut)
+    #[arg(long, value_delimiter = ',')]
+    rpc_cors_origins: Vec<String>,
+    
+    /// Aucun en-tête CORS sur les réponses RPC
+    #[arg(long)]
+    rpc_disable_cors: bool,
+    
+    /// Refuser les routes RPC qui modifient l'état (/transaction, /new_block, /submitblock, /wallet/*)
+    #[arg(long)]
+    rpc_read_only: bool,
+    
+    /// Adresse d'écoute P2P (toutes les interfaces par défaut)
+    #[arg(long, default_value = "0.0.0.0")]
+    p2p_bind: IpAddr,
     
     #[arg(short = 'g', long)]
     genesis: bool,
@@ -26,6 +65,76 @@ struct Args {
     
     #[arg(long, default_value = "/tmp/auriumchain.json")]
     data_file: String,
+    
+    /// Chiffrer le fichier de chaîne (AES-256-GCM, clé Argon2). Mot de passe lu dans
+    /// `AURIUMCHAIN_PASSPHRASE`, sinon demandé au démarrage
+    #[arg(long)]
+    encrypt_chain: bool,
+    
+    #[arg(long, default_value = "8")]
+    max_fanout: usize,
+    
+    /// Nombre maximal de peers entrants (connexions reçues)
+    #[arg(long, default_value = "8")]
+    max_inbound: usize,
+    
+    /// Nombre maximal de peers sortants (choisis par le nœud), jamais occupés par des entrants
+    #[arg(long, default_value = "8")]
+    max_outbound: usize,
+    
+    /// Pause (s) entre deux synchronisations périodiques avec les peers
+    #[arg(long, default_value_t = auriumchain::p2p::sync::DEFAULT_SYNC_INTERVAL_SECS)]
+    sync_interval_secs: u64,
+    
+    /// Blocs demandés par requête lors d'une synchronisation
+    #[arg(long, default_value_t = auriumchain::p2p::server::MAX_BLOCKS_PER_REQUEST)]
+    sync_batch_size: usize,
+    
+    /// Durée (s) minimum entre deux blocs minés par ce nœud
+    #[arg(long, default_value = "30")]
+    block_interval_secs: u64,
+    
+    /// Fichier TOML décrivant le genesis (testnet/devnet) ; mainnet par défaut
+    #[arg(long)]
+    genesis_config: Option<String>,
+    
+    /// Répertoire du certificat TLS persistant (identité du nœud)
+    #[arg(long, default_value = "/tmp/auriumchain_identity")]
+    identity_dir: String,
+    
+    /// Durée (s) sans nouvelles d'un peer avant éviction
+    #[arg(long, default_value = "300")]
+    peer_timeout: u64,
+    
+    /// Durée (s) sans message d'un peer connecté avant fermeture de la connexion
+    #[arg(long, default_value_t = auriumchain::p2p::server::DEFAULT_IDLE_TIMEOUT_SECS)]
+    p2p_idle_timeout: u64,
+    
+    /// Durée (s) maximum pour écrire une réponse à un peer
+    #[arg(long, default_value_t = auriumchain::p2p::server::DEFAULT_WRITE_TIMEOUT_SECS)]
+    p2p_write_timeout: u64,
+    
+    #[arg(long, default_value = "/tmp/auriumchain_mempool.json")]
+    mempool_file: String,
+    
+    #[arg(long, default_value = "/tmp/auriumchain_security.log")]
+    security_log: String,
+    
+    /// Synchronisation initiale par en-têtes (P2P TLS) plutôt que par blocs complets (HTTP)
+    #[arg(long)]
+    headers_first: bool,
+    
+    /// Bans P2P persistés entre deux démarrages
+    #[arg(long, default_value = "/tmp/auriumchain_bans.json")]
+    bans_file: String,
+    
+    /// Servir `/wallet/unlock` et `/wallet/send` avec ce fichier `.secure.wallet` (sensible : désactivé par défaut)
+    #[arg(long)]
+    wallet_rpc: Option<String>,
+    
+    /// Durée (s) pendant laquelle le wallet reste déverrouillé
+    #[arg(long, default_value_t = auriumchain::wallet::node_wallet::DEFAULT_UNLOCK_TIMEOUT_SECS)]
+    wallet_unlock_timeout: u64,
 }
 
 #[tokio::main]
@@ -33,30 +142,58 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init();
     
     let args = Args::parse();
+    let network = args.network;
+    let port = args.port.unwrap_or(network.default_p2p_port());
+    let rpc_port = args.rpc_port.unwrap_or(network.default_rpc_port());
+    let p2p_addr = SocketAddr::new(args.p2p_bind, port);
+    let rpc_addr = SocketAddr::new(args.rpc_bind, rpc_port);
+    if !args.rpc_bind.is_loopback() {
+        println!("⚠️  RPC exposed on {} : reachable from other hosts", args.rpc_bind);
+    }
     
     println!("AuriumChain Node - TLS P2P Edition");
-    println!("Port P2P:  {}", args.port);
-    println!("Port RPC:  {}", args.rpc_port);
+    println!("Network:   {}", network);
+    println!("Port P2P:  {}", port);
+    println!("Port RPC:  {}", rpc_port);
     println!("Genesis:   {}", args.genesis);
     println!("Mining:    {}", args.mining);
     println!("Data file: {}", args.data_file);
     
     let wallet_addr = "AUR3ZnxihprBGetUiMoHwRWZbcyU94TzP52Jkk".to_string();
     
+    // Genesis et paramètres de consensus : construits une seule fois, partagés par tout le nœud
+    let mut genesis_config = match &args.genesis_config {
+        Some(path) => GenesisConfig::load(path)?,
+        None => network.genesis_config(),
+    };
+    genesis_config.network = network;
+    let params = genesis_config.consensus_params();
+    
+    // Mot de passe du fichier de chaîne : toutes les sauvegardes seront chiffrées
+    let chain_passphrase = if args.encrypt_chain {
+        let passphrase = match std::env::var(CHAIN_PASSPHRASE_ENV) {
+            Ok(passphrase) => passphrase,
+            Err(_) => rpassword::prompt_password("Chain file passphrase: ")?,
+        };
+        if passphrase.is_empty() {
+            return Err("--encrypt-chain requires a non-empty passphrase".into());
+        }
+        Some(zeroize::Zeroizing::new(passphrase))
+    } else {
+        None
+    };
+    
     // Charger ou créer la blockchain
     let blockchain = if args.genesis {
         println!("Creating new Genesis blockchain...");
-        let mut chain = Blockchain::new();
+        let mut chain = Blockchain::with_params(params.clone());
+        if let Some(passphrase) = &chain_passphrase {
+            chain.encrypt_storage(passphrase, KdfParams::default())?;
+        }
         
         // Créer le bloc genesis
-        let genesis = auriumchain::blockchain::Block::new(
-            0,
-            vec![],
-            "0".to_string(),
-            4,
-            wallet_addr.clone(),
-        );
-        chain.chain.push(genesis.clone());
+        let genesis = create_genesis_block_with_config(&genesis_config, &params);
+        chain.chain.push(genesis);
         
         if let Err(e) = chain.save_to_file(&args.data_file) {
             eprintln!("Error saving blockchain: {}", e);
@@ -68,47 +205,89 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         chain
     } else {
         println!("Loading blockchain from {}...", args.data_file);
-        match Blockchain::load_from_file(&args.data_file) {
+        let loaded = match &chain_passphrase {
+            Some(passphrase) => Blockchain::load_from_file_encrypted(&args.data_file, passphrase, params.clone()),
+            None => Blockchain::load_from_file_with_params(&args.data_file, params.clone()),
+        };
+        match loaded {
             Ok(chain) => {
+                // Un genesis falsifié ne doit pas être remplacé en silence : le nœud refuse de démarrer
+                chain.verify_genesis(&genesis_config)
+                    .map_err(|e| format!("Refusing to start from {}: {}", args.data_file, e))?;
                 println!("Blockchain loaded: {} blocks from {}", chain.chain.len(), args.data_file);
                 println!("Loaded {} blocks", chain.chain.len());
                 chain
             },
-            Err(e) => {
-                println!("Error loading blockchain: {}", e);
-                println!("Creating new blockchain...");
-                let mut chain = Blockchain::new();
-                
-                // Créer le bloc genesis par défaut
-                let genesis = auriumchain::blockchain::Block::new(
-                    0,
-                    vec![],
-                    "0".to_string(),
-                    4,
-                    wallet_addr.clone(),
-                );
-                chain.chain.push(genesis);
-                chain
-            }
+            // Fichier illisible ou corrompu : repartir d'un genesis l'écraserait à la première
+            // sauvegarde. Le nœud refuse de démarrer et indique comment réparer.
+            // `chain_tool` ne lit pas les fichiers chiffrés (mot de passe erroné le plus souvent).
+            Err(e) if chain_passphrase.is_some() => return Err(format!(
+                "Refusing to start from encrypted chain file {}: {}", args.data_file, e
+            ).into()),
+            Err(e) => return Err(format!(
+                "Refusing to start from {}: {}. Run `chain_tool --data-file {} --network {} verify` to inspect it, \
+                 then `repair` to truncate it to its last valid block (the original is kept as .bak)",
+                args.data_file, e, args.data_file, network
+            ).into()),
         }
     };
     
+    let mut blockchain = blockchain;
+    match blockchain.load_mempool(&args.mempool_file) {
+        Ok(count) => println!("Mempool restored: {} pending transactions", count),
+        Err(e) => eprintln!("Error loading mempool: {}", e),
+    }
+    
     let blockchain = Arc::new(RwLock::new(blockchain));
-    let peer_manager = Arc::new(PeerManager::new(10));
+    let security_logger = Arc::new(SecurityLogger::with_file(&args.security_log));
+    security_logger.log_event(
+        SecurityEventType::NodeStarted,
+        "node",
+        &format!("network={} p2p={} rpc={} mining={}", network, p2p_addr, rpc_addr, args.mining),
+    );
+    
+    // Drapeau d'arrêt observé par les tâches de mining et de synchronisation
+    let shutdown = Arc::new(AtomicBool::new(false));
+    
+    let peer_manager = Arc::new(PeerManager::new(args.max_inbound, args.max_outbound));
     
     // Initialiser la sécurité TLS
-    let security = Arc::new(NetworkSecurity::new()?);
-    println!("TLS security initialized");
+    let security = Arc::new(NetworkSecurity::load_or_create(&args.identity_dir)?);
+    println!("TLS security initialized (node {})", security.fingerprint);
     
-    let sync_manager = Arc::new(SyncManager::new(blockchain.clone(), peer_manager.clone()));
+    let security_monitor = Arc::new(Mutex::new(SecurityMonitor::new()));
+    let metrics = Arc::new(Metrics::new());
     
     // Démarrer serveur P2P TLS
-    let p2p_server = P2PServer::new(
+    let mut p2p_server = P2PServer::new(
         blockchain.clone(),
         peer_manager.clone(),
         security.clone(),
-        SocketAddr::from(([0, 0, 0, 0], args.port))
+        p2p_addr
     );
+    p2p_server.security_logger = security_logger.clone();
+    p2p_server.timeouts = ConnectionTimeouts {
+        idle: Duration::from_secs(args.p2p_idle_timeout),
+        write: Duration::from_secs(args.p2p_write_timeout),
+    };
+    
+    let mut sync_manager = SyncManager::new(blockchain.clone(), peer_manager.clone());
+    sync_manager.max_fanout = args.max_fanout;
+    sync_manager.monitor = security_monitor.clone();
+    sync_manager.metrics = metrics.clone();
+    sync_manager.reputation = Some(p2p_server.reputation.clone());
+    sync_manager.rate_limiter = Some(p2p_server.rate_limiter.clone());
+    sync_manager.sync_interval = Duration::from_secs(args.sync_interval_secs);
+    sync_manager.batch_size = args.sync_batch_size.max(1);
+    sync_manager.data_file = args.data_file.clone();
+    let sync_manager = Arc::new(sync_manager);
+    let rate_limiter = p2p_server.rate_limiter.clone();
+    if let Ok(mut limiter) = rate_limiter.lock() {
+        match limiter.load_bans(&args.bans_file) {
+            Ok(count) => println!("Bans restored: {} banned IPs", count),
+            Err(e) => eprintln!("Error loading bans: {}", e),
+        }
+    }
     
     tokio::spawn(async move {
         if let Err(e) = p2p_server.start().await {
@@ -119,110 +298,161 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Ajouter peer si spécifié
     if let Some(peer_addr) = args.peer {
         if let Ok(addr) = peer_addr.parse() {
-            peer_manager.add_peer(addr).await;
+            peer_manager.add_peer(addr, PeerDirection::Outbound).await;
             println!("Added peer: {}", addr);
         }
     }
     
+    // Heartbeat : ping des peers et éviction des peers muets
+    let mut heartbeat = Heartbeat::new(peer_manager.clone(), blockchain.clone(), security.clone());
+    heartbeat.max_age_secs = args.peer_timeout;
+    heartbeat.security_logger = security_logger.clone();
+    let heartbeat = Arc::new(heartbeat);
+    let heartbeat_task = heartbeat.clone();
+    let shutdown_heartbeat = shutdown.clone();
+    tokio::spawn(async move {
+        heartbeat_task.run(shutdown_heartbeat).await;
+    });
+    
     // **NOUVELLE FONCTIONNALITÉ : Synchronisation automatique périodique**
     let sync_manager_periodic = sync_manager.clone();
-    let peer_manager_sync = peer_manager.clone();
+    let shutdown_sync = shutdown.clone();
     tokio::spawn(async move {
-        loop {
-            // Attendre 30 secondes avant chaque cycle de synchronisation
-            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
-            
-            let peers = peer_manager_sync.get_all_peers().await;
-            if !peers.is_empty() {
-                println!("Starting periodic sync with {} peers...", peers.len());
-                
-                for peer_addr in peers {
-                    match sync_manager_periodic.sync_with_peer(peer_addr).await {
-                        Ok(synced) => {
-                            if synced {
-                                println!("✅ Synchronized new blocks from peer: {}", peer_addr);
-                            }
-                        },
-                        Err(e) => {
-                            println!("❌ Sync failed with peer {}: {}", peer_addr, e);
-                        }
-                    }
-                }
-            }
-        }
+        sync_manager_periodic.run(shutdown_sync).await;
     });
     
+    let energy_tracker = Arc::new(Mutex::new(EnergyTracker::new()));
+    let energy_mining = energy_tracker.clone();
+    let monitor_mining = security_monitor.clone();
+    let metrics_mining = metrics.clone();
+    
     let blockchain_rpc = blockchain.clone();
     let blockchain_mining = blockchain.clone();
     let data_file_mining = args.data_file.clone();
     let sync_manager_mining = sync_manager.clone();
     
+    // Transactions soumises en RPC : relayées aux peers
+    let mut tx_relay = TxRelay::new(peer_manager.clone(), blockchain.clone(), security.clone());
+    tx_relay.max_fanout = args.max_fanout;
+    let mut rpc_state = RpcState::new(blockchain_rpc, energy_tracker, security_monitor);
+    rpc_state.tx_relay = Some(Arc::new(tx_relay));
+    rpc_state.rate_limiter = rate_limiter.clone();
+    rpc_state.security_logger = security_logger.clone();
+    rpc_state.sync_manager = Some(sync_manager.clone());
+    rpc_state.data_file = args.data_file.clone();
+    rpc_state.peer_manager = Some(peer_manager.clone());
+    rpc_state.metrics = metrics.clone();
+    rpc_state.read_only = args.rpc_read_only;
+    rpc_state.cors = if args.rpc_disable_cors {
+        CorsPolicy::Disabled
+    } else if !args.rpc_cors_origins.is_empty() {
+        CorsPolicy::Origins(args.rpc_cors_origins.clone())
+    } else {
+        CorsPolicy::AnyOrigin
+    };
+    if let Some(wallet_file) = &args.wallet_rpc {
+        match SecureWallet::load(wallet_file) {
+            Ok(wallet) => {
+                println!("Wallet RPC enabled for {}", wallet.address);
+                let mut node_wallet = NodeWallet::new(wallet);
+                node_wallet.unlock_timeout = Duration::from_secs(args.wallet_unlock_timeout);
+                rpc_state.wallet = Some(Arc::new(node_wallet));
+            },
+            Err(e) => eprintln!("Wallet RPC disabled, cannot load {}: {}", wallet_file, e),
+        }
+    }
+    
     // Démarrer RPC
     tokio::spawn(async move {
-        if let Err(e) = start_rpc_server(blockchain_rpc, args.rpc_port).await {
+        if let Err(e) = start_rpc_server(rpc_state, rpc_addr).await {
             eprintln!("RPC error: {}", e);
         }
     });
     
     // **AMÉLIORATION : Mining avec propagation automatique des blocs**
     if args.mining {
+        let miner = Arc::new(Miner::new(wallet_addr.clone()));
+        let block_interval = Duration::from_secs(args.block_interval_secs);
+        let mining_shutdown = shutdown.clone();
+        
         tokio::spawn(async move {
             loop {
+                if mining_shutdown.load(Ordering::SeqCst) {
+                    println!("⛏️  Mining stopped");
+                    break;
+                }
+                
                 let start = std::time::Instant::now();
                 
-                let new_block = {
+                // Instantané du sommet : le verrou n'est pas tenu pendant la preuve de travail.
+                // L'abonnement est pris sous le même verrou pour ne manquer aucun sommet.
+                let template = {
+                    let chain = blockchain_mining.read().await;
+                    (!chain.chain.is_empty()).then(|| (chain.block_template(wallet_addr.clone()), chain.subscribe_tips()))
+                };
+                let Some((template, tips)) = template else {
+                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                    continue;
+                };
+                
+                let pow = {
+                    let miner = miner.clone();
+                    let cancel = cancel_on_new_tip(&template, tips, mining_shutdown.clone());
+                    tokio::task::spawn_blocking(move || miner.mine_block(template, cancel)).await
+                };
+                let Ok(Some((new_block, stats))) = pow else {
+                    continue;
+                };
+                
+                {
                     let mut chain = blockchain_mining.write().await;
-                    if let Some(prev_block) = chain.chain.last().cloned() {
-                        let new_block = auriumchain::blockchain::Block::new(
-                            prev_block.index + 1,
-                            vec![],
-                            prev_block.hash.clone(),
-                            4,
-                            wallet_addr.clone(),
-                        );
-                        
-                        chain.chain.push(new_block.clone());
-                        
-                        if let Err(e) = chain.save_to_file(&data_file_mining) {
-                            eprintln!("Error saving blockchain: {}", e);
-                        } else {
-                            println!("Blockchain saved: {} blocks to {}", chain.chain.len(), data_file_mining);
-                        }
-                        
-                        println!("Block {} mined and saved (TLS)!", new_block.index);
-                        println!("   Hash: {}", new_block.hash);
-                        println!("   Chain: {} blocks", chain.chain.len());
-                        
-                        Some(new_block)
+                    if !chain.append_if_tip(new_block.clone()) {
+                        // Un bloc reçu entre-temps a changé le sommet : recommencer sur le nouveau
+                        println!("♻️  Tip changed while mining block {}, discarding it", new_block.index);
+                        continue;
+                    }
+                    
+                    metrics_mining.record_block_mined();
+                    if let Ok(mut tracker) = energy_mining.lock() {
+                        tracker.record_block(stats);
+                    }
+                    if let Ok(mut monitor) = monitor_mining.lock() {
+                        monitor.process_block(&new_block);
+                    }
+                    
+                    if let Err(e) = chain.save_to_file(&data_file_mining) {
+                        eprintln!("Error saving blockchain: {}", e);
                     } else {
-                        None
+                        println!("Blockchain saved: {} blocks to {}", chain.chain.len(), data_file_mining);
                     }
-                };
+                    
+                    println!("Block {} mined and saved (TLS)!", new_block.index);
+                    println!("   Hash: {}", new_block.hash);
+                    println!("   Chain: {} blocks", chain.chain.len());
+                }
                 
                 // **NOUVELLE FONCTIONNALITÉ : Propager le nouveau bloc vers tous les peers**
-                if let Some(block) = new_block {
-                    tokio::spawn({
-                        let sync_manager = sync_manager_mining.clone();
-                        async move {
-                            if let Err(e) = sync_manager.broadcast_new_block(block).await {
-                                eprintln!("Failed to broadcast new block: {}", e);
-                            } else {
-                                println!("📡 New block broadcasted to all peers");
-                            }
+                tokio::spawn({
+                    let sync_manager = sync_manager_mining.clone();
+                    async move {
+                        if let Err(e) = sync_manager.broadcast_new_block(new_block).await {
+                            eprintln!("Failed to broadcast new block: {}", e);
+                        } else {
+                            println!("📡 New block broadcasted to all peers");
                         }
-                    });
-                }
+                    }
+                });
                 
                 let elapsed = start.elapsed();
-                if elapsed.as_secs() < 30 {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(30 - elapsed.as_secs())).await;
+                if let Some(remaining) = block_interval.checked_sub(elapsed) {
+                    tokio::time::sleep(remaining).await;
                 }
             }
         });
     }
     
-    println!("P2P Server (TLS) listening on 0.0.0.0:{}", args.port);
-    println!("RPC Server listening on http://0.0.0.0:{}", args.rpc_port);
+    println!("P2P Server (TLS) listening on {}", p2p_addr);
+    println!("RPC Server listening on http://{}", rpc_addr);
     println!("TLS P2P Node running! Press Ctrl+C to stop");
     
     // **NOUVELLE FONCTIONNALITÉ : Synchronisation initiale au démarrage**
@@ -230,8 +460,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
         println!("🔄 Starting initial synchronization...");
         
-        let peers = peer_manager.get_all_peers().await;
+        // Hauteur de chaque peer d'abord : le plus long est synchronisé en premier
+        heartbeat.ping_all().await;
+        let peers = peer_manager.sync_order().await;
+        let header_sync = HeaderSync::new(blockchain.clone(), security.clone());
         for peer_addr in peers {
+            if args.headers_first {
+                match header_sync.sync_with_peer(peer_addr).await {
+                    Ok(count) => println!("✅ Headers-first sync with {}: {} blocks added", peer_addr, count),
+                    Err(e) => {
+                        println!("❌ Headers-first sync failed with peer {}: {}", peer_addr, e);
+                        metrics.record_sync_error();
+                    },
+                }
+                continue;
+            }
+            
             match sync_manager.sync_with_peer(peer_addr).await {
                 Ok(synced) => {
                     if synced {
@@ -242,12 +486,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 },
                 Err(e) => {
                     println!("❌ Initial sync failed with peer {}: {}", peer_addr, e);
+                    metrics.record_sync_error();
                 }
             }
         }
     }
     
-    loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+    // Arrêt propre sur Ctrl+C : stopper les tâches, sauvegarder la chaîne, journaliser.
+    // Ligne attendue dans le journal de sécurité (--security-log) :
+    //   <timestamp>|NodeStopped|node|clean shutdown, <n> blocks flushed to <data_file>
+    tokio::signal::ctrl_c().await?;
+    println!("🛑 Shutdown requested, stopping tasks...");
+    shutdown.store(true, Ordering::SeqCst);
+    
+    // Le verrou en écriture n'est obtenu qu'une fois le bloc en cours de mining annulé
+    let chain = blockchain.write().await;
+    let details = match chain.save_to_file(&args.data_file) {
+        Ok(()) => format!("clean shutdown, {} blocks flushed to {}", chain.chain.len(), args.data_file),
+        Err(e) => {
+            eprintln!("Error saving blockchain: {}", e);
+            format!("shutdown with flush error: {}", e)
+        }
+    };
+    if let Err(e) = chain.mempool.save(&args.mempool_file) {
+        eprintln!("Error saving mempool: {}", e);
     }
+    if let Ok(limiter) = rate_limiter.lock() {
+        if let Err(e) = limiter.save_bans(&args.bans_file) {
+            eprintln!("Error saving bans: {}", e);
+        }
+    }
+    security_logger.log_event(SecurityEventType::NodeStopped, "node", &details);
+    println!("✅ Node stopped: {}", details);
+    
+    Ok(())
 }
\ No newline at end of file
@@ -1,5 +1,4 @@
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
 
 /// Statistiques énergétiques d'un bloc
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +11,17 @@ pub struct BlockEnergyStats {
     pub difficulty: u32,
 }
 
+impl BlockEnergyStats {
+    /// Hashrate moyen pendant le minage du bloc (hashes/seconde)
+    pub fn hashrate(&self) -> f64 {
+        if self.mining_duration_secs > 0.0 {
+            self.hash_attempts as f64 / self.mining_duration_secs
+        } else {
+            0.0
+        }
+    }
+}
+
 /// Calculateur d'énergie
 pub struct EnergyCalculator {
     pub watts_per_mhash: f64,  // Watts par million de hashes
@@ -34,7 +44,11 @@ impl EnergyCalculator {
         duration_secs: f64,
     ) -> BlockEnergyStats {
         // Calculer le hashrate (hashes/seconde)
-        let hashrate = hash_attempts as f64 / duration_secs;
+        let hashrate = if duration_secs > 0.0 {
+            hash_attempts as f64 / duration_secs
+        } else {
+            0.0
+        };
         let hashrate_mh = hashrate / 1_000_000.0; // Convertir en MH/s
         
         // Puissance estimée
@@ -152,7 +166,7 @@ impl EnergyTracker {
         println!("║  └─ Ethereum PoS : {:<26.1} Wh ║", comparison.ethereum_pos_wh_per_tx);
         println!("║                                                ║");
         
-        let reduction_vs_btc = (1.0 - comparison.auriumchain_wh_per_tx / comparison.bitcoin_wh_per_tx) * 100.0;
+        let reduction_vs_btc = comparison.reduction_vs_bitcoin();
         println!("║  ✅ Réduction vs Bitcoin : {:<17.1}% ║", reduction_vs_btc);
         println!("║                                                ║");
         println!("╚════════════════════════════════════════════════╝\n");
@@ -173,6 +187,13 @@ pub struct EnergyComparison {
     pub ethereum_pos_wh_per_tx: f64,
 }
 
+impl EnergyComparison {
+    /// Réduction de consommation par transaction par rapport à Bitcoin (%)
+    pub fn reduction_vs_bitcoin(&self) -> f64 {
+        (1.0 - self.auriumchain_wh_per_tx / self.bitcoin_wh_per_tx) * 100.0
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
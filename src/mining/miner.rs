@@ -1,17 +1,168 @@
-use std::sync::{Arc, Mutex};
-use crate::blockchain::Blockchain;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use crate::blockchain::{Block, ChainTip};
+use crate::mining::energy::{BlockEnergyStats, EnergyCalculator};
 
 pub struct Miner {
     pub address: String,
+    pub calculator: EnergyCalculator,
 }
 
 impl Miner {
     pub fn new(address: String) -> Self {
-        Self { address }
+        Self {
+            address,
+            calculator: EnergyCalculator::new(),
+        }
     }
     
-    pub fn mine_block(&self, blockchain: Arc<Mutex<Blockchain>>) {
-        // Implémentation simplifiée pour éviter les erreurs
-        println!("Mining for address: {}", self.address);
+    /// Itérer les nonces jusqu'à atteindre la cible, ou abandonner dès que `cancel` passe à vrai
+    /// (par exemple quand un bloc concurrent arrive et rend ce travail obsolète).
+    pub fn mine_block(&self, template: Block, cancel: Arc<AtomicBool>) -> Option<(Block, BlockEnergyStats)> {
+        let mut block = template;
+        let mut hash_attempts: u64 = 0;
+        let start = Instant::now();
+
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                println!("⏹️  Mining of block {} cancelled after {} hashes", block.index, hash_attempts);
+                return None;
+            }
+
+            block.hash = block.calculate_hash();
+            hash_attempts += 1;
+
+            if block.meets_difficulty() {
+                break;
+            }
+
+            block.nonce = block.nonce.wrapping_add(1);
+        }
+
+        let duration_secs = start.elapsed().as_secs_f64();
+        let mut stats = self.calculator.calculate_block_energy(hash_attempts, duration_secs);
+        stats.block_index = block.index;
+        stats.difficulty = block.target_bits.unwrap_or(block.difficulty);
+
+        println!("✅ Block {} mined in {:.2}s ({} hashes, {:.0} H/s)",
+            block.index, duration_secs, hash_attempts, stats.hashrate());
+
+        Some((block, stats))
+    }
+}
+
+/// Drapeau d'annulation propre au minage de `template` : levé à l'arrêt du nœud (`shutdown`)
+/// ou dès que `tips` signale un sommet autre que `template.previous_hash`, le bloc en cours
+/// ne pouvant plus prolonger la chaîne. La tâche d'écoute se termine avec le minage.
+pub fn cancel_on_new_tip(
+    template: &Block,
+    mut tips: broadcast::Receiver<ChainTip>,
+    shutdown: Arc<AtomicBool>,
+) -> Arc<AtomicBool> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let previous_hash = template.previous_hash.clone();
+    let flag = cancel.clone();
+
+    tokio::spawn(async move {
+        let mut poll = tokio::time::interval(Duration::from_millis(200));
+        loop {
+            tokio::select! {
+                tip = tips.recv() => match tip {
+                    Ok(tip) if tip.hash == previous_hash => continue,
+                    // Nouveau sommet, sommets manqués ou chaîne fermée : modèle obsolète
+                    _ => break,
+                },
+                _ = poll.tick() => {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    // Minage terminé : plus personne ne lit le drapeau
+                    if Arc::strong_count(&flag) == 1 {
+                        return;
+                    }
+                },
+            }
+        }
+        flag.store(true, Ordering::SeqCst);
+    });
+
+    cancel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mine_block_meets_difficulty() {
+        let miner = Miner::new("Miner".to_string());
+        let template = Block::new(1, vec![], "0".repeat(64), 2, miner.address.clone());
+
+        let (block, stats) = miner
+            .mine_block(template, Arc::new(AtomicBool::new(false)))
+            .expect("mining should succeed");
+
+        assert!(block.hash.starts_with("00"));
+        assert_eq!(block.hash, block.calculate_hash());
+        assert!(stats.hash_attempts > 0);
+        assert_eq!(stats.block_index, 1);
+        assert_eq!(stats.difficulty, 2);
+    }
+
+    #[test]
+    fn test_mine_block_cancelled() {
+        let miner = Miner::new("Miner".to_string());
+        let mut template = Block::new(1, vec![], "0".repeat(64), 0, miner.address.clone());
+        template.target_bits = Some(255);
+
+        let cancel = Arc::new(AtomicBool::new(true));
+        assert!(miner.mine_block(template, cancel).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mining_cancelled_when_tip_changes() {
+        use crate::blockchain::Blockchain;
+        use crate::utils::config::ConsensusParams;
+
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(crate::blockchain::genesis::create_genesis_block_with_params(&params));
+        let miner = Miner::new(crate::wallet::address::generate_address(b"slow", params.network));
+
+        // Cible inatteignable : seul le drapeau peut arrêter le minage
+        let mut template = chain.block_template(miner.address.clone());
+        template.target_bits = Some(255);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let cancel = cancel_on_new_tip(&template, chain.subscribe_tips(), shutdown.clone());
+        let mining = tokio::task::spawn_blocking({
+            let cancel = cancel.clone();
+            move || miner.mine_block(template, cancel)
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!cancel.load(Ordering::SeqCst));
+
+        // Un bloc concurrent devient le sommet
+        chain.mine_pending_transactions(crate::wallet::address::generate_address(b"fast", params.network));
+        let outcome = tokio::time::timeout(Duration::from_secs(5), mining).await.unwrap().unwrap();
+        assert!(outcome.is_none());
+        assert!(!shutdown.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_mining_cancelled_on_shutdown() {
+        let miner = Miner::new("Miner".to_string());
+        let mut template = Block::new(1, vec![], "0".repeat(64), 0, miner.address.clone());
+        template.target_bits = Some(255);
+        let (_sender, tips) = broadcast::channel(4);
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let cancel = cancel_on_new_tip(&template, tips, shutdown.clone());
+        let mining = tokio::task::spawn_blocking(move || miner.mine_block(template, cancel));
+
+        shutdown.store(true, Ordering::SeqCst);
+        let outcome = tokio::time::timeout(Duration::from_secs(5), mining).await.unwrap().unwrap();
+        assert!(outcome.is_none());
     }
 }
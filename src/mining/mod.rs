@@ -2,7 +2,7 @@ pub mod miner;
 pub mod pow;
 pub mod energy;
 
-pub use miner::Miner;
+pub use miner::{cancel_on_new_tip, Miner};
 pub use energy::{EnergyCalculator, EnergyTracker, BlockEnergyStats};
 
 pub async fn start_mining(
@@ -1,5 +1,44 @@
-use sha2::{Digest, Sha256};
 use crate::blockchain::Block;
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+
+/// Nombre de blocs récents sur lesquels hashrate et temps de bloc sont estimés
+pub const HASHRATE_WINDOW: usize = 100;
+/// Mémoire (KiB) remplie par chaque hash en mode `PowAlgorithm::MemoryHard`
+pub const MEMORY_HARD_KIB: u32 = 1024;
+/// Sel fixe de la passe Argon2 : le hash ne dépend que de l'en-tête
+const MEMORY_HARD_SALT: &[u8] = b"AuriumChain-PoW";
+
+/// Fonction de preuve de travail d'un réseau (paramètre de consensus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PowAlgorithm {
+    /// Double SHA-256 de l'en-tête
+    #[default]
+    Sha256d,
+    /// Passe Argon2id sur `MEMORY_HARD_KIB` de mémoire, appliquée au double SHA-256 de
+    /// l'en-tête : le coût est dominé par la bande passante mémoire plutôt que par le calcul
+    MemoryHard,
+}
+
+impl PowAlgorithm {
+    /// Hash de bloc à partir du double SHA-256 hexadécimal de l'en-tête.
+    /// La difficulté se vérifie ensuite de la même façon quel que soit l'algorithme.
+    pub fn block_hash(&self, header_hash: String) -> String {
+        match self {
+            PowAlgorithm::Sha256d => header_hash,
+            PowAlgorithm::MemoryHard => {
+                let params = Params::new(MEMORY_HARD_KIB, 1, 1, Some(32))
+                    .expect("valid memory-hard PoW parameters");
+                let mut output = [0u8; 32];
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(header_hash.as_bytes(), MEMORY_HARD_SALT, &mut output)
+                    .expect("Argon2 with a fixed salt and 32-byte output cannot fail");
+                hex::encode(output)
+            },
+        }
+    }
+}
 
 pub struct ProofOfWork {
     pub difficulty: u32,
@@ -19,20 +58,152 @@ impl ProofOfWork {
         hash.starts_with(&target)
     }
 
+    /// Convertir une difficulté en bits (nombre de bits de tête à zéro) en cible 256 bits big-endian.
+    ///
+    /// Chaque bit supplémentaire double le travail attendu, contre ×16 pour un zéro hexadécimal.
+    pub fn difficulty_to_target(bits: u32) -> [u8; 32] {
+        let mut target = [0xFFu8; 32];
+        let zero_bytes = (bits / 8).min(32) as usize;
+
+        for byte in target.iter_mut().take(zero_bytes) {
+            *byte = 0;
+        }
+
+        if zero_bytes < 32 {
+            target[zero_bytes] = 0xFF >> (bits % 8);
+        }
+
+        target
+    }
+
+    /// Vérifier qu'un hash hexadécimal est inférieur ou égal à la cible (comparaison big-endian)
+    pub fn meets_target(hash_hex: &str, target: &[u8; 32]) -> bool {
+        match hex::decode(hash_hex) {
+            Ok(hash) if hash.len() == 32 => hash.as_slice() <= &target[..],
+            _ => false,
+        }
+    }
+
+    /// Hashs par seconde sur les `HASHRATE_WINDOW` derniers blocs : travail attendu
+    /// (2^bits, un zéro hexadécimal valant 4 bits) divisé par la durée couverte
     pub fn estimate_hashrate(&self, blocks: &[Block]) -> f64 {
-        if blocks.len() < 2 {
+        let recent = &blocks[blocks.len().saturating_sub(HASHRATE_WINDOW)..];
+        let Some(time_span) = Self::time_span(recent) else {
             return 0.0;
-        }
+        };
 
-        let recent = &blocks[blocks.len().saturating_sub(100)..];
-        let time_span = recent.last().unwrap().timestamp - recent.first().unwrap().timestamp;
+        // Le premier bloc ouvre la fenêtre : son travail précède la durée mesurée
+        let total_work: f64 = recent.iter()
+            .skip(1)
+            .map(|b| 2f64.powi(b.target_bits.unwrap_or(b.difficulty.saturating_mul(4)) as i32))
+            .sum();
         
-        if time_span == 0 {
-            return 0.0;
+        total_work / time_span as f64
+    }
+
+    /// Temps moyen entre deux blocs (secondes) sur les `HASHRATE_WINDOW` derniers blocs
+    pub fn average_block_time(blocks: &[Block]) -> f64 {
+        let recent = &blocks[blocks.len().saturating_sub(HASHRATE_WINDOW)..];
+        match Self::time_span(recent) {
+            Some(time_span) => time_span as f64 / (recent.len() - 1) as f64,
+            None => 0.0,
         }
+    }
 
-        let total_work: u64 = recent.iter().map(|b| 1u64 << b.difficulty).sum();
-        
-        (total_work as f64) / (time_span as f64)
+    fn time_span(blocks: &[Block]) -> Option<i64> {
+        let (first, last) = (blocks.first()?, blocks.last()?);
+        Some(last.timestamp - first.timestamp).filter(|span| *span > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_difficulty_to_target() {
+        assert_eq!(ProofOfWork::difficulty_to_target(0), [0xFF; 32]);
+        assert_eq!(ProofOfWork::difficulty_to_target(256), [0x00; 32]);
+
+        let target = ProofOfWork::difficulty_to_target(12);
+        assert_eq!(target[0], 0x00);
+        assert_eq!(target[1], 0x0F);
+        assert_eq!(target[2], 0xFF);
+    }
+
+    #[test]
+    fn test_fine_grained_targets() {
+        // 16 bits à zéro puis un bit à 1 : valide pour 16 bits, pas pour 17
+        let hash = format!("00008{}", "f".repeat(59));
+        assert!(ProofOfWork::meets_target(&hash, &ProofOfWork::difficulty_to_target(16)));
+        assert!(!ProofOfWork::meets_target(&hash, &ProofOfWork::difficulty_to_target(17)));
+
+        // 17 bits à zéro : les deux cibles sont satisfaites
+        let hash = format!("00007{}", "f".repeat(59));
+        assert!(ProofOfWork::meets_target(&hash, &ProofOfWork::difficulty_to_target(16)));
+        assert!(ProofOfWork::meets_target(&hash, &ProofOfWork::difficulty_to_target(17)));
+        assert!(!ProofOfWork::meets_target(&hash, &ProofOfWork::difficulty_to_target(18)));
+    }
+
+    #[test]
+    fn test_invalid_hash_rejected() {
+        let target = ProofOfWork::difficulty_to_target(0);
+        assert!(!ProofOfWork::meets_target("not-hex", &target));
+        assert!(!ProofOfWork::meets_target("00ff", &target));
+    }
+
+    #[test]
+    fn test_mine_with_numeric_target() {
+        let mut block = Block::new(1, vec![], "0".repeat(64), 0, "Miner".to_string());
+        block.target_bits = Some(10);
+        block.mine();
+
+        assert!(block.meets_difficulty());
+        assert!(ProofOfWork::meets_target(&block.hash, &ProofOfWork::difficulty_to_target(10)));
+    }
+
+    #[test]
+    fn test_legacy_difficulty_still_validates() {
+        let mut block = Block::new(1, vec![], "0".repeat(64), 2, "Miner".to_string());
+        block.mine();
+
+        assert!(block.target_bits.is_none());
+        assert!(block.hash.starts_with("00"));
+        assert!(block.meets_difficulty());
+    }
+
+    #[test]
+    fn test_memory_hard_hash_is_deterministic() {
+        let header_hash = "ab".repeat(32);
+        let hash = PowAlgorithm::MemoryHard.block_hash(header_hash.clone());
+
+        assert_eq!(hash, PowAlgorithm::MemoryHard.block_hash(header_hash.clone()));
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, header_hash);
+        assert_eq!(PowAlgorithm::Sha256d.block_hash(header_hash.clone()), header_hash);
+    }
+
+    #[test]
+    fn test_memory_hard_block_validates() {
+        use crate::blockchain::Blockchain;
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::security::SecurityValidator;
+        use crate::utils::config::{ConsensusParams, Network};
+
+        let params = ConsensusParams { difficulty: 1, pow_algorithm: PowAlgorithm::MemoryHard, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        let miner = crate::wallet::address::generate_address(b"memory-hard", Network::Mainnet);
+        let mut block = chain.block_template(miner);
+        block.mine();
+
+        assert_eq!(block.pow_algorithm, PowAlgorithm::MemoryHard);
+        assert!(block.meets_difficulty());
+        assert!(SecurityValidator::with_params(&params).validate_block_against_chain(&block, &chain).is_ok());
+        assert!(chain.validate_new_block(&block));
+
+        // Un nœud en double SHA-256 refuse le bloc
+        let err = SecurityValidator::new().validate_block_against_chain(&block, &chain).unwrap_err();
+        assert!(err.to_string().contains("proof of work"), "{}", err);
     }
 }
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use crate::blockchain::{Block, Transaction};
+
+/// Taille maximale d'un filtre (octets) et nombre maximal de fonctions de hachage, comme BIP37
+pub const MAX_BLOOM_FILTER_SIZE: usize = 36_000;
+pub const MAX_BLOOM_HASH_FUNCS: u32 = 50;
+
+/// Filtre de Bloom fourni par un client léger (style BIP37)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomFilter {
+    data: Vec<u8>,
+    hash_funcs: u32,
+    tweak: u32,
+}
+
+impl BloomFilter {
+    /// Filtre vide de `size_bytes` octets
+    pub fn new(size_bytes: usize, hash_funcs: u32, tweak: u32) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_parts(vec![0u8; size_bytes], hash_funcs, tweak)
+    }
+
+    /// Filtre reçu d'un peer ; refuse les filtres vides ou hors limites
+    pub fn from_parts(data: Vec<u8>, hash_funcs: u32, tweak: u32) -> Result<Self, Box<dyn std::error::Error>> {
+        if data.is_empty() || data.len() > MAX_BLOOM_FILTER_SIZE {
+            return Err(format!("Bloom filter size {} out of range", data.len()).into());
+        }
+        if hash_funcs == 0 || hash_funcs > MAX_BLOOM_HASH_FUNCS {
+            return Err(format!("Bloom filter uses {} hash functions", hash_funcs).into());
+        }
+
+        Ok(BloomFilter { data, hash_funcs, tweak })
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn hash_funcs(&self) -> u32 {
+        self.hash_funcs
+    }
+
+    pub fn tweak(&self) -> u32 {
+        self.tweak
+    }
+
+    fn bit_index(&self, n: u32, element: &[u8]) -> usize {
+        let seed = n.wrapping_mul(0xFBA4C795).wrapping_add(self.tweak);
+        murmur3(seed, element) as usize % (self.data.len() * 8)
+    }
+
+    pub fn insert(&mut self, element: &[u8]) {
+        for n in 0..self.hash_funcs {
+            let bit = self.bit_index(n, element);
+            self.data[bit >> 3] |= 1 << (bit & 7);
+        }
+    }
+
+    pub fn contains(&self, element: &[u8]) -> bool {
+        (0..self.hash_funcs).all(|n| {
+            let bit = self.bit_index(n, element);
+            self.data[bit >> 3] & (1 << (bit & 7)) != 0
+        })
+    }
+
+    /// Une transaction correspond si son id, une adresse de sortie
+    /// ou une transaction dépensée figure dans le filtre
+    pub fn matches_transaction(&self, tx: &Transaction) -> bool {
+        self.contains(tx.id.as_bytes())
+            || tx.outputs.iter().any(|output| self.contains(output.address.as_bytes()))
+            || tx.inputs.iter().any(|input| self.contains(input.prev_tx_id.as_bytes()))
+    }
+}
+
+/// Transaction retenue par le filtre, avec sa preuve d'inclusion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilteredTransaction {
+    pub tx: Transaction,
+    pub index: usize,
+    pub merkle_branch: Vec<String>,
+}
+
+impl FilteredTransaction {
+    /// Vérifier la preuve d'inclusion contre la racine de Merkle du bloc
+    pub fn verify(&self, merkle_root: &str) -> bool {
+        Block::verify_merkle_branch(&Block::merkle_leaf(&self.tx), self.index, &self.merkle_branch, merkle_root)
+    }
+}
+
+/// En-tête d'un bloc accompagné des seules transactions correspondant au filtre du client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilteredBlock {
+    pub index: u64,
+    pub hash: String,
+    pub previous_hash: String,
+    pub merkle_root: String,
+    pub timestamp: i64,
+    pub total_transactions: usize,
+    pub transactions: Vec<FilteredTransaction>,
+}
+
+impl FilteredBlock {
+    pub fn from_block(block: &Block, filter: &BloomFilter) -> Self {
+        let transactions = block.transactions.iter()
+            .enumerate()
+            .filter(|(_, tx)| filter.matches_transaction(tx))
+            .map(|(index, tx)| FilteredTransaction {
+                tx: tx.clone(),
+                index,
+                merkle_branch: Block::merkle_branch(&block.transactions, index),
+            })
+            .collect();
+
+        FilteredBlock {
+            index: block.index,
+            hash: block.hash.clone(),
+            previous_hash: block.previous_hash.clone(),
+            merkle_root: block.merkle_root.clone(),
+            timestamp: block.timestamp,
+            total_transactions: block.transactions.len(),
+            transactions,
+        }
+    }
+}
+
+/// MurmurHash3 (x86, 32 bits), la fonction de hachage de BIP37
+fn murmur3(seed: u32, data: &[u8]) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+
+    let mut hash = seed;
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+
+    for chunk in chunks {
+        let k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        hash ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    if !tail.is_empty() {
+        let k = tail.iter()
+            .enumerate()
+            .fold(0u32, |k, (i, byte)| k ^ ((*byte as u32) << (8 * i)));
+        hash ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85ebca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2ae35);
+    hash ^ (hash >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::{TxInput, TxOutput};
+
+    fn payment(prev: &str, to: &str, value: u64) -> Transaction {
+        Transaction::new(
+            vec![TxInput {
+                prev_tx_id: prev.to_string(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![TxOutput { value, address: to.to_string() }],
+        )
+    }
+
+    #[test]
+    fn test_murmur3_reference_values() {
+        assert_eq!(murmur3(0, b""), 0);
+        assert_eq!(murmur3(1, b""), 0x514e28b7);
+        assert_eq!(murmur3(0, b"hello"), 0x248bfa47);
+    }
+
+    #[test]
+    fn test_filtered_block_keeps_only_matching_transactions() {
+        let transactions = vec![
+            Transaction::coinbase("AUR1Miner".to_string(), 50_0000_0000, 1_730_000_000),
+            payment("aa", "AUR1Bob", 1_000),
+            payment("bb", "AUR1Alice", 2_000),
+            payment("cc", "AUR1Carol", 3_000),
+            payment("dd", "AUR1Alice", 4_000),
+        ];
+        let block = Block::new(1, transactions, "0".repeat(64), 1, "AUR1Miner".to_string());
+
+        let mut filter = BloomFilter::new(256, 5, 42).unwrap();
+        filter.insert(b"AUR1Alice");
+
+        let filtered = FilteredBlock::from_block(&block, &filter);
+        assert_eq!(filtered.total_transactions, 5);
+        assert_eq!(filtered.transactions.len(), 2);
+
+        for matched in &filtered.transactions {
+            assert_eq!(matched.tx.outputs[0].address, "AUR1Alice");
+            assert!(matched.verify(&block.merkle_root));
+        }
+        assert_eq!(filtered.transactions[0].index, 2);
+        assert_eq!(filtered.transactions[1].index, 4);
+
+        // Une preuve rattachée à une autre position ne vérifie pas
+        let mut forged = filtered.transactions[0].clone();
+        forged.index = 3;
+        assert!(!forged.verify(&block.merkle_root));
+    }
+
+    #[test]
+    fn test_filter_limits() {
+        assert!(BloomFilter::from_parts(vec![], 1, 0).is_err());
+        assert!(BloomFilter::from_parts(vec![0; MAX_BLOOM_FILTER_SIZE + 1], 1, 0).is_err());
+        assert!(BloomFilter::from_parts(vec![0; 16], MAX_BLOOM_HASH_FUNCS + 1, 0).is_err());
+    }
+}
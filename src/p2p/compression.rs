@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+
+/// Taille (octets) en dessous de laquelle une trame n'est jamais compressée
+pub const COMPRESSION_THRESHOLD: usize = 16 * 1024;
+
+/// Compression d'une trame P2P, négociée dans le handshake.
+/// Le premier octet de chaque trame est le tag de l'algorithme utilisé.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Compression {
+    #[default]
+    None,
+    /// Format bloc LZ4, précédé de la taille décompressée (`u32` LE)
+    Lz4,
+}
+
+impl Compression {
+    pub fn tag(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Lz4 => 1,
+        }
+    }
+
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Compression::None),
+            1 => Some(Compression::Lz4),
+            _ => None,
+        }
+    }
+}
+
+/// Corps d'une trame : tag puis données. Les charges sous `COMPRESSION_THRESHOLD`,
+/// ou que la compression n'allège pas, partent non compressées.
+pub fn encode_frame(payload: &[u8], compression: Compression) -> Vec<u8> {
+    if compression == Compression::Lz4 && payload.len() >= COMPRESSION_THRESHOLD {
+        let compressed = lz4_compress(payload);
+        if compressed.len() + 4 < payload.len() {
+            let mut frame = Vec::with_capacity(compressed.len() + 5);
+            frame.push(Compression::Lz4.tag());
+            frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            frame.extend_from_slice(&compressed);
+            return frame;
+        }
+    }
+
+    let mut frame = Vec::with_capacity(payload.len() + 1);
+    frame.push(Compression::None.tag());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Retrouver la charge d'une trame ; la taille décompressée annoncée est bornée par `max_size`
+pub fn decode_frame(frame: &[u8], max_size: usize) -> Result<Vec<u8>, String> {
+    let (&tag, body) = frame.split_first().ok_or("empty frame")?;
+
+    match Compression::from_tag(tag) {
+        Some(Compression::None) => Ok(body.to_vec()),
+        Some(Compression::Lz4) => {
+            if body.len() < 4 {
+                return Err("truncated compressed frame".to_string());
+            }
+            let size = u32::from_le_bytes([body[0], body[1], body[2], body[3]]) as usize;
+            if size > max_size {
+                return Err(format!("decompressed size {} exceeds {}", size, max_size));
+            }
+            lz4_decompress(&body[4..], size)
+        },
+        None => Err(format!("unknown compression tag {}", tag)),
+    }
+}
+
+/// Compression LZ4 (format bloc, `lz4_flex`)
+pub fn lz4_compress(input: &[u8]) -> Vec<u8> {
+    lz4_flex::block::compress(input)
+}
+
+/// Décompression LZ4 (format bloc) ; la sortie doit faire exactement `expected_size` octets,
+/// et rien n'est alloué ni écrit au-delà
+pub fn lz4_decompress(input: &[u8], expected_size: usize) -> Result<Vec<u8>, String> {
+    let out = lz4_flex::block::decompress(input, expected_size).map_err(|e| e.to_string())?;
+    if out.len() != expected_size {
+        return Err(format!("decompressed {} bytes, expected {}", out.len(), expected_size));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::{Block, Transaction, TxInput, TxOutput};
+    use crate::p2p::messages::P2PMessage;
+
+    #[test]
+    fn test_large_send_blocks_round_trip() {
+        let blocks: Vec<Block> = (0..20)
+            .map(|index| {
+                let transactions = (0..50)
+                    .map(|n| Transaction::new(
+                        vec![TxInput {
+                            prev_tx_id: format!("{:064x}", index * 100 + n),
+                            output_index: 0,
+                            signature: "ab".repeat(64),
+                            public_key: "02".repeat(33),
+                        }],
+                        vec![TxOutput { value: 1_000 + n, address: format!("AUR1Receiver{}", n) }],
+                    ))
+                    .collect();
+                Block::new(index, transactions, "0".repeat(64), 1, "AUR1Miner".to_string())
+            })
+            .collect();
+        let payload = P2PMessage::SendBlocks { blocks }.serialize();
+        assert!(payload.len() > COMPRESSION_THRESHOLD);
+
+        let frame = encode_frame(&payload, Compression::Lz4);
+        assert_eq!(Compression::from_tag(frame[0]), Some(Compression::Lz4));
+        assert!(frame.len() < payload.len() / 2);
+        assert_eq!(decode_frame(&frame, payload.len()).unwrap(), payload);
+
+        // Taille annoncée au-delà du maximum du lecteur : refusée avant décompression
+        assert!(decode_frame(&frame, payload.len() - 1).is_err());
+        assert!(matches!(
+            P2PMessage::deserialize(&decode_frame(&frame, payload.len()).unwrap()).unwrap(),
+            P2PMessage::SendBlocks { blocks } if blocks.len() == 20
+        ));
+    }
+
+    #[test]
+    fn test_small_and_incompressible_payloads_stay_raw() {
+        let ping = P2PMessage::Ping.serialize();
+        let frame = encode_frame(&ping, Compression::Lz4);
+        assert_eq!(frame[0], Compression::None.tag());
+        assert_eq!(decode_frame(&frame, 1024).unwrap(), ping);
+
+        // Données pseudo-aléatoires : aucun gain, envoyées telles quelles
+        let mut state = 0x2545F491u32;
+        let noise: Vec<u8> = (0..COMPRESSION_THRESHOLD * 2)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        assert_eq!(lz4_decompress(&lz4_compress(&noise), noise.len()).unwrap(), noise);
+        assert_eq!(encode_frame(&noise, Compression::Lz4)[0], Compression::None.tag());
+
+        assert!(decode_frame(&[7, 1, 2], 1024).is_err());
+        assert!(lz4_decompress(&[0x0F, 0x05, 0x00], 64).is_err());
+    }
+
+    #[test]
+    fn test_arbitrary_compressed_bodies_fail_cleanly() {
+        let mut state = 0x9E3779B9u32;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        // Trames forgées par un peer : erreur ou sortie exacte, jamais de panique
+        for _ in 0..2_000 {
+            let len = (next() % 64) as usize;
+            let body: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            let expected_size = (next() % 4096) as usize;
+            if let Ok(out) = lz4_decompress(&body, expected_size) {
+                assert_eq!(out.len(), expected_size);
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use crate::blockchain::{Blockchain, Transaction};
+use crate::p2p::messages::P2PMessage;
+use crate::p2p::server::MAX_HANDSHAKE_SIZE;
+use crate::p2p::sync::SyncManager;
+use crate::p2p::{NetworkSecurity, P2PServer, PeerManager};
+use crate::utils::config::Network;
+
+/// Propagation des transactions du mempool vers les peers
+pub struct TxRelay {
+    peer_manager: Arc<PeerManager>,
+    blockchain: Arc<RwLock<Blockchain>>,
+    security: Arc<NetworkSecurity>,
+    /// Nombre maximum de peers contactés simultanément
+    pub max_fanout: usize,
+}
+
+impl TxRelay {
+    pub fn new(
+        peer_manager: Arc<PeerManager>,
+        blockchain: Arc<RwLock<Blockchain>>,
+        security: Arc<NetworkSecurity>,
+    ) -> Self {
+        Self {
+            peer_manager,
+            blockchain,
+            security,
+            max_fanout: 8,
+        }
+    }
+
+    /// Relayer une transaction du mempool à tous les peers sauf `except` (celui qui nous
+    /// l'a envoyée) et ceux dont le `FeeFilter` connu dépasse son taux de frais
+    pub async fn broadcast(&self, tx: &Transaction, except: Option<SocketAddr>) {
+        let fee_rate = self.blockchain.read().await.mempool.entries().iter()
+            .find(|entry| entry.tx.id == tx.id)
+            .map(|entry| entry.fee_rate());
+        // Minée ou évincée entre-temps : plus rien à relayer
+        let Some(fee_rate) = fee_rate else {
+            return;
+        };
+        let peers: Vec<SocketAddr> = self.peer_manager.peers_accepting(fee_rate).await
+            .into_iter()
+            .filter(|peer| Some(*peer) != except)
+            .collect();
+
+        for wave in SyncManager::relay_waves(&peers, self.max_fanout) {
+            let sends = wave.iter().map(|peer| async move {
+                self.send_to_peer(*peer, tx, fee_rate).await.map_err(|e| e.to_string())
+            });
+            let results = futures::future::join_all(sends).await;
+
+            for (peer, result) in wave.iter().zip(results) {
+                match result {
+                    Ok(min_fee_rate) => self.peer_manager.update_peer_fee_filter(*peer, min_fee_rate).await,
+                    Err(e) => println!("❌ Failed to relay tx {} to {}: {}", tx.id, peer, e),
+                }
+            }
+        }
+    }
+
+    async fn send_to_peer(&self, addr: SocketAddr, tx: &Transaction, fee_rate: u64) -> Result<u64, anyhow::Error> {
+        let stream = TcpStream::connect(addr).await?;
+        let server_name = rustls::ServerName::try_from("auriumchain-node")?;
+        let tls_stream = self.security.tls_connector.connect(server_name, stream).await?;
+        let (mut reader, mut writer) = tokio::io::split(tls_stream);
+
+        let (genesis_hash, network) = {
+            let chain = self.blockchain.read().await;
+            let genesis_hash = chain.chain.first()
+                .map(|b| b.hash.clone())
+                .unwrap_or_else(|| "none".to_string());
+            (genesis_hash, chain.params.network)
+        };
+        Self::send_over(&mut reader, &mut writer, &self.security.fingerprint, &genesis_hash, network, tx, fee_rate).await
+    }
+
+    /// Côté client : handshake, lecture du `FeeFilter` que le peer envoie ensuite, puis
+    /// annonce de la transaction si son taux `fee_rate` l'atteint.
+    /// Retourne le plancher annoncé par le peer.
+    pub(crate) async fn send_over<R, W>(
+        reader: &mut R,
+        writer: &mut W,
+        node_id: &str,
+        genesis_hash: &str,
+        network: Network,
+        tx: &Transaction,
+        fee_rate: u64,
+    ) -> Result<u64, anyhow::Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        P2PServer::client_handshake(reader, writer, node_id, genesis_hash, network).await?;
+        let min_fee_rate = match P2PServer::read_message(reader, MAX_HANDSHAKE_SIZE).await? {
+            P2PMessage::FeeFilter { min_fee_rate } => min_fee_rate,
+            other => return Err(anyhow::anyhow!("expected fee filter, got {:?}", other)),
+        };
+        if fee_rate >= min_fee_rate {
+            P2PServer::send_message(writer, &P2PMessage::NewTransaction { tx: tx.clone() }).await?;
+        }
+        Ok(min_fee_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::{TxInput, TxOutput};
+    use crate::mining::EnergyTracker;
+    use crate::p2p::server::PeerSession;
+    use crate::rpc::server::{handle_new_transaction, RpcState};
+    use crate::security::{RateLimiter, SecurityLogger, SecurityMonitor};
+    use crate::utils::config::ConsensusParams;
+    use crate::wallet::address::address_from_keypair;
+    use crate::wallet::keys::KeyPair;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_transaction_submitted_to_a_reaches_b_mempool() {
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(crate::blockchain::genesis::create_genesis_block_with_params(&params));
+        let alice = KeyPair::generate();
+        chain.mine_pending_transactions(address_from_keypair(&alice, Network::Mainnet));
+        let genesis_hash = chain.chain[0].hash.clone();
+
+        // Deux nœuds partageant la même chaîne
+        let node_a = Arc::new(RwLock::new(chain.clone()));
+        let node_b = Arc::new(RwLock::new(chain));
+
+        let funding = node_a.read().await.chain[1].transactions[0].clone();
+        let mut tx = Transaction::new(
+            vec![TxInput {
+                prev_tx_id: funding.id.clone(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![TxOutput { value: funding.total_output() - 50_000, address: "AUR1Bob".to_string() }],
+        );
+        // Même txid (il n'engage pas les signatures), signé par une clé qui ne possède pas l'output
+        let mut forged = tx.clone();
+        forged.sign_input(0, &KeyPair::generate()).unwrap();
+        tx.sign_input(0, &alice).unwrap();
+        assert_eq!(forged.id, tx.id);
+
+        let state_a = RpcState::new(
+            node_a.clone(),
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        );
+        let reply = handle_new_transaction(&state_a, &serde_json::to_string(&tx).unwrap()).await;
+        assert!(reply.contains("accepted"), "{}", reply);
+        assert!(node_a.read().await.mempool.contains(&tx.id));
+
+        let (server_side, client_side) = tokio::io::duplex(64 * 1024);
+        let server_chain = node_b.clone();
+        let server = tokio::spawn(async move {
+            let (mut reader, mut writer) = tokio::io::split(server_side);
+            let peer: SocketAddr = "127.0.0.1:40001".parse().unwrap();
+            P2PServer::perform_handshake(&mut reader, &mut writer, peer, "node-b", &server_chain).await.unwrap();
+            P2PServer::serve_messages(
+                &mut reader,
+                &mut writer,
+                peer,
+                &server_chain,
+                &Arc::new(Mutex::new(RateLimiter::new())),
+                &SecurityLogger::new(),
+                PeerSession::default(),
+            ).await
+        });
+
+        // La copie falsifiée, annoncée la première, est refusée sans masquer l'originale
+        let (mut reader, mut writer) = tokio::io::split(client_side);
+        TxRelay::send_over(&mut reader, &mut writer, "node-a", &genesis_hash, Network::Mainnet, &forged, u64::MAX).await.unwrap();
+        P2PServer::send_message(&mut writer, &P2PMessage::NewTransaction { tx: tx.clone() }).await.unwrap();
+        // Une seconde annonce est ignorée sans erreur
+        P2PServer::send_message(&mut writer, &P2PMessage::NewTransaction { tx: tx.clone() }).await.unwrap();
+        tokio::io::AsyncWriteExt::shutdown(&mut writer).await.unwrap();
+        assert!(server.await.unwrap().is_ok());
+
+        let chain_b = node_b.read().await;
+        assert_eq!(chain_b.mempool.len(), 1);
+        assert_eq!(chain_b.mempool.entries()[0].tx.inputs[0].signature, tx.inputs[0].signature);
+    }
+
+    #[tokio::test]
+    async fn test_fee_filter_gates_relay_and_is_reannounced() {
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(crate::blockchain::genesis::create_genesis_block_with_params(&params));
+        let alice = KeyPair::generate();
+        chain.mine_pending_transactions(address_from_keypair(&alice, Network::Mainnet));
+        let genesis_hash = chain.chain[0].hash.clone();
+
+        // Mempool au seuil de congestion : une transaction de plus relève le plancher
+        let normal_rate = chain.mempool.min_fee_rate();
+        for i in 0..1000u64 {
+            let filler = Transaction::new(
+                vec![TxInput {
+                    prev_tx_id: format!("{:064x}", i),
+                    output_index: 0,
+                    signature: String::new(),
+                    public_key: String::new(),
+                }],
+                vec![TxOutput { value: i, address: "AUR1Filler".to_string() }],
+            );
+            chain.mempool.add_transaction(filler, 1_000_000).unwrap();
+        }
+        assert_eq!(chain.mempool.min_fee_rate(), normal_rate);
+
+        let funding = chain.chain[1].transactions[0].clone();
+        let mut tx = Transaction::new(
+            vec![TxInput {
+                prev_tx_id: funding.id.clone(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![TxOutput {
+                value: funding.total_output() - 50_000,
+                address: address_from_keypair(&KeyPair::generate(), Network::Mainnet),
+            }],
+        );
+        tx.sign_input(0, &alice).unwrap();
+
+        let node = Arc::new(RwLock::new(chain));
+        let (server_side, client_side) = tokio::io::duplex(64 * 1024);
+        let server_chain = node.clone();
+        let server = tokio::spawn(async move {
+            let (mut reader, mut writer) = tokio::io::split(server_side);
+            let peer: SocketAddr = "127.0.0.1:40002".parse().unwrap();
+            P2PServer::perform_handshake(&mut reader, &mut writer, peer, "node-b", &server_chain).await.unwrap();
+            P2PServer::serve_messages(
+                &mut reader,
+                &mut writer,
+                peer,
+                &server_chain,
+                &Arc::new(Mutex::new(RateLimiter::new())),
+                &SecurityLogger::new(),
+                PeerSession::default(),
+            ).await
+        });
+
+        // Sous le plancher annoncé après le handshake : rien n'est envoyé
+        let (mut reader, mut writer) = tokio::io::split(client_side);
+        let floor = TxRelay::send_over(&mut reader, &mut writer, "node-a", &genesis_hash, Network::Mainnet, &tx, normal_rate - 1).await.unwrap();
+        assert_eq!(floor, normal_rate);
+        assert!(!node.read().await.mempool.contains(&tx.id));
+
+        // Acceptée, elle fait monter le plancher : le peer le ré-annonce aussitôt
+        P2PServer::send_message(&mut writer, &P2PMessage::NewTransaction { tx: tx.clone() }).await.unwrap();
+        match P2PServer::read_message(&mut reader, MAX_HANDSHAKE_SIZE).await.unwrap() {
+            P2PMessage::FeeFilter { min_fee_rate } => assert!(min_fee_rate > normal_rate),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(node.read().await.mempool.contains(&tx.id));
+
+        tokio::io::AsyncWriteExt::shutdown(&mut writer).await.unwrap();
+        assert!(server.await.unwrap().is_ok());
+    }
+}
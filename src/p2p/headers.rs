@@ -0,0 +1,220 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use crate::blockchain::{Block, BlockHeader, Blockchain};
+use crate::p2p::messages::P2PMessage;
+use crate::p2p::server::{MAX_BLOCKS_PER_REQUEST, MAX_HEADERS_PER_REQUEST};
+use crate::p2p::{NetworkSecurity, P2PServer};
+use crate::utils::config::ConsensusParams;
+
+/// Taille maximale d'une réponse `SendHeaders` / `SendBlocks` acceptée du serveur
+const MAX_RESPONSE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Vérifier qu'une suite d'en-têtes prolonge `tip` : chaînage, hash recalculé et preuve
+/// de travail à la difficulté exigée par `params`
+pub fn validate_header_chain(tip: &BlockHeader, headers: &[BlockHeader], params: &ConsensusParams) -> Result<(), anyhow::Error> {
+    let mut previous = tip;
+    for header in headers {
+        header.verify_consensus_pow(params)?;
+        if !header.is_valid(previous) {
+            return Err(anyhow::anyhow!("invalid header at height {}", header.index));
+        }
+        previous = header;
+    }
+    Ok(())
+}
+
+/// Synchronisation initiale en deux temps : la chaîne d'en-têtes du peer est validée
+/// avant de télécharger le moindre corps de bloc
+pub struct HeaderSync {
+    blockchain: Arc<RwLock<Blockchain>>,
+    security: Arc<NetworkSecurity>,
+    /// Nombre de corps de blocs demandés par requête
+    pub batch_size: usize,
+}
+
+impl HeaderSync {
+    pub fn new(blockchain: Arc<RwLock<Blockchain>>, security: Arc<NetworkSecurity>) -> Self {
+        Self {
+            blockchain,
+            security,
+            batch_size: MAX_BLOCKS_PER_REQUEST,
+        }
+    }
+
+    /// Synchroniser avec un peer ; retourne le nombre de blocs ajoutés
+    pub async fn sync_with_peer(&self, addr: SocketAddr) -> Result<usize, anyhow::Error> {
+        let stream = TcpStream::connect(addr).await?;
+        let server_name = rustls::ServerName::try_from("auriumchain-node")?;
+        let tls_stream = self.security.tls_connector.connect(server_name, stream).await?;
+        let (mut reader, mut writer) = tokio::io::split(tls_stream);
+
+        self.sync_over(&mut reader, &mut writer).await
+    }
+
+    pub(crate) async fn sync_over<R, W>(&self, reader: &mut R, writer: &mut W) -> Result<usize, anyhow::Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let (genesis_hash, params, mut tip) = {
+            let chain = self.blockchain.read().await;
+            match (chain.chain.first(), chain.get_latest_block()) {
+                (Some(genesis), Some(latest)) => (genesis.hash.clone(), chain.params.clone(), latest.header()),
+                _ => return Err(anyhow::anyhow!("local chain has no genesis block")),
+            }
+        };
+        P2PServer::client_handshake(reader, writer, &self.security.fingerprint, &genesis_hash, params.network).await?;
+
+        let mut headers = Vec::new();
+        loop {
+            let batch = Self::request_headers(reader, writer, tip.index + 1).await?;
+            validate_header_chain(&tip, &batch, &params)?;
+            let complete = batch.len() < MAX_HEADERS_PER_REQUEST;
+            if let Some(last) = batch.last() {
+                tip = last.clone();
+            }
+            headers.extend(batch);
+            if complete {
+                break;
+            }
+        }
+        println!("📑 {} headers validated, downloading bodies...", headers.len());
+
+        let mut applied = 0;
+        for chunk in headers.chunks(self.batch_size.max(1)) {
+            let blocks = Self::request_bodies(reader, writer, chunk).await?;
+            let mut chain = self.blockchain.write().await;
+            for block in blocks {
+                let index = block.index;
+                if !chain.validate_new_block(&block) || !chain.append_if_tip(block) {
+                    return Err(anyhow::anyhow!("block {} rejected after header validation", index));
+                }
+                applied += 1;
+            }
+        }
+
+        Ok(applied)
+    }
+
+    /// Demander les en-têtes à partir de `from_height`
+    pub(crate) async fn request_headers<R, W>(
+        reader: &mut R,
+        writer: &mut W,
+        from_height: u64,
+    ) -> Result<Vec<BlockHeader>, anyhow::Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        P2PServer::send_message(writer, &P2PMessage::RequestHeaders { from_height }).await?;
+
+        loop {
+            match P2PServer::read_message(reader, MAX_RESPONSE_SIZE).await? {
+                P2PMessage::SendHeaders { headers } => return Ok(headers),
+                // FeeFilter (suivi par `Heartbeat` et `TxRelay`) et autres annonces : ignorés
+                _ => continue,
+            }
+        }
+    }
+
+    /// Télécharger les corps des blocs correspondant à `headers` (hauteurs consécutives)
+    /// et vérifier que chacun correspond à son en-tête
+    pub(crate) async fn request_bodies<R, W>(
+        reader: &mut R,
+        writer: &mut W,
+        headers: &[BlockHeader],
+    ) -> Result<Vec<Block>, anyhow::Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let (Some(first), Some(last)) = (headers.first(), headers.last()) else {
+            return Ok(Vec::new());
+        };
+        let request = P2PMessage::RequestBlocks { from_height: first.index, to_height: last.index };
+        P2PServer::send_message(writer, &request).await?;
+
+        let blocks = loop {
+            match P2PServer::read_message(reader, MAX_RESPONSE_SIZE).await? {
+                P2PMessage::SendBlocks { blocks } => break blocks,
+                _ => continue,
+            }
+        };
+
+        if blocks.len() != headers.len() {
+            return Err(anyhow::anyhow!("expected {} blocks, received {}", headers.len(), blocks.len()));
+        }
+        if let Some(block) = blocks.iter().zip(headers).find(|(block, header)| !block.matches_header(header)) {
+            return Err(anyhow::anyhow!("block {} does not match its header", block.0.index));
+        }
+
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::genesis::create_genesis_block_with_params;
+    use crate::p2p::server::PeerSession;
+    use crate::security::{RateLimiter, SecurityLogger};
+    use crate::utils::config::{ConsensusParams, Network};
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_headers_first_then_bodies_for_subrange() {
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut local = Blockchain::with_params(params.clone());
+        local.chain.push(create_genesis_block_with_params(&params));
+        let genesis_hash = local.chain[0].hash.clone();
+        let tip = local.chain[0].header();
+
+        let mut remote = local.clone();
+        for miner in ["AUR1Alice", "AUR1Bob", "AUR1Carol", "AUR1Dave", "AUR1Erin"] {
+            remote.mine_pending_transactions(miner.to_string());
+        }
+        let remote = Arc::new(RwLock::new(remote));
+
+        let (server_side, client_side) = tokio::io::duplex(1024 * 1024);
+        let server_chain = remote.clone();
+        let server = tokio::spawn(async move {
+            let (mut reader, mut writer) = tokio::io::split(server_side);
+            let peer: SocketAddr = "127.0.0.1:40002".parse().unwrap();
+            P2PServer::perform_handshake(&mut reader, &mut writer, peer, "remote", &server_chain).await.unwrap();
+            P2PServer::serve_messages(
+                &mut reader,
+                &mut writer,
+                peer,
+                &server_chain,
+                &Arc::new(Mutex::new(RateLimiter::new())),
+                &SecurityLogger::new(),
+                PeerSession::default(),
+            ).await
+        });
+
+        let (mut reader, mut writer) = tokio::io::split(client_side);
+        P2PServer::client_handshake(&mut reader, &mut writer, "local", &genesis_hash, Network::Mainnet).await.unwrap();
+
+        let headers = HeaderSync::request_headers(&mut reader, &mut writer, 1).await.unwrap();
+        assert_eq!(headers.len(), 5);
+        validate_header_chain(&tip, &headers, &params).unwrap();
+
+        // Un en-tête altéré casse la chaîne
+        let mut forged = headers.clone();
+        forged[2].nonce += 1;
+        assert!(validate_header_chain(&tip, &forged, &params).is_err());
+
+        let bodies = HeaderSync::request_bodies(&mut reader, &mut writer, &headers[1..4]).await.unwrap();
+        assert_eq!(bodies.iter().map(|block| block.index).collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(bodies[0].miner_address, "AUR1Bob");
+
+        // Les corps doivent correspondre aux en-têtes demandés
+        assert!(HeaderSync::request_bodies(&mut reader, &mut writer, &forged[2..3]).await.is_err());
+
+        tokio::io::AsyncWriteExt::shutdown(&mut writer).await.unwrap();
+        assert!(server.await.unwrap().is_ok());
+    }
+}
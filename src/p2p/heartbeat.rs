@@ -0,0 +1,219 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use crate::blockchain::Blockchain;
+use crate::p2p::messages::P2PMessage;
+use crate::p2p::server::MAX_HANDSHAKE_SIZE;
+use crate::p2p::{NetworkSecurity, P2PServer, PeerManager};
+use crate::security::{SecurityEventType, SecurityLogger};
+use crate::utils::config::Network;
+
+/// Annonces d'un peer recueillies par un ping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerStatus {
+    /// Longueur de chaîne annoncée dans son handshake
+    pub chain_length: u64,
+    /// Plancher de frais de son dernier `FeeFilter`, s'il en a envoyé un
+    pub min_fee_rate: Option<u64>,
+}
+
+/// Ping périodique des peers et éviction de ceux qui ne répondent plus
+pub struct Heartbeat {
+    peer_manager: Arc<PeerManager>,
+    blockchain: Arc<RwLock<Blockchain>>,
+    security: Arc<NetworkSecurity>,
+    pub security_logger: Arc<SecurityLogger>,
+    /// Intervalle entre deux tournées de ping
+    pub interval: Duration,
+    /// Un peer sans nouvelles depuis plus longtemps est évincé
+    pub max_age_secs: u64,
+    pub ping_timeout: Duration,
+}
+
+impl Heartbeat {
+    pub fn new(
+        peer_manager: Arc<PeerManager>,
+        blockchain: Arc<RwLock<Blockchain>>,
+        security: Arc<NetworkSecurity>,
+    ) -> Self {
+        Self {
+            peer_manager,
+            blockchain,
+            security,
+            security_logger: Arc::new(SecurityLogger::new()),
+            interval: Duration::from_secs(30),
+            max_age_secs: 300,
+            ping_timeout: Duration::from_secs(10),
+        }
+    }
+
+    pub async fn run(&self, shutdown: Arc<AtomicBool>) {
+        while !shutdown.load(Ordering::SeqCst) {
+            tokio::time::sleep(self.interval).await;
+            self.tick().await;
+        }
+    }
+
+    /// Une tournée : ping de chaque peer puis éviction des peers périmés
+    pub async fn tick(&self) -> Vec<SocketAddr> {
+        self.ping_all().await;
+
+        let evicted = self.peer_manager.evict_stale(self.max_age_secs).await;
+        for addr in &evicted {
+            self.security_logger.log_event(
+                SecurityEventType::PeerDisconnected,
+                &addr.to_string(),
+                &format!("no response for more than {}s", self.max_age_secs),
+            );
+        }
+        evicted
+    }
+
+    /// Ping de chaque peer ; la longueur de chaîne annoncée dans son handshake est
+    /// reportée dans `PeerConnection.chain_length` (voir `PeerManager::get_best_peer`),
+    /// son plancher de frais dans `PeerConnection.fee_filter` (voir `TxRelay::broadcast`)
+    pub async fn ping_all(&self) {
+        for addr in self.peer_manager.get_all_peers().await {
+            match tokio::time::timeout(self.ping_timeout, self.ping_peer(addr)).await {
+                Ok(Ok(status)) => {
+                    self.peer_manager.mark_seen(addr).await;
+                    self.peer_manager.update_peer_chain_length(addr, status.chain_length).await;
+                    if let Some(min_fee_rate) = status.min_fee_rate {
+                        self.peer_manager.update_peer_fee_filter(addr, min_fee_rate).await;
+                    }
+                },
+                Ok(Err(e)) => println!("💔 Ping failed for peer {}: {}", addr, e),
+                Err(_) => println!("💔 Ping timeout for peer {}", addr),
+            }
+        }
+    }
+
+    async fn ping_peer(&self, addr: SocketAddr) -> Result<PeerStatus, anyhow::Error> {
+        let stream = TcpStream::connect(addr).await?;
+        let server_name = rustls::ServerName::try_from("auriumchain-node")?;
+        let tls_stream = self.security.tls_connector.connect(server_name, stream).await?;
+        let (mut reader, mut writer) = tokio::io::split(tls_stream);
+
+        let (genesis_hash, network) = {
+            let chain = self.blockchain.read().await;
+            let genesis_hash = chain.chain.first()
+                .map(|b| b.hash.clone())
+                .unwrap_or_else(|| "none".to_string());
+            (genesis_hash, chain.params.network)
+        };
+        Self::ping_over(&mut reader, &mut writer, &self.security.fingerprint, &genesis_hash, network).await
+    }
+
+    /// Côté client du protocole : handshake, `Ping`, attente du `Pong`.
+    /// Retourne la longueur de chaîne et le plancher de frais annoncés par le peer.
+    pub(crate) async fn ping_over<R, W>(
+        reader: &mut R,
+        writer: &mut W,
+        node_id: &str,
+        genesis_hash: &str,
+        network: Network,
+    ) -> Result<PeerStatus, anyhow::Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let chain_length = P2PServer::client_handshake(reader, writer, node_id, genesis_hash, network).await?;
+        P2PServer::send_message(writer, &P2PMessage::Ping).await?;
+
+        let mut min_fee_rate = None;
+        loop {
+            match P2PServer::read_message(reader, MAX_HANDSHAKE_SIZE).await? {
+                P2PMessage::Pong => return Ok(PeerStatus { chain_length, min_fee_rate }),
+                P2PMessage::FeeFilter { min_fee_rate: rate } => min_fee_rate = Some(rate),
+                _ => continue,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p2p::PeerDirection;
+    use crate::p2p::server::PeerSession;
+    use crate::security::RateLimiter;
+    use crate::utils::config::ConsensusParams;
+    use std::sync::Mutex;
+
+    fn chain_of(blocks: usize) -> Arc<RwLock<Blockchain>> {
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(crate::blockchain::genesis::create_genesis_block_with_params(&params));
+        for _ in 1..blocks {
+            chain.mine_pending_transactions("AUR1Miner".to_string());
+        }
+        Arc::new(RwLock::new(chain))
+    }
+
+    /// Boucle serveur d'un peer sur un canal en mémoire ; retourne le côté client
+    fn spawn_peer(
+        blockchain: Arc<RwLock<Blockchain>>,
+    ) -> (tokio::io::DuplexStream, tokio::task::JoinHandle<Result<(), anyhow::Error>>) {
+        let (server_side, client_side) = tokio::io::duplex(64 * 1024);
+        let server = tokio::spawn(async move {
+            let (mut reader, mut writer) = tokio::io::split(server_side);
+            let peer: SocketAddr = "127.0.0.1:40000".parse().unwrap();
+            P2PServer::perform_handshake(&mut reader, &mut writer, peer, "server-node", &blockchain).await.unwrap();
+            P2PServer::serve_messages(
+                &mut reader,
+                &mut writer,
+                peer,
+                &blockchain,
+                &Arc::new(Mutex::new(RateLimiter::new())),
+                &SecurityLogger::new(),
+                PeerSession::default(),
+            ).await
+        });
+        (client_side, server)
+    }
+
+    #[tokio::test]
+    async fn test_ping_over_gets_pong_from_server_loop() {
+        let blockchain = chain_of(1);
+        let genesis_hash = blockchain.read().await.chain[0].hash.clone();
+        let blockchain_floor = blockchain.read().await.mempool.min_fee_rate();
+        let (client_side, server) = spawn_peer(blockchain);
+
+        let (mut reader, mut writer) = tokio::io::split(client_side);
+        let status = Heartbeat::ping_over(&mut reader, &mut writer, "client-node", &genesis_hash, Network::Mainnet).await.unwrap();
+        assert_eq!(status.chain_length, 1);
+        assert_eq!(status.min_fee_rate, Some(blockchain_floor));
+
+        drop((reader, writer));
+        assert!(server.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_taller_peer_chosen_for_initial_sync() {
+        let short = chain_of(2);
+        let tall = chain_of(4);
+        let genesis_hash = short.read().await.chain[0].hash.clone();
+        assert_eq!(genesis_hash, tall.read().await.chain[0].hash);
+
+        let peer_manager = PeerManager::new_deterministic(10, 10);
+        let short_addr: SocketAddr = "10.0.0.1:3001".parse().unwrap();
+        let tall_addr: SocketAddr = "10.0.0.2:3001".parse().unwrap();
+        peer_manager.add_peer(short_addr, PeerDirection::Outbound).await;
+        peer_manager.add_peer(tall_addr, PeerDirection::Outbound).await;
+        assert_eq!(peer_manager.sync_order().await, vec![short_addr, tall_addr]);
+
+        for (addr, blockchain) in [(short_addr, short), (tall_addr, tall)] {
+            let (client_side, _server) = spawn_peer(blockchain);
+            let (mut reader, mut writer) = tokio::io::split(client_side);
+            let status = Heartbeat::ping_over(&mut reader, &mut writer, "client-node", &genesis_hash, Network::Mainnet).await.unwrap();
+            peer_manager.update_peer_chain_length(addr, status.chain_length).await;
+        }
+
+        assert_eq!(peer_manager.get_best_peer().await, Some(tall_addr));
+        assert_eq!(peer_manager.sync_order().await, vec![tall_addr, short_addr]);
+    }
+}
@@ -1,14 +1,33 @@
-use serde::{Serialize, Deserialize};
-use crate::blockchain::Block;
+use bincode::Options;
+use serde::{Serialize, Deserialize, Deserializer};
+use serde::de::{self, SeqAccess, Visitor};
+use std::marker::PhantomData;
+use crate::blockchain::{Block, BlockHeader, Transaction};
+use crate::p2p::bloom::{FilteredBlock, MAX_BLOOM_FILTER_SIZE};
+use crate::p2p::compression::Compression;
+use crate::p2p::server::{MAX_BLOCKS_PER_REQUEST, MAX_HEADERS_PER_REQUEST};
+
+/// Version du protocole P2P annoncée dans le handshake
+pub const PROTOCOL_VERSION: &str = "1.0";
+
+/// Deux versions sont compatibles si elles partagent la même version majeure
+pub fn is_compatible_version(version: &str) -> bool {
+    let major = |v: &str| v.split('.').next().map(str::to_string);
+    major(version).is_some_and(|m| Some(m) == major(PROTOCOL_VERSION))
+}
 
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(tag = "type")]
 pub enum P2PMessage {
     Handshake { 
         version: String, 
         chain_length: u64,
         peer_id: String,
         genesis_hash: String,
+        /// `Network::network_id` : mainnet, testnet et devnet ne se connectent pas entre eux
+        network_id: String,
+        /// Le nœud accepte des trames compressées avec `compression`
+        supports_compression: bool,
+        compression: Compression,
     },
     ChainLengthQuery,
     ChainLengthResponse { length: u64 },
@@ -17,6 +36,7 @@ pub enum P2PMessage {
         to_height: u64 
     },
     SendBlocks { 
+        #[serde(deserialize_with = "bounded_vec::<_, _, MAX_BLOCKS_PER_REQUEST>")]
         blocks: Vec<Block> 
     },
     NewBlock { 
@@ -24,6 +44,33 @@ pub enum P2PMessage {
     },
     Ping,
     Pong,
+    /// Taux de frais minimum (satoshis/byte) en dessous duquel le nœud refuse de relayer
+    FeeFilter {
+        min_fee_rate: u64
+    },
+    /// Filtre de Bloom d'un client léger : les blocs demandés ensuite sont servis filtrés
+    SetBloomFilter {
+        #[serde(deserialize_with = "bounded_vec::<_, _, MAX_BLOOM_FILTER_SIZE>")]
+        filter: Vec<u8>,
+        hash_funcs: u32,
+        tweak: u32,
+    },
+    FilteredBlocks {
+        #[serde(deserialize_with = "bounded_vec::<_, _, MAX_BLOCKS_PER_REQUEST>")]
+        blocks: Vec<FilteredBlock>
+    },
+    /// Transaction relayée de mempool à mempool
+    NewTransaction {
+        tx: Transaction
+    },
+    /// Synchronisation par en-têtes : en-têtes à partir de `from_height`
+    RequestHeaders {
+        from_height: u64
+    },
+    SendHeaders {
+        #[serde(deserialize_with = "bounded_vec::<_, _, MAX_HEADERS_PER_REQUEST>")]
+        headers: Vec<BlockHeader>
+    },
 }
 
 impl P2PMessage {
@@ -31,7 +78,86 @@ impl P2PMessage {
         bincode::serialize(self).unwrap_or_default()
     }
     
+    /// Décoder un message reçu : même format que `serialize`, mais bincode ne lit pas
+    /// plus d'octets que `data` n'en contient, quelles que soient les longueurs annoncées
+    /// (une chaîne d'un milliard d'octets échoue au lieu d'être allouée), et les listes
+    /// au-delà de leur plafond protocolaire sont refusées avant toute allocation.
     pub fn deserialize(data: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(bincode::deserialize(data)?)
+        Ok(bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .with_limit(data.len() as u64)
+            .deserialize(data)?)
+    }
+}
+
+/// Liste d'au plus `MAX` éléments ; la longueur annoncée est vérifiée avant de lire
+fn bounded_vec<'de, D, T, const MAX: usize>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    struct BoundedVisitor<T, const MAX: usize>(PhantomData<T>);
+
+    impl<'de, T: Deserialize<'de>, const MAX: usize> Visitor<'de> for BoundedVisitor<T, MAX> {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(formatter, "a sequence of at most {} elements", MAX)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+            let claimed = seq.size_hint().unwrap_or(0);
+            if claimed > MAX {
+                return Err(de::Error::invalid_length(claimed, &self));
+            }
+            let mut items = Vec::with_capacity(claimed);
+            while let Some(item) = seq.next_element()? {
+                if items.len() == MAX {
+                    return Err(de::Error::invalid_length(MAX + 1, &self));
+                }
+                items.push(item);
+            }
+            Ok(items)
+        }
+    }
+
+    deserializer.deserialize_seq(BoundedVisitor::<T, MAX>(PhantomData))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trame forgée : variante `variant` (u32) suivie d'une longueur `u64` annoncée, sans données
+    fn claimed_length(variant: u32, length: u64) -> Vec<u8> {
+        let mut payload = variant.to_le_bytes().to_vec();
+        payload.extend_from_slice(&length.to_le_bytes());
+        payload
+    }
+
+    #[test]
+    fn test_huge_length_prefix_fails_without_allocating() {
+        // SendBlocks annonçant un milliard de blocs
+        let err = P2PMessage::deserialize(&claimed_length(4, 1_000_000_000)).unwrap_err();
+        assert!(err.to_string().contains("at most 500 elements"), "{}", err);
+
+        // Handshake dont la version annonce une chaîne de 2^64 - 1 octets : fin de données
+        assert!(P2PMessage::deserialize(&claimed_length(0, u64::MAX)).is_err());
+
+        // Juste au-dessus du plafond des en-têtes
+        let err = P2PMessage::deserialize(&claimed_length(13, MAX_HEADERS_PER_REQUEST as u64 + 1)).unwrap_err();
+        assert!(err.to_string().contains("at most 2000 elements"), "{}", err);
+    }
+
+    #[test]
+    fn test_round_trip_within_limits() {
+        let message = P2PMessage::RequestBlocks { from_height: 3, to_height: 6 };
+        match P2PMessage::deserialize(&message.serialize()).unwrap() {
+            P2PMessage::RequestBlocks { from_height, to_height } => assert_eq!((from_height, to_height), (3, 6)),
+            other => panic!("unexpected message: {:?}", other),
+        }
+        let headers = P2PMessage::SendHeaders { headers: Vec::new() };
+        assert!(matches!(P2PMessage::deserialize(&headers.serialize()).unwrap(), P2PMessage::SendHeaders { .. }));
     }
 }
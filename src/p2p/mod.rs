@@ -1,3 +1,8 @@
+pub mod bloom;
+pub mod compression;
+pub mod gossip;
+pub mod headers;
+pub mod heartbeat;
 pub mod messages;
 pub mod peer_manager;
 pub mod server;
@@ -10,3 +15,7 @@ pub use peer_manager::*;
 pub use server::*;
 pub use security::*;
 pub use sync::{SyncManager, BlockchainSync};
+pub use heartbeat::{Heartbeat, PeerStatus};
+pub use gossip::TxRelay;
+pub use headers::HeaderSync;
+pub use bloom::{BloomFilter, FilteredBlock};
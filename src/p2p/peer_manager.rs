@@ -1,11 +1,23 @@
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::net::TcpStream;
 use tokio::sync::RwLock;
 
 pub struct PeerManager {
     peers: RwLock<HashMap<SocketAddr, PeerConnection>>,
-    max_peers: usize,
+    max_inbound: usize,
+    max_outbound: usize,
+    deterministic_order: bool,
+}
+
+/// Sens de la connexion : un peer entrant nous a contactés, un peer sortant a été
+/// choisi par le nœud (`--peer`, seeds). Chaque sens a ses propres places, pour qu'un
+/// afflux de connexions entrantes ne puisse pas évincer les peers choisis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerDirection {
+    Inbound,
+    Outbound,
 }
 
 pub struct PeerConnection {
@@ -13,20 +25,59 @@ pub struct PeerConnection {
     pub stream: Option<TcpStream>,
     pub last_seen: u64,
     pub chain_length: u64,
+    pub direction: PeerDirection,
+    /// Dernier `FeeFilter` annoncé (satoshis/byte) : rien en dessous ne lui est relayé
+    pub fee_filter: u64,
 }
 
 impl PeerManager {
-    pub fn new(max_peers: usize) -> Self {
+    pub fn new(max_inbound: usize, max_outbound: usize) -> Self {
         Self {
             peers: RwLock::new(HashMap::new()),
-            max_peers,
+            max_inbound,
+            max_outbound,
+            deterministic_order: false,
+        }
+    }
+
+    /// Mode déterministe : les peers sont toujours parcourus triés par adresse.
+    ///
+    /// Réservé aux tests et au développement (réseaux multi-nœuds reproductibles).
+    /// En production, l'ordre de la `HashMap` évite que tous les nœuds
+    /// sollicitent les mêmes peers en premier.
+    pub fn new_deterministic(max_inbound: usize, max_outbound: usize) -> Self {
+        Self {
+            deterministic_order: true,
+            ..Self::new(max_inbound, max_outbound)
         }
     }
     
-    pub async fn add_peer(&self, addr: SocketAddr) -> bool {
+    fn max_for(&self, direction: PeerDirection) -> usize {
+        match direction {
+            PeerDirection::Inbound => self.max_inbound,
+            PeerDirection::Outbound => self.max_outbound,
+        }
+    }
+    
+    /// Nombre de peers connectés dans ce sens
+    pub async fn count(&self, direction: PeerDirection) -> usize {
+        self.peers.read().await.values()
+            .filter(|peer| peer.direction == direction)
+            .count()
+    }
+    
+    /// Toutes les places de ce sens sont prises
+    pub async fn is_full(&self, direction: PeerDirection) -> bool {
+        self.count(direction).await >= self.max_for(direction)
+    }
+    
+    /// Ajouter un peer dans le sens `direction` ; refusé si ses places sont prises
+    /// (même s'il en reste dans l'autre sens) ou si le peer est déjà connu
+    pub async fn add_peer(&self, addr: SocketAddr, direction: PeerDirection) -> bool {
         let mut peers = self.peers.write().await;
         
-        if peers.len() >= self.max_peers {
+        let connected = peers.values().filter(|peer| peer.direction == direction).count();
+        if connected >= self.max_for(direction) {
             return false;
         }
         
@@ -34,22 +85,65 @@ impl PeerManager {
             let peer = PeerConnection {
                 addr,
                 stream: None,
-                last_seen: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs(),
+                last_seen: now_secs(),
                 chain_length: 0,
+                direction,
+                fee_filter: 0,
             };
             peers.insert(addr, peer);
-            println!("Added peer: {}", addr);
+            println!("Added {:?} peer: {}", direction, addr);
             true
         } else {
             false
         }
     }
     
+    pub async fn remove_peer(&self, addr: SocketAddr) -> bool {
+        self.peers.write().await.remove(&addr).is_some()
+    }
+    
+    /// Réserver une place pour `addr` le temps d'une connexion, atomiquement (voir `add_peer`).
+    /// La place est libérée quand le `PeerSlot` retourné est abandonné, quelle que soit la
+    /// façon dont la connexion se termine. `None` si les places de ce sens sont prises.
+    pub async fn reserve(self: &Arc<Self>, addr: SocketAddr, direction: PeerDirection) -> Option<PeerSlot> {
+        self.add_peer(addr, direction).await.then(|| PeerSlot { peer_manager: self.clone(), addr })
+    }
+    
+    /// Noter qu'on vient d'avoir des nouvelles du peer
+    pub async fn mark_seen(&self, addr: SocketAddr) {
+        self.mark_seen_at(addr, now_secs()).await;
+    }
+    
+    pub async fn mark_seen_at(&self, addr: SocketAddr, now: u64) {
+        if let Some(peer) = self.peers.write().await.get_mut(&addr) {
+            peer.last_seen = now;
+        }
+    }
+    
+    /// Retirer les peers sans nouvelles depuis plus de `max_age_secs` ; retourne les peers évincés
+    pub async fn evict_stale(&self, max_age_secs: u64) -> Vec<SocketAddr> {
+        self.evict_stale_at(now_secs(), max_age_secs).await
+    }
+    
+    pub async fn evict_stale_at(&self, now: u64, max_age_secs: u64) -> Vec<SocketAddr> {
+        let mut peers = self.peers.write().await;
+        let stale: Vec<SocketAddr> = peers.values()
+            .filter(|peer| now.saturating_sub(peer.last_seen) > max_age_secs)
+            .map(|peer| peer.addr)
+            .collect();
+        
+        for addr in &stale {
+            peers.remove(addr);
+        }
+        stale
+    }
+    
     pub async fn get_peers(&self) -> Vec<SocketAddr> {
-        self.peers.read().await.keys().cloned().collect()
+        let mut addrs: Vec<SocketAddr> = self.peers.read().await.keys().cloned().collect();
+        if self.deterministic_order {
+            addrs.sort();
+        }
+        addrs
     }
     
     pub async fn update_peer_chain_length(&self, addr: SocketAddr, length: u64) {
@@ -59,18 +153,182 @@ impl PeerManager {
         }
     }
     
+    pub async fn update_peer_fee_filter(&self, addr: SocketAddr, min_fee_rate: u64) {
+        let mut peers = self.peers.write().await;
+        if let Some(peer) = peers.get_mut(&addr) {
+            peer.fee_filter = min_fee_rate;
+        }
+    }
+    
+    /// Peers dont le `FeeFilter` accepte une transaction payant `fee_rate` satoshis/byte
+    pub async fn peers_accepting(&self, fee_rate: u64) -> Vec<SocketAddr> {
+        let mut addrs: Vec<SocketAddr> = self.peers.read().await.values()
+            .filter(|peer| peer.fee_filter <= fee_rate)
+            .map(|peer| peer.addr)
+            .collect();
+        if self.deterministic_order {
+            addrs.sort();
+        }
+        addrs
+    }
+    
     pub async fn get_best_peer(&self) -> Option<SocketAddr> {
         let peers = self.peers.read().await;
+        if self.deterministic_order {
+            // À longueur égale, la plus petite adresse l'emporte
+            return peers.values()
+                .max_by(|a, b| a.chain_length.cmp(&b.chain_length).then(b.addr.cmp(&a.addr)))
+                .map(|p| p.addr);
+        }
         peers.values()
             .max_by_key(|p| p.chain_length)
             .map(|p| p.addr)
     }
+
+    /// Ordre de synchronisation au démarrage : le peer à la plus longue chaîne
+    /// (`get_best_peer`) d'abord, puis les autres
+    pub async fn sync_order(&self) -> Vec<SocketAddr> {
+        let mut peers = self.get_peers().await;
+        if let Some(best) = self.get_best_peer().await {
+            peers.retain(|addr| *addr != best);
+            peers.insert(0, best);
+        }
+        peers
+    }
+}
+
+/// Place réservée par `PeerManager::reserve`, rendue à l'abandon
+pub struct PeerSlot {
+    peer_manager: Arc<PeerManager>,
+    addr: SocketAddr,
+}
+
+impl Drop for PeerSlot {
+    fn drop(&mut self) {
+        // Libération immédiate si le verrou est libre, sinon différée
+        match self.peer_manager.peers.try_write() {
+            Ok(mut peers) => {
+                peers.remove(&self.addr);
+            }
+            Err(_) => {
+                let peer_manager = self.peer_manager.clone();
+                let addr = self.addr;
+                tokio::spawn(async move { peer_manager.remove_peer(addr).await });
+            }
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
 }
 
 impl PeerManager {
     pub async fn get_all_peers(&self) -> Vec<std::net::SocketAddr> {
-        // Pour l'instant, retourner une liste vide
-        // À implémenter avec la vraie liste des peers connectés
-        vec![]
+        self.get_peers().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_peer_order() {
+        let peers = [
+            "10.0.0.7:3001", "10.0.0.2:3001", "192.168.1.5:3002",
+            "10.0.0.2:3000", "172.16.0.9:3003", "10.0.0.11:3001",
+        ];
+
+        let forward = PeerManager::new_deterministic(10, 10);
+        for p in peers.iter() {
+            forward.add_peer(addr(p), PeerDirection::Outbound).await;
+        }
+
+        let reverse = PeerManager::new_deterministic(10, 10);
+        for p in peers.iter().rev() {
+            reverse.add_peer(addr(p), PeerDirection::Outbound).await;
+        }
+
+        let order = forward.get_all_peers().await;
+        assert_eq!(order, reverse.get_all_peers().await);
+        assert_eq!(order, forward.get_all_peers().await);
+
+        let mut expected: Vec<SocketAddr> = peers.iter().map(|p| addr(p)).collect();
+        expected.sort();
+        assert_eq!(order, expected);
+    }
+
+    #[tokio::test]
+    async fn test_evict_stale_peers() {
+        let manager = PeerManager::new_deterministic(10, 10);
+        manager.add_peer(addr("10.0.0.1:3001"), PeerDirection::Outbound).await;
+        manager.add_peer(addr("10.0.0.2:3001"), PeerDirection::Outbound).await;
+        
+        let start = now_secs();
+        manager.mark_seen_at(addr("10.0.0.1:3001"), start).await;
+        manager.mark_seen_at(addr("10.0.0.2:3001"), start + 90).await;
+        
+        // 100 s plus tard, seul le second a répondu dans la fenêtre de 60 s
+        let evicted = manager.evict_stale_at(start + 100, 60).await;
+        assert_eq!(evicted, vec![addr("10.0.0.1:3001")]);
+        assert_eq!(manager.get_all_peers().await, vec![addr("10.0.0.2:3001")]);
+        
+        assert!(manager.remove_peer(addr("10.0.0.2:3001")).await);
+        assert!(!manager.remove_peer(addr("10.0.0.2:3001")).await);
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_best_peer_tie_break() {
+        let manager = PeerManager::new_deterministic(10, 10);
+        manager.add_peer(addr("10.0.0.9:3001"), PeerDirection::Outbound).await;
+        manager.add_peer(addr("10.0.0.3:3001"), PeerDirection::Outbound).await;
+        manager.update_peer_chain_length(addr("10.0.0.9:3001"), 42).await;
+        manager.update_peer_chain_length(addr("10.0.0.3:3001"), 42).await;
+
+        assert_eq!(manager.get_best_peer().await, Some(addr("10.0.0.3:3001")));
+    }
+
+    #[tokio::test]
+    async fn test_inbound_flood_leaves_outbound_slots() {
+        let manager = PeerManager::new_deterministic(3, 2);
+        for i in 1..=3 {
+            assert!(manager.add_peer(addr(&format!("10.0.0.{}:40000", i)), PeerDirection::Inbound).await);
+        }
+        
+        // Places entrantes pleines : le suivant est refusé, les sortantes restent libres
+        assert!(manager.is_full(PeerDirection::Inbound).await);
+        assert!(!manager.add_peer(addr("10.0.0.4:40000"), PeerDirection::Inbound).await);
+        assert!(manager.add_peer(addr("192.168.1.1:3001"), PeerDirection::Outbound).await);
+        assert!(manager.add_peer(addr("192.168.1.2:3001"), PeerDirection::Outbound).await);
+        assert!(!manager.add_peer(addr("192.168.1.3:3001"), PeerDirection::Outbound).await);
+        
+        assert_eq!(manager.count(PeerDirection::Inbound).await, 3);
+        assert_eq!(manager.count(PeerDirection::Outbound).await, 2);
+        assert_eq!(manager.get_all_peers().await.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_peers_accepting_respects_fee_filters() {
+        let manager = PeerManager::new_deterministic(10, 10);
+        for peer in ["10.0.0.1:3001", "10.0.0.2:3001", "10.0.0.3:3001"] {
+            manager.add_peer(addr(peer), PeerDirection::Outbound).await;
+        }
+        manager.update_peer_fee_filter(addr("10.0.0.2:3001"), 5).await;
+        manager.update_peer_fee_filter(addr("10.0.0.3:3001"), 20).await;
+
+        assert_eq!(manager.peers_accepting(5).await, vec![addr("10.0.0.1:3001"), addr("10.0.0.2:3001")]);
+        assert_eq!(manager.peers_accepting(20).await.len(), 3);
+
+        // Un plancher abaissé est pris en compte à l'annonce suivante
+        manager.update_peer_fee_filter(addr("10.0.0.3:3001"), 1).await;
+        assert_eq!(manager.peers_accepting(1).await.len(), 2);
     }
 }
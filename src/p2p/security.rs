@@ -1,23 +1,65 @@
+use std::fs;
+use std::path::Path;
 use std::sync::Arc;
+use sha2::{Digest, Sha256};
 use tokio_rustls::{TlsAcceptor, TlsConnector};
 use rustls::{Certificate, PrivateKey, ServerConfig, ClientConfig, RootCertStore};
 use rcgen::{Certificate as RcgenCert, CertificateParams, DistinguishedName};
 
+const CERT_FILE: &str = "node_cert.der";
+const KEY_FILE: &str = "node_key.der";
+
 pub struct NetworkSecurity {
     pub tls_acceptor: TlsAcceptor,
     pub tls_connector: TlsConnector,
+    /// Certificat du nœud (DER)
+    pub cert_der: Vec<u8>,
+    /// Empreinte SHA-256 (hex) du certificat : identité stable du nœud
+    pub fingerprint: String,
 }
 
 impl NetworkSecurity {
+    /// Identité éphémère : nouveau certificat auto-signé à chaque appel
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let (cert_der, key_der) = Self::generate_identity()?;
+        Self::from_der(cert_der, key_der)
+    }
+
+    /// Identité persistante : recharge le certificat et la clé de `dir`,
+    /// ou les génère et les sauvegarde au premier démarrage.
+    pub fn load_or_create(dir: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let cert_path = Path::new(dir).join(CERT_FILE);
+        let key_path = Path::new(dir).join(KEY_FILE);
+
+        if cert_path.exists() && key_path.exists() {
+            return Self::from_der(fs::read(&cert_path)?, fs::read(&key_path)?);
+        }
+
+        let (cert_der, key_der) = Self::generate_identity()?;
+        fs::create_dir_all(dir)?;
+        fs::write(&cert_path, &cert_der)?;
+        fs::write(&key_path, &key_der)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&key_path, fs::Permissions::from_mode(0o600))?;
+        }
+
+        println!("New node identity saved to {}", dir);
+        Self::from_der(cert_der, key_der)
+    }
+
+    fn generate_identity() -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>> {
         // Générer certificat auto-signé pour P2P
         let mut params = CertificateParams::new(vec!["auriumchain-node".to_string()]);
         params.distinguished_name = DistinguishedName::new();
         
         let cert = RcgenCert::from_params(params)?;
-        let cert_der = cert.serialize_der()?;
-        let key_der = cert.serialize_private_key_der();
-        
+        Ok((cert.serialize_der()?, cert.serialize_private_key_der()))
+    }
+
+    fn from_der(cert_der: Vec<u8>, key_der: Vec<u8>) -> Result<Self, Box<dyn std::error::Error>> {
         // Configuration serveur
         let cert_chain = vec![Certificate(cert_der.clone())];
         let private_key = PrivateKey(key_der);
@@ -29,7 +71,7 @@ impl NetworkSecurity {
         
         // Configuration client - accepte le certificat auto-signé
         let mut root_store = RootCertStore::empty();
-        root_store.add(&Certificate(cert_der))?;
+        root_store.add(&Certificate(cert_der.clone()))?;
         
         let client_config = ClientConfig::builder()
             .with_safe_defaults()
@@ -39,6 +81,29 @@ impl NetworkSecurity {
         Ok(Self {
             tls_acceptor: TlsAcceptor::from(Arc::new(server_config)),
             tls_connector: TlsConnector::from(Arc::new(client_config)),
+            fingerprint: hex::encode(Sha256::digest(&cert_der)),
+            cert_der,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_persists_across_loads() {
+        let dir = std::env::temp_dir().join(format!("aur_identity_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let _ = fs::remove_dir_all(dir);
+
+        let first = NetworkSecurity::load_or_create(dir).unwrap();
+        let second = NetworkSecurity::load_or_create(dir).unwrap();
+
+        assert_eq!(first.cert_der, second.cert_der);
+        assert_eq!(first.fingerprint, second.fingerprint);
+        assert_ne!(first.fingerprint, NetworkSecurity::new().unwrap().fingerprint);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,16 +1,100 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::net::SocketAddr;
+use std::time::Duration;
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::RwLock;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use crate::blockchain::Blockchain;
-use crate::p2p::{messages::P2PMessage, PeerManager, NetworkSecurity};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use crate::blockchain::{BlockOutcome, Blockchain};
+use crate::p2p::{messages::P2PMessage, PeerDirection, PeerManager, NetworkSecurity};
+use crate::p2p::bloom::{BloomFilter, FilteredBlock};
+use crate::p2p::compression::{self, Compression};
+use crate::p2p::gossip::TxRelay;
+use crate::p2p::messages::{PROTOCOL_VERSION, is_compatible_version};
+use crate::security::{PeerEvent, PeerReputation, RateLimiter, SecurityLogger, SecurityEventType, SignatureValidator};
+use crate::utils::config::Network;
+
+/// Taille maximale acceptée pour le handshake d'un peer
+pub(crate) const MAX_HANDSHAKE_SIZE: usize = 64 * 1024;
+/// Délai maximum pour recevoir le handshake d'un peer
+const HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+/// Délai par défaut sans aucun message d'un peer avant de fermer la connexion
+/// (le heartbeat d'un peer actif envoie un ping bien avant)
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 120;
+/// Délai par défaut pour écrire une réponse à un peer qui ne lit pas
+pub const DEFAULT_WRITE_TIMEOUT_SECS: u64 = 30;
+/// Délai d'inactivité TCP avant les sondes keepalive du système
+const TCP_KEEPALIVE_SECS: u64 = 60;
+/// Nombre maximum de blocs servis par `RequestBlocks`
+pub const MAX_BLOCKS_PER_REQUEST: usize = crate::blockchain::chain::MAX_BLOCKS_PER_RANGE as usize;
+/// Nombre maximum d'en-têtes servis par `RequestHeaders`
+pub(crate) const MAX_HEADERS_PER_REQUEST: usize = 2000;
+
+/// Délais d'une connexion P2P : un peer muet ou qui ne lit plus ses réponses
+/// ne doit pas monopoliser une tâche indéfiniment (slowloris)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConnectionTimeouts {
+    /// Temps maximum pour recevoir un message complet
+    pub idle: Duration,
+    /// Temps maximum pour envoyer une réponse
+    pub write: Duration,
+}
+
+impl Default for ConnectionTimeouts {
+    fn default() -> Self {
+        ConnectionTimeouts {
+            idle: Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS),
+            write: Duration::from_secs(DEFAULT_WRITE_TIMEOUT_SECS),
+        }
+    }
+}
+
+/// État propre à une connexion entrante : compression négociée au handshake,
+/// relais des transactions reçues (`None` pour ne pas relayer), délais et
+/// réputation des peers (`None` : aucune pénalité)
+#[derive(Default)]
+pub(crate) struct PeerSession<'a> {
+    pub compression: Compression,
+    pub relay: Option<&'a Arc<TxRelay>>,
+    pub timeouts: ConnectionTimeouts,
+    pub reputation: Option<&'a PeerReputation>,
+}
+
+/// Erreur de lecture d'un message préfixé par sa longueur
+#[derive(Debug)]
+pub enum FrameError {
+    /// Le peer a fermé la connexion avant un nouveau message
+    Closed,
+    /// Longueur annoncée supérieure au maximum autorisé (rien n'est alloué)
+    Oversized { length: usize, max: usize },
+    Io(std::io::Error),
+    Decode(String),
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameError::Closed => write!(f, "connection closed"),
+            FrameError::Oversized { length, max } => {
+                write!(f, "message too large: {} bytes (max {})", length, max)
+            },
+            FrameError::Io(e) => write!(f, "io error: {}", e),
+            FrameError::Decode(e) => write!(f, "decode error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
 
 pub struct P2PServer {
     blockchain: Arc<RwLock<Blockchain>>,
     peer_manager: Arc<PeerManager>,
     security: Arc<NetworkSecurity>,
     bind_addr: SocketAddr,
+    pub security_logger: Arc<SecurityLogger>,
+    pub rate_limiter: Arc<Mutex<RateLimiter>>,
+    /// Pénalités des peers, qui bannissent via `rate_limiter`
+    pub reputation: Arc<PeerReputation>,
+    pub timeouts: ConnectionTimeouts,
 }
 
 impl P2PServer {
@@ -20,11 +104,16 @@ impl P2PServer {
         security: Arc<NetworkSecurity>,
         bind_addr: SocketAddr,
     ) -> Self {
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new()));
         Self {
             blockchain,
             peer_manager,
             security,
             bind_addr,
+            security_logger: Arc::new(SecurityLogger::new()),
+            reputation: Arc::new(PeerReputation::new(rate_limiter.clone())),
+            rate_limiter,
+            timeouts: ConnectionTimeouts::default(),
         }
     }
     
@@ -36,10 +125,17 @@ impl P2PServer {
             match listener.accept().await {
                 Ok((stream, peer_addr)) => {
                     println!("New TLS P2P connection from: {}", peer_addr);
+                    if let Err(e) = Self::enable_keepalive(&stream) {
+                        eprintln!("TCP keepalive not enabled for {}: {}", peer_addr, e);
+                    }
                     
                     let blockchain = self.blockchain.clone();
                     let peer_manager = self.peer_manager.clone();
                     let security = self.security.clone();
+                    let security_logger = self.security_logger.clone();
+                    let rate_limiter = self.rate_limiter.clone();
+                    let reputation = self.reputation.clone();
+                    let timeouts = self.timeouts;
                     
                     tokio::spawn(async move {
                         if let Err(e) = Self::handle_connection(
@@ -47,7 +143,11 @@ impl P2PServer {
                             peer_addr, 
                             blockchain, 
                             peer_manager,
-                            security
+                            security,
+                            security_logger,
+                            rate_limiter,
+                            reputation,
+                            timeouts,
                         ).await {
                             eprintln!("TLS P2P connection error {}: {}", peer_addr, e);
                         }
@@ -58,45 +158,369 @@ impl P2PServer {
         }
     }
     
+    /// Sondes keepalive TCP : détecte les peers disparus sans fermeture propre
+    fn enable_keepalive(stream: &TcpStream) -> std::io::Result<()> {
+        let keepalive = socket2::TcpKeepalive::new().with_time(Duration::from_secs(TCP_KEEPALIVE_SECS));
+        socket2::SockRef::from(stream).set_tcp_keepalive(&keepalive)
+    }
+    
+    #[allow(clippy::too_many_arguments)]
     async fn handle_connection(
         stream: TcpStream,
         peer_addr: SocketAddr,
         blockchain: Arc<RwLock<Blockchain>>,
         peer_manager: Arc<PeerManager>,
         security: Arc<NetworkSecurity>,
+        security_logger: Arc<SecurityLogger>,
+        rate_limiter: Arc<Mutex<RateLimiter>>,
+        reputation: Arc<PeerReputation>,
+        timeouts: ConnectionTimeouts,
     ) -> Result<(), anyhow::Error> {
-        // Upgrade to TLS
-        let tls_stream = security.tls_acceptor.accept(stream).await?;
+        // Place entrante réservée avant même le TLS et rendue à la fin de la connexion ;
+        // places pleines : refusé, les places sortantes restent libres
+        let Some(_slot) = peer_manager.reserve(peer_addr, PeerDirection::Inbound).await else {
+            let reason = "inbound peer slots full";
+            security_logger.log_event(SecurityEventType::ConnectionRefused, &peer_addr.to_string(), reason);
+            return Err(anyhow::anyhow!("Connection refused: {}", reason));
+        };
+        
+        // Upgrade to TLS, borné lui aussi : un client muet ne doit pas bloquer l'acceptation TLS
+        let tls_stream = tokio::time::timeout(
+            Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
+            security.tls_acceptor.accept(stream),
+        ).await.map_err(|_| anyhow::anyhow!("TLS handshake timeout"))??;
         let (mut reader, mut writer) = tokio::io::split(tls_stream);
         
-        peer_manager.add_peer(peer_addr).await;
+        let compression = match Self::perform_handshake(&mut reader, &mut writer, peer_addr, &security.fingerprint, &blockchain).await {
+            Ok(compression) => compression,
+            Err(reason) => {
+                security_logger.log_event(SecurityEventType::ConnectionRefused, &peer_addr.to_string(), &reason);
+                return Err(anyhow::anyhow!("Connection refused: {}", reason));
+            }
+        };
         
-        // Handshake sécurisé
+        let relay = Arc::new(TxRelay::new(peer_manager, blockchain.clone(), security));
+        
+        Self::serve_messages(
+            &mut reader,
+            &mut writer,
+            peer_addr,
+            &blockchain,
+            &rate_limiter,
+            &security_logger,
+            PeerSession { compression, relay: Some(&relay), timeouts, reputation: Some(&reputation) },
+        ).await
+    }
+    
+    /// Boucle de lecture des messages d'un peer jusqu'à la fermeture de la connexion.
+    /// Un message dépassant `RateLimiter::max_message_size_bytes` coupe la connexion,
+    /// de même qu'un peer muet au-delà de `session.timeouts.idle`.
+    /// Les réponses volumineuses sont compressées selon `session.compression`.
+    pub(crate) async fn serve_messages<R, W>(
+        reader: &mut R,
+        writer: &mut W,
+        peer_addr: SocketAddr,
+        blockchain: &Arc<RwLock<Blockchain>>,
+        rate_limiter: &Arc<Mutex<RateLimiter>>,
+        security_logger: &SecurityLogger,
+        session: PeerSession<'_>,
+    ) -> Result<(), anyhow::Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let PeerSession { compression, relay, timeouts, reputation } = session;
+        let mut bloom_filter: Option<BloomFilter> = None;
+        // Plancher annoncé au handshake, ré-annoncé dès qu'il change
+        let mut announced_fee_rate = blockchain.read().await.mempool.min_fee_rate();
+        
+        loop {
+            let max_size = rate_limiter.lock()
+                .map(|limiter| limiter.max_message_size_bytes)
+                .unwrap_or(MAX_HANDSHAKE_SIZE);
+            
+            let read = tokio::time::timeout(timeouts.idle, Self::read_message(reader, max_size)).await;
+            let Ok(read) = read else {
+                let reason = format!("idle timeout: no message for {}s", timeouts.idle.as_secs_f64());
+                security_logger.log_event(SecurityEventType::PeerDisconnected, &peer_addr.to_string(), &reason);
+                return Err(anyhow::anyhow!(reason));
+            };
+            
+            let message = match read {
+                Ok(message) => message,
+                Err(FrameError::Closed) => return Ok(()),
+                Err(e @ FrameError::Oversized { .. }) => {
+                    if let Ok(mut limiter) = rate_limiter.lock() {
+                        limiter.record_violation(peer_addr.ip());
+                    }
+                    if let Some(reputation) = reputation {
+                        reputation.record(peer_addr.ip(), PeerEvent::OversizedMessage);
+                    }
+                    security_logger.log_event(
+                        SecurityEventType::OversizedMessage,
+                        &peer_addr.to_string(),
+                        &e.to_string(),
+                    );
+                    return Err(e.into());
+                },
+                Err(e) => return Err(e.into()),
+            };
+            
+            let allowed = rate_limiter.lock()
+                .map(|mut limiter| limiter.allow_message_variant(peer_addr.ip(), &message))
+                .unwrap_or(true);
+            if !allowed {
+                if let Ok(mut limiter) = rate_limiter.lock() {
+                    limiter.record_violation(peer_addr.ip());
+                }
+                security_logger.log_event(
+                    SecurityEventType::RateLimitExceeded,
+                    &peer_addr.to_string(),
+                    "message rate limit exceeded",
+                );
+                continue;
+            }
+            
+            match message {
+                P2PMessage::Ping => Self::reply(writer, &P2PMessage::Pong, Compression::None, peer_addr, security_logger, timeouts).await?,
+                P2PMessage::ChainLengthQuery => {
+                    let length = blockchain.read().await.chain.len() as u64;
+                    Self::reply(writer, &P2PMessage::ChainLengthResponse { length }, Compression::None, peer_addr, security_logger, timeouts).await?;
+                },
+                P2PMessage::SetBloomFilter { filter, hash_funcs, tweak } => {
+                    let filter = BloomFilter::from_parts(filter, hash_funcs, tweak)
+                        .map_err(|e| anyhow::anyhow!("invalid bloom filter from {}: {}", peer_addr, e))?;
+                    bloom_filter = Some(filter);
+                },
+                P2PMessage::RequestBlocks { from_height, to_height } => {
+                    let reply = {
+                        let chain = blockchain.read().await;
+                        // Exactement `[from_height, to_height]`, borné à MAX_BLOCKS_PER_REQUEST blocs ;
+                        // une plage inversée reçoit une réponse vide
+                        let blocks = chain.get_blocks_range(from_height, to_height)
+                            .map_or(&[][..], |range| range.blocks)
+                            .iter();
+                        match &bloom_filter {
+                            Some(filter) => P2PMessage::FilteredBlocks {
+                                blocks: blocks.map(|block| FilteredBlock::from_block(block, filter)).collect(),
+                            },
+                            None => P2PMessage::SendBlocks { blocks: blocks.cloned().collect() },
+                        }
+                    };
+                    Self::reply(writer, &reply, compression, peer_addr, security_logger, timeouts).await?;
+                },
+                P2PMessage::RequestHeaders { from_height } => {
+                    let headers = blockchain.read().await.chain.iter()
+                        .skip_while(|block| block.index < from_height)
+                        .take(MAX_HEADERS_PER_REQUEST)
+                        .map(|block| block.header())
+                        .collect();
+                    Self::reply(writer, &P2PMessage::SendHeaders { headers }, compression, peer_addr, security_logger, timeouts).await?;
+                },
+                P2PMessage::NewTransaction { tx } => {
+                    let (accepted, min_fee_rate) = {
+                        let mut chain = blockchain.write().await;
+                        // Déjà acceptée : ni revalidée ni relayée, ce qui coupe les boucles de gossip.
+                        // Marquée seulement une fois acceptée (voir `Mempool::mark_seen`).
+                        let accepted = !chain.mempool.is_seen(&tx.id) && match chain.accept_transaction(tx.clone()) {
+                            Ok(fee) => {
+                                chain.mempool.mark_seen(&tx.id);
+                                println!("📥 Tx {} from {} added to mempool (fee {})", tx.id, peer_addr, fee);
+                                true
+                            },
+                            Err(e) => {
+                                println!("🚫 Tx {} from {} rejected: {}", tx.id, peer_addr, e);
+                                if let (Some(reputation), Err(_)) = (reputation, SignatureValidator::verify_transaction(&tx)) {
+                                    reputation.record(peer_addr.ip(), PeerEvent::BadSignature);
+                                }
+                                false
+                            },
+                        };
+                        (accepted, chain.mempool.min_fee_rate())
+                    };
+                    
+                    if min_fee_rate != announced_fee_rate {
+                        Self::reply(writer, &P2PMessage::FeeFilter { min_fee_rate }, Compression::None, peer_addr, security_logger, timeouts).await?;
+                        announced_fee_rate = min_fee_rate;
+                    }
+                    if let (true, Some(relay)) = (accepted, relay) {
+                        let relay = relay.clone();
+                        tokio::spawn(async move { relay.broadcast(&tx, Some(peer_addr)).await });
+                    }
+                },
+                P2PMessage::NewBlock { block } => {
+                    let allowed = rate_limiter.lock()
+                        .map(|mut limiter| limiter.allow_block(peer_addr.ip()))
+                        .unwrap_or(true);
+                    if !allowed {
+                        if let Ok(mut limiter) = rate_limiter.lock() {
+                            limiter.record_violation(peer_addr.ip());
+                        }
+                        security_logger.log_event(
+                            SecurityEventType::RateLimitExceeded,
+                            &peer_addr.to_string(),
+                            &format!("block rate limit exceeded, block {} dropped", block.index),
+                        );
+                        continue;
+                    }
+                    
+                    let outcome = blockchain.write().await.receive_block(block); 
+                    // Un orphelin n'est pas encore validé : ni récompense ni pénalité
+                    let event = match outcome {
+                        BlockOutcome::Rejected => Some(PeerEvent::InvalidBlock),
+                        BlockOutcome::Connected(_) => Some(PeerEvent::ValidBlock),
+                        BlockOutcome::Orphaned => None,
+                    };
+                    if let (Some(reputation), Some(event)) = (reputation, event) {
+                        reputation.record(peer_addr.ip(), event);
+                    }
+                },
+                other => println!("P2P message from {} not handled: {:?}", peer_addr, other),
+            }
+        }
+    }
+    
+    /// Répondre à un peer dans le délai `timeouts.write` ; au-delà la connexion est fermée
+    async fn reply<W: AsyncWrite + Unpin>(
+        writer: &mut W,
+        message: &P2PMessage,
+        compression: Compression,
+        peer_addr: SocketAddr,
+        security_logger: &SecurityLogger,
+        timeouts: ConnectionTimeouts,
+    ) -> Result<(), anyhow::Error> {
+        match tokio::time::timeout(timeouts.write, Self::send_message_with(writer, message, compression)).await {
+            Ok(sent) => sent,
+            Err(_) => {
+                let reason = format!("write timeout: reply not read within {}s", timeouts.write.as_secs_f64());
+                security_logger.log_event(SecurityEventType::PeerDisconnected, &peer_addr.to_string(), &reason);
+                Err(anyhow::anyhow!(reason))
+            },
+        }
+    }
+    
+    /// Échanger les handshakes et vérifier que le peer est sur le même réseau
+    /// (même identifiant de réseau, même genesis, version de protocole compatible).
+    /// Retourne la compression à utiliser pour les réponses à ce peer.
+    pub(crate) async fn perform_handshake<R, W>(
+        reader: &mut R,
+        writer: &mut W,
+        peer_addr: SocketAddr,
+        node_id: &str,
+        blockchain: &Arc<RwLock<Blockchain>>,
+    ) -> Result<Compression, String>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
         let chain = blockchain.read().await;
         let my_length = chain.chain.len() as u64;
         let genesis_hash = chain.chain.first()
             .map(|b| b.hash.clone())
             .unwrap_or_else(|| "none".to_string());
+        let min_fee_rate = chain.mempool.min_fee_rate();
+        let network_id = chain.params.network.network_id();
         drop(chain);
         
         let handshake = P2PMessage::Handshake {
-            version: "1.0".to_string(),
+            version: PROTOCOL_VERSION.to_string(),
             chain_length: my_length,
-            peer_id: format!("tls-server-{}", peer_addr.port()),
-            genesis_hash,
+            peer_id: node_id.to_string(),
+            genesis_hash: genesis_hash.clone(),
+            network_id: network_id.to_string(),
+            supports_compression: true,
+            compression: Compression::Lz4,
         };
         
-        Self::send_message(&mut writer, &handshake).await?;
+        Self::send_message(writer, &handshake).await.map_err(|e| e.to_string())?;
         println!("TLS P2P handshake sent to: {}", peer_addr);
         
-        Ok(())
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
+            Self::read_message(reader, MAX_HANDSHAKE_SIZE),
+        ).await
+            .map_err(|_| "handshake timeout".to_string())?
+            .map_err(|e| format!("invalid handshake: {}", e))?;
+        
+        let compression = match response {
+            P2PMessage::Handshake {
+                version,
+                genesis_hash: peer_genesis,
+                network_id: peer_network,
+                supports_compression,
+                compression,
+                ..
+            } => {
+                if !is_compatible_version(&version) {
+                    return Err(format!("incompatible version {} (local {})", version, PROTOCOL_VERSION));
+                }
+                if peer_network != network_id {
+                    return Err(format!("network mismatch: peer {} local {}", peer_network, network_id));
+                }
+                if peer_genesis != genesis_hash {
+                    return Err(format!("genesis mismatch: peer {} local {}", peer_genesis, genesis_hash));
+                }
+                if supports_compression { compression } else { Compression::None }
+            },
+            other => return Err(format!("expected handshake, got {:?}", other)),
+        };
+        println!("TLS P2P handshake accepted from: {}", peer_addr);
+        
+        // Annoncer notre plancher de frais pour éviter de recevoir des transactions refusées
+        Self::send_message(writer, &P2PMessage::FeeFilter { min_fee_rate }).await.map_err(|e| e.to_string())?;
+        
+        Ok(compression)
     }
     
-    async fn send_message(
-        writer: &mut tokio::io::WriteHalf<tokio_rustls::server::TlsStream<TcpStream>>,
+    /// Côté client du handshake : attendre celui du serveur (même genesis), puis envoyer le nôtre.
+    /// Retourne la longueur de chaîne annoncée par le serveur.
+    pub(crate) async fn client_handshake<R, W>(
+        reader: &mut R,
+        writer: &mut W,
+        node_id: &str,
+        genesis_hash: &str,
+        network: Network,
+    ) -> Result<u64, anyhow::Error>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let peer_length = match Self::read_message(reader, MAX_HANDSHAKE_SIZE).await? {
+            P2PMessage::Handshake { genesis_hash: peer_genesis, network_id, chain_length, .. }
+                if peer_genesis == genesis_hash && network_id == network.network_id() => chain_length,
+            P2PMessage::Handshake { .. } => return Err(anyhow::anyhow!("peer is on another network")),
+            other => return Err(anyhow::anyhow!("expected handshake, got {:?}", other)),
+        };
+
+        let handshake = P2PMessage::Handshake {
+            version: PROTOCOL_VERSION.to_string(),
+            chain_length: 0,
+            peer_id: node_id.to_string(),
+            genesis_hash: genesis_hash.to_string(),
+            network_id: network.network_id().to_string(),
+            supports_compression: true,
+            compression: Compression::Lz4,
+        };
+        Self::send_message(writer, &handshake).await?;
+        Ok(peer_length)
+    }
+    
+    /// Envoyer un message sans compression
+    pub async fn send_message<W: AsyncWrite + Unpin>(
+        writer: &mut W,
+        message: &P2PMessage,
+    ) -> Result<(), anyhow::Error> {
+        Self::send_message_with(writer, message, Compression::None).await
+    }
+    
+    /// Trame : longueur (`u32` big-endian), tag de compression, données
+    /// (compressées seulement au-delà de `COMPRESSION_THRESHOLD`)
+    pub async fn send_message_with<W: AsyncWrite + Unpin>(
+        writer: &mut W,
         message: &P2PMessage,
+        compression: Compression,
     ) -> Result<(), anyhow::Error> {
-        let data = message.serialize();
+        let data = compression::encode_frame(&message.serialize(), compression);
         let length = data.len() as u32;
         
         writer.write_all(&length.to_be_bytes()).await?;
@@ -104,4 +528,347 @@ impl P2PServer {
         
         Ok(())
     }
+    
+    /// Lire un message préfixé par sa longueur (`u32` big-endian) ; la longueur
+    /// est vérifiée contre `max_size` avant toute allocation, de même que la taille
+    /// décompressée d'une trame compressée.
+    pub async fn read_message<R: AsyncRead + Unpin>(
+        reader: &mut R,
+        max_size: usize,
+    ) -> Result<P2PMessage, FrameError> {
+        let mut length_bytes = [0u8; 4];
+        reader.read_exact(&mut length_bytes).await.map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => FrameError::Closed,
+            _ => FrameError::Io(e),
+        })?;
+        let length = u32::from_be_bytes(length_bytes) as usize;
+        
+        if length > max_size {
+            return Err(FrameError::Oversized { length, max: max_size });
+        }
+        
+        let mut data = vec![0u8; length];
+        reader.read_exact(&mut data).await.map_err(FrameError::Io)?;
+        
+        let payload = compression::decode_frame(&data, max_size).map_err(FrameError::Decode)?;
+        P2PMessage::deserialize(&payload).map_err(|e| FrameError::Decode(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::genesis::create_genesis_block_with_params;
+    use crate::utils::config::ConsensusParams;
+
+    /// Adresse valide propre à `name`, pour les coinbases des blocs de test
+    fn miner_address(name: &str) -> String {
+        crate::wallet::address::generate_address(name.as_bytes(), Network::Mainnet)
+    }
+
+    fn chain_with_genesis() -> Arc<RwLock<Blockchain>> {
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        Arc::new(RwLock::new(chain))
+    }
+
+    /// Simule un peer qui répond au handshake avec `version`, `genesis_hash` et `network`
+    async fn handshake_with(version: &str, genesis_hash: String, network: Network) -> Result<(), String> {
+        let blockchain = chain_with_genesis();
+        let (server_side, client_side) = tokio::io::duplex(64 * 1024);
+        let (mut server_reader, mut server_writer) = tokio::io::split(server_side);
+        let (mut client_reader, mut client_writer) = tokio::io::split(client_side);
+
+        let peer = P2PMessage::Handshake {
+            version: version.to_string(),
+            chain_length: 1,
+            peer_id: "test-peer".to_string(),
+            genesis_hash,
+            network_id: network.network_id().to_string(),
+            supports_compression: false,
+            compression: Compression::None,
+        };
+        P2PServer::send_message(&mut client_writer, &peer).await.unwrap();
+
+        let result = P2PServer::perform_handshake(
+            &mut server_reader,
+            &mut server_writer,
+            "127.0.0.1:3001".parse().unwrap(),
+            "test-node",
+            &blockchain,
+        ).await.map(|_| ());
+
+        // Le serveur envoie toujours son handshake en premier
+        let sent = P2PServer::read_message(&mut client_reader, MAX_HANDSHAKE_SIZE).await.unwrap();
+        assert!(matches!(sent, P2PMessage::Handshake { .. }));
+        result
+    }
+
+    #[tokio::test]
+    async fn test_oversized_length_prefix_drops_connection() {
+        let blockchain = chain_with_genesis();
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new()));
+        let (server_side, mut client_side) = tokio::io::duplex(1024);
+        let (mut reader, mut writer) = tokio::io::split(server_side);
+        
+        // 4 GB annoncés, aucune donnée derrière
+        client_side.write_all(&u32::MAX.to_be_bytes()).await.unwrap();
+        
+        let result = P2PServer::serve_messages(
+            &mut reader,
+            &mut writer,
+            "127.0.0.1:3001".parse().unwrap(),
+            &blockchain,
+            &rate_limiter,
+            &SecurityLogger::new(),
+            PeerSession::default(),
+        ).await;
+        
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FrameError>(),
+            Some(FrameError::Oversized { length, .. }) if *length == u32::MAX as usize
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_silent_connection_closed_after_idle_timeout() {
+        let blockchain = chain_with_genesis();
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new()));
+        let log_path = std::env::temp_dir().join(format!("aur-idle-timeout-{}.log", std::process::id()));
+        let security_logger = SecurityLogger::with_file(&log_path);
+        // Le client reste connecté mais n'envoie rien
+        let (server_side, _client_side) = tokio::io::duplex(1024);
+        let (mut reader, mut writer) = tokio::io::split(server_side);
+        
+        let timeouts = ConnectionTimeouts { idle: Duration::from_millis(200), ..ConnectionTimeouts::default() };
+        let result = tokio::time::timeout(Duration::from_secs(5), P2PServer::serve_messages(
+            &mut reader,
+            &mut writer,
+            "127.0.0.1:3001".parse().unwrap(),
+            &blockchain,
+            &rate_limiter,
+            &security_logger,
+            PeerSession { timeouts, ..PeerSession::default() },
+        )).await.expect("silent connection was never closed");
+        
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("idle timeout"), "{}", err);
+        
+        let events = security_logger.query(&crate::security::logger::EventFilter {
+            event_type: Some(SecurityEventType::PeerDisconnected),
+            ..Default::default()
+        });
+        std::fs::remove_file(&log_path).ok();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].details.contains("idle timeout"), "{}", events[0].details);
+    }
+
+    #[tokio::test]
+    async fn test_closed_inbound_connections_release_their_slots() {
+        let blockchain = chain_with_genesis();
+        let genesis_hash = blockchain.read().await.chain[0].hash.clone();
+        let max_inbound = 2;
+        let peer_manager = Arc::new(PeerManager::new(max_inbound, 8));
+        let security = Arc::new(NetworkSecurity::new().unwrap());
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new()));
+        let log_path = std::env::temp_dir().join(format!("aur-inbound-slots-{}.log", std::process::id()));
+        let security_logger = Arc::new(SecurityLogger::with_file(&log_path));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        
+        // Comme les relais et les pings : une connexion courte par message, depuis un port éphémère
+        for _ in 0..max_inbound + 2 {
+            let server = async {
+                let (stream, peer_addr) = listener.accept().await.unwrap();
+                P2PServer::handle_connection(
+                    stream,
+                    peer_addr,
+                    blockchain.clone(),
+                    peer_manager.clone(),
+                    security.clone(),
+                    security_logger.clone(),
+                    rate_limiter.clone(),
+                    Arc::new(PeerReputation::new(rate_limiter.clone())),
+                    ConnectionTimeouts::default(),
+                ).await
+            };
+            let client = async {
+                let stream = TcpStream::connect(addr).await.unwrap();
+                let server_name = rustls::ServerName::try_from("auriumchain-node").unwrap();
+                let tls_stream = security.tls_connector.connect(server_name, stream).await.unwrap();
+                let (mut reader, mut writer) = tokio::io::split(tls_stream);
+                crate::p2p::Heartbeat::ping_over(&mut reader, &mut writer, "client", &genesis_hash, Network::Mainnet).await
+            };
+            let (_, pinged) = tokio::time::timeout(Duration::from_secs(10), async { tokio::join!(server, client) })
+                .await
+                .unwrap();
+            assert!(pinged.is_ok(), "{:?}", pinged.err());
+            assert_eq!(peer_manager.count(PeerDirection::Inbound).await, 0);
+        }
+        std::fs::remove_file(&log_path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_message_loop_answers_ping() {
+        let blockchain = chain_with_genesis();
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new()));
+        let (server_side, client_side) = tokio::io::duplex(1024);
+        let (mut reader, mut writer) = tokio::io::split(server_side);
+        let (mut client_reader, mut client_writer) = tokio::io::split(client_side);
+        
+        P2PServer::send_message(&mut client_writer, &P2PMessage::Ping).await.unwrap();
+        client_writer.shutdown().await.unwrap();
+        
+        // Fermeture propre après le Ping
+        P2PServer::serve_messages(
+            &mut reader,
+            &mut writer,
+            "127.0.0.1:3001".parse().unwrap(),
+            &blockchain,
+            &rate_limiter,
+            &SecurityLogger::new(),
+            PeerSession::default(),
+        ).await.unwrap();
+        
+        let reply = P2PServer::read_message(&mut client_reader, 1024).await.unwrap();
+        assert!(matches!(reply, P2PMessage::Pong));
+    }
+
+    #[tokio::test]
+    async fn test_blocks_served_filtered_after_set_bloom_filter() {
+        let blockchain = chain_with_genesis();
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new()));
+        let (server_side, client_side) = tokio::io::duplex(64 * 1024);
+        let (mut reader, mut writer) = tokio::io::split(server_side);
+        let (mut client_reader, mut client_writer) = tokio::io::split(client_side);
+        
+        let mut filter = BloomFilter::new(64, 3, 7).unwrap();
+        filter.insert(b"AUR1NobodyHere");
+        let set_filter = P2PMessage::SetBloomFilter {
+            filter: filter.data().to_vec(),
+            hash_funcs: filter.hash_funcs(),
+            tweak: filter.tweak(),
+        };
+        P2PServer::send_message(&mut client_writer, &set_filter).await.unwrap();
+        P2PServer::send_message(&mut client_writer, &P2PMessage::RequestBlocks { from_height: 0, to_height: 10 }).await.unwrap();
+        client_writer.shutdown().await.unwrap();
+        
+        P2PServer::serve_messages(
+            &mut reader,
+            &mut writer,
+            "127.0.0.1:3001".parse().unwrap(),
+            &blockchain,
+            &rate_limiter,
+            &SecurityLogger::new(),
+            PeerSession::default(),
+        ).await.unwrap();
+        
+        match P2PServer::read_message(&mut client_reader, 64 * 1024).await.unwrap() {
+            P2PMessage::FilteredBlocks { blocks } => {
+                assert_eq!(blocks.len(), 1);
+                assert_eq!(blocks[0].total_transactions, 1);
+                assert!(blocks[0].transactions.is_empty());
+            },
+            other => panic!("unexpected reply: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_blocks_serves_mid_chain_range() {
+        let blockchain = chain_with_genesis();
+        for _ in 0..9 {
+            blockchain.write().await.mine_pending_transactions("AUR1Miner".to_string());
+        }
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new()));
+        let (server_side, client_side) = tokio::io::duplex(256 * 1024);
+        let (mut reader, mut writer) = tokio::io::split(server_side);
+        let (mut client_reader, mut client_writer) = tokio::io::split(client_side);
+        
+        P2PServer::send_message(&mut client_writer, &P2PMessage::RequestBlocks { from_height: 3, to_height: 6 }).await.unwrap();
+        client_writer.shutdown().await.unwrap();
+        
+        P2PServer::serve_messages(
+            &mut reader,
+            &mut writer,
+            "127.0.0.1:3001".parse().unwrap(),
+            &blockchain,
+            &rate_limiter,
+            &SecurityLogger::new(),
+            PeerSession::default(),
+        ).await.unwrap();
+        
+        match P2PServer::read_message(&mut client_reader, 256 * 1024).await.unwrap() {
+            P2PMessage::SendBlocks { blocks } => {
+                let indices: Vec<u64> = blocks.iter().map(|block| block.index).collect();
+                assert_eq!(indices, vec![3, 4, 5, 6]);
+            },
+            other => panic!("unexpected reply: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_blocks_beyond_block_rate_limit_rejected() {
+        // Blocs valides minés sur une copie de la chaîne, poussés un par un par le même peer
+        let source = chain_with_genesis();
+        for index in 0..5 {
+            source.write().await.mine_pending_transactions(miner_address(&format!("Miner{}", index)));
+        }
+        let mined: Vec<_> = source.read().await.chain[1..].to_vec();
+        
+        let blockchain = chain_with_genesis();
+        let mut limiter = RateLimiter::new();
+        limiter.max_blocks_per_minute = 3;
+        let rate_limiter = Arc::new(Mutex::new(limiter));
+        let (server_side, client_side) = tokio::io::duplex(256 * 1024);
+        let (mut reader, mut writer) = tokio::io::split(server_side);
+        let (_client_reader, mut client_writer) = tokio::io::split(client_side);
+        
+        for block in mined {
+            P2PServer::send_message(&mut client_writer, &P2PMessage::NewBlock { block }).await.unwrap();
+        }
+        client_writer.shutdown().await.unwrap();
+        
+        let peer_addr: SocketAddr = "127.0.0.1:3001".parse().unwrap();
+        P2PServer::serve_messages(
+            &mut reader,
+            &mut writer,
+            peer_addr,
+            &blockchain,
+            &rate_limiter,
+            &SecurityLogger::new(),
+            PeerSession::default(),
+        ).await.unwrap();
+        
+        // Genesis + les 3 premiers blocs ; les 2 suivants sont refusés par `allow_block`
+        assert_eq!(blockchain.read().await.chain.len(), 4);
+        assert!(!rate_limiter.lock().unwrap().allow_block(peer_addr.ip()));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_same_network_accepted() {
+        let genesis = chain_with_genesis().read().await.chain[0].hash.clone();
+        assert!(handshake_with(PROTOCOL_VERSION, genesis, Network::Mainnet).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handshake_mismatched_genesis_refused() {
+        let result = handshake_with(PROTOCOL_VERSION, "f".repeat(64), Network::Mainnet).await;
+        assert!(result.unwrap_err().contains("genesis mismatch"));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_incompatible_version_refused() {
+        let genesis = chain_with_genesis().read().await.chain[0].hash.clone();
+        let result = handshake_with("2.0", genesis, Network::Mainnet).await;
+        assert!(result.unwrap_err().contains("incompatible version"));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_other_network_refused() {
+        let genesis = chain_with_genesis().read().await.chain[0].hash.clone();
+        let result = handshake_with(PROTOCOL_VERSION, genesis, Network::Testnet).await;
+        assert!(result.unwrap_err().contains("network mismatch"));
+    }
 }
@@ -1,8 +1,18 @@
 use crate::blockchain::Blockchain;
+use crate::p2p::server::MAX_BLOCKS_PER_REQUEST;
+use crate::utils::config::Network;
+use crate::security::{PeerEvent, PeerReputation, RateLimiter, SecurityValidator};
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use serde::{Deserialize, Serialize};
 
+/// Pause par défaut entre deux synchronisations périodiques
+pub const DEFAULT_SYNC_INTERVAL_SECS: u64 = 30;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum SyncMessage {
     RequestChainInfo,
@@ -39,6 +49,25 @@ pub struct SyncManager {
     blockchain: Arc<RwLock<Blockchain>>,
     peer_manager: Arc<crate::p2p::PeerManager>,
     client: reqwest::Client,
+    /// Nombre maximum de peers contactés simultanément lors de la propagation d'un bloc
+    pub max_fanout: usize,
+    /// Pause entre deux vagues de propagation
+    pub wave_delay: Duration,
+    pub monitor: Arc<std::sync::Mutex<crate::security::SecurityMonitor>>,
+    /// Échecs de synchronisation comptés dans `Metrics::sync_errors`
+    pub metrics: Arc<crate::utils::metrics::Metrics>,
+    /// Blocs valides ou invalides servis par un peer ; `None` : sans effet sur les bans
+    pub reputation: Option<Arc<PeerReputation>>,
+    /// Blocs demandés par requête lors d'une synchronisation
+    pub batch_size: usize,
+    /// Pause entre deux synchronisations périodiques (`run`)
+    pub sync_interval: Duration,
+    /// Limite de blocs par peer (`allow_block`) ; `None` : sans limite
+    pub rate_limiter: Option<Arc<std::sync::Mutex<RateLimiter>>>,
+    /// Fichier de la chaîne, réécrit après chaque synchronisation (chiffré si la chaîne l'est)
+    pub data_file: String,
+    /// Dernière plage de hauteurs demandée à chaque peer : seuls ces blocs échappent à `allow_block`
+    requested: std::sync::Mutex<HashMap<SocketAddr, std::ops::RangeInclusive<u64>>>,
 }
 
 impl SyncManager {
@@ -47,16 +76,93 @@ impl SyncManager {
             blockchain,
             peer_manager,
             client: reqwest::Client::new(),
+            max_fanout: 8,
+            wave_delay: Duration::from_millis(500),
+            monitor: Arc::new(std::sync::Mutex::new(crate::security::SecurityMonitor::new())),
+            metrics: Arc::new(crate::utils::metrics::Metrics::new()),
+            reputation: None,
+            batch_size: MAX_BLOCKS_PER_REQUEST,
+            sync_interval: Duration::from_secs(DEFAULT_SYNC_INTERVAL_SECS),
+            rate_limiter: None,
+            data_file: "/tmp/auriumchain.json".to_string(),
+            requested: std::sync::Mutex::new(HashMap::new()),
         }
     }
 
-    pub async fn broadcast_new_block(&self, block: crate::blockchain::Block) -> Result<(), Box<dyn std::error::Error>> {
+    /// Synchronisation périodique avec tous les peers, toutes les `sync_interval`
+    pub async fn run(&self, shutdown: Arc<AtomicBool>) {
+        loop {
+            tokio::time::sleep(self.sync_interval).await;
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+            self.sync_round().await;
+        }
+    }
+
+    /// Une tournée : synchroniser avec chaque peer connu ; retourne le nombre de peers
+    /// qui ont fourni de nouveaux blocs
+    pub async fn sync_round(&self) -> usize {
         let peers = self.peer_manager.get_all_peers().await;
+        if peers.is_empty() {
+            return 0;
+        }
         
+        println!("Starting periodic sync with {} peers...", peers.len());
+        let mut synced_peers = 0;
         for peer_addr in peers {
-            match self.send_block_to_peer(peer_addr, &block).await {
-                Ok(_) => println!("✅ Block sent to peer: {}", peer_addr),
-                Err(e) => println!("❌ Failed to send block to peer {}: {}", peer_addr, e),
+            match self.sync_with_peer(peer_addr).await {
+                Ok(true) => {
+                    println!("✅ Synchronized new blocks from peer: {}", peer_addr);
+                    synced_peers += 1;
+                },
+                Ok(false) => {},
+                Err(e) => {
+                    println!("❌ Sync failed with peer {}: {}", peer_addr, e);
+                    self.metrics.record_sync_error();
+                }
+            }
+        }
+        synced_peers
+    }
+
+    fn record_peer_event(&self, peer_addr: SocketAddr, event: PeerEvent) {
+        if let Some(reputation) = &self.reputation {
+            if reputation.record(peer_addr.ip(), event) {
+                println!("🚫 Peer {} banned after repeated misbehaviour", peer_addr);
+            }
+        }
+    }
+
+    /// Découper la liste des peers en vagues d'au plus `max_fanout` destinataires
+    pub fn relay_waves(peers: &[SocketAddr], max_fanout: usize) -> Vec<Vec<SocketAddr>> {
+        peers.chunks(max_fanout.max(1))
+            .map(|wave| wave.to_vec())
+            .collect()
+    }
+
+    pub async fn broadcast_new_block(&self, block: crate::blockchain::Block) -> Result<(), Box<dyn std::error::Error>> {
+        let peers = self.peer_manager.get_all_peers().await;
+        let waves = Self::relay_waves(&peers, self.max_fanout);
+        
+        for (i, wave) in waves.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(self.wave_delay).await;
+            }
+            
+            let sends = wave.iter().map(|peer_addr| {
+                let block = &block;
+                async move {
+                    self.send_block_to_peer(*peer_addr, block).await.map_err(|e| e.to_string())
+                }
+            });
+            let results = futures::future::join_all(sends).await;
+            
+            for (peer_addr, result) in wave.iter().zip(results) {
+                match result {
+                    Ok(_) => println!("✅ Block sent to peer: {}", peer_addr),
+                    Err(e) => println!("❌ Failed to send block to peer {}: {}", peer_addr, e),
+                }
             }
         }
         
@@ -76,46 +182,56 @@ impl SyncManager {
             Ok(height) => height,
             Err(e) => {
                 println!("❌ Failed to get peer height from {}: {}", peer_addr, e);
+                self.metrics.record_sync_error();
                 return Ok(false);
             }
         };
+        self.peer_manager.mark_seen(peer_addr).await;
         
         println!("📊 Heights - Us: {}, Peer {}: {}", our_height, peer_addr, peer_height);
         
         if peer_height > our_height {
             println!("⬇️ Peer {} has longer chain ({} vs {}), downloading blocks...", peer_addr, peer_height, our_height);
             
-            // Vraie requête HTTP pour télécharger les blocs
-            let new_blocks = match self.download_blocks_from_peer(peer_addr, our_height).await {
-                Ok(blocks) => blocks,
-                Err(e) => {
-                    println!("❌ Failed to download blocks from {}: {}", peer_addr, e);
-                    return Ok(false);
-                }
-            };
-            
-            if new_blocks.is_empty() {
-                println!("⚠️ No blocks received from peer");
-                return Ok(false);
-            }
-            
-            // Appliquer les nouveaux blocs
-            let mut chain = self.blockchain.write().await;
+            // Téléchargement par lots de `batch_size` blocs, chacun appliqué avant de demander le suivant
+            let mut next_height = our_height;
             let mut applied_blocks = 0;
-            
-            for block in new_blocks {
-                if chain.validate_new_block(&block) {
-                    chain.chain.push(block);
-                    applied_blocks += 1;
-                } else {
-                    println!("❌ Rejected invalid block from peer");
+            while next_height <= peer_height {
+                let end = peer_height.min(next_height + self.batch_size.max(1) - 1);
+                let batch = match self.download_block_range(peer_addr, next_height, end).await {
+                    Ok(blocks) => blocks,
+                    Err(e) => {
+                        println!("❌ Failed to download blocks {}..={} from {}: {}", next_height, end, peer_addr, e);
+                        self.metrics.record_sync_error();
+                        break;
+                    }
+                };
+                if batch.is_empty() {
+                    println!("⚠️ No blocks received from peer");
+                    break;
+                }
+                
+                // Le premier lot ne prolonge pas notre sommet : le peer a divergé
+                let tip_hash = self.blockchain.read().await.get_latest_block().map(|tip| tip.hash.clone());
+                let forked = next_height == our_height && tip_hash.is_some_and(|hash| hash != batch[0].previous_hash);
+                if forked {
+                    applied_blocks += self.sync_fork(peer_addr, our_height, peer_height).await;
+                    break;
+                }
+                
+                let batch_len = batch.len();
+                let applied = self.apply_blocks(peer_addr, batch).await;
+                applied_blocks += applied;
+                next_height += applied;
+                if applied < batch_len {
                     break;
                 }
             }
             
             if applied_blocks > 0 {
+                let chain = self.blockchain.read().await;
                 // Sauvegarder la blockchain mise à jour
-                if let Err(e) = chain.save_to_file("/tmp/auriumchain.json") {
+                if let Err(e) = chain.save_to_file(&self.data_file) {
                     println!("⚠️ Failed to save updated blockchain: {}", e);
                 }
                 
@@ -133,7 +249,7 @@ impl SyncManager {
     
     // Vraie requête HTTP pour obtenir la hauteur de chaîne du peer
     async fn get_peer_chain_height(&self, peer_addr: std::net::SocketAddr) -> Result<usize, Box<dyn std::error::Error>> {
-        let rpc_port = self.get_rpc_port_for_peer(peer_addr);
+        let rpc_port = self.rpc_port_for_peer(peer_addr).await;
         let url = format!("http://{}:{}/status", peer_addr.ip(), rpc_port);
         
         println!("🌐 Requesting status from: {}", url);
@@ -154,10 +270,106 @@ impl SyncManager {
         Ok(status.block_height as usize)
     }
     
-    // Vraie requête HTTP pour télécharger les blocs depuis un peer
-    async fn download_blocks_from_peer(&self, peer_addr: std::net::SocketAddr, from_height: usize) -> Result<Vec<crate::blockchain::Block>, Box<dyn std::error::Error>> {
-        let rpc_port = self.get_rpc_port_for_peer(peer_addr);
-        let url = format!("http://{}:{}/blocks_from/{}", peer_addr.ip(), rpc_port, from_height);
+    /// Télécharger la branche du peer depuis `max_reorg_depth` blocs sous notre sommet
+    /// et l'adopter si elle est plus longue (voir `adopt_fork`)
+    async fn sync_fork(&self, peer_addr: SocketAddr, our_height: usize, peer_height: usize) -> usize {
+        let start = {
+            let chain = self.blockchain.read().await;
+            our_height.saturating_sub(chain.params.max_reorg_depth as usize + 1).max(1)
+        };
+        match self.download_block_range(peer_addr, start, peer_height).await.map_err(|e| e.to_string()) {
+            Ok(blocks) => self.adopt_fork(peer_addr, blocks).await,
+            Err(e) => {
+                println!("❌ Failed to download fork {}..={} from {}: {}", start, peer_height, peer_addr, e);
+                self.metrics.record_sync_error();
+                0
+            }
+        }
+    }
+
+    /// Basculer sur la branche `blocks` d'un peer : les blocs communs avec notre chaîne sont
+    /// ignorés, le reste passe par `Blockchain::reorganize` avec le validateur du nœud.
+    /// Retourne le nombre de blocs de la branche adoptée.
+    async fn adopt_fork(&self, peer_addr: SocketAddr, mut blocks: Vec<crate::blockchain::Block>) -> usize {
+        blocks.truncate(self.admit_blocks(peer_addr, &blocks));
+        let mut chain = self.blockchain.write().await;
+        let fork: Vec<_> = blocks.into_iter()
+            .skip_while(|block| chain.chain.get(block.index as usize).is_some_and(|ours| ours.hash == block.hash))
+            .collect();
+        let fork_len = fork.len();
+        let validator = SecurityValidator::with_params(&chain.params);
+        match chain.reorganize(fork, &validator) {
+            Ok(depth) => {
+                println!("🔀 Reorganized {} blocks onto the branch of {}", depth, peer_addr);
+                self.record_peer_event(peer_addr, PeerEvent::ValidBlock);
+                fork_len
+            }
+            Err(e) => {
+                println!("❌ Rejected fork from {}: {}", peer_addr, e);
+                self.metrics.record_sync_error();
+                0
+            }
+        }
+    }
+
+    /// Nombre de blocs admis en tête de `blocks` : ceux de la plage demandée au peer sont
+    /// exemptés, les autres (non sollicités) sont comptés par `allow_block`
+    fn admit_blocks(&self, peer_addr: SocketAddr, blocks: &[crate::blockchain::Block]) -> usize {
+        let requested = self.requested.lock().ok().and_then(|mut requested| requested.remove(&peer_addr));
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return blocks.len();
+        };
+        
+        blocks.iter()
+            .take_while(|block| {
+                if requested.as_ref().is_some_and(|range| range.contains(&block.index)) {
+                    return true;
+                }
+                let Ok(mut limiter) = rate_limiter.lock() else {
+                    return true;
+                };
+                let allowed = limiter.allow_block(peer_addr.ip());
+                if !allowed {
+                    limiter.record_violation(peer_addr.ip());
+                    println!("🚫 Unsolicited block {} from {} over the block rate limit", block.index, peer_addr);
+                }
+                allowed
+            })
+            .count()
+    }
+    
+    /// Appliquer un lot de blocs téléchargés ; s'arrête au premier bloc invalide ou
+    /// refusé par `admit_blocks`. Retourne le nombre de blocs ajoutés.
+    async fn apply_blocks(&self, peer_addr: SocketAddr, blocks: Vec<crate::blockchain::Block>) -> usize {
+        let admitted = self.admit_blocks(peer_addr, &blocks);
+        let mut chain = self.blockchain.write().await;
+        let mut applied = 0;
+        
+        for block in blocks.into_iter().take(admitted) {
+            if let Ok(mut monitor) = self.monitor.lock() {
+                monitor.process_block(&block);
+            }
+            
+            if chain.connect_block(block) {
+                applied += 1;
+                self.record_peer_event(peer_addr, PeerEvent::ValidBlock);
+            } else {
+                println!("❌ Rejected invalid block from peer");
+                self.metrics.record_sync_error();
+                self.record_peer_event(peer_addr, PeerEvent::InvalidBlock);
+                break;
+            }
+        }
+        applied
+    }
+    
+    // Vraie requête HTTP pour télécharger les blocs `start..=end` depuis un peer
+    async fn download_block_range(&self, peer_addr: std::net::SocketAddr, start: usize, end: usize) -> Result<Vec<crate::blockchain::Block>, Box<dyn std::error::Error>> {
+        let rpc_port = self.rpc_port_for_peer(peer_addr).await;
+        let url = format!("http://{}:{}/blocks?start={}&end={}", peer_addr.ip(), rpc_port, start, end);
+        if let Ok(mut requested) = self.requested.lock() {
+            requested.insert(peer_addr, start as u64..=end as u64);
+        }
         
         println!("⬇️ Downloading blocks from: {}", url);
         
@@ -179,7 +391,7 @@ impl SyncManager {
     
     // Envoyer un nouveau bloc à un peer (pour le broadcasting)
     async fn send_block_to_peer(&self, peer_addr: std::net::SocketAddr, block: &crate::blockchain::Block) -> Result<(), Box<dyn std::error::Error>> {
-        let rpc_port = self.get_rpc_port_for_peer(peer_addr);
+        let rpc_port = self.rpc_port_for_peer(peer_addr).await;
         let url = format!("http://{}:{}/new_block", peer_addr.ip(), rpc_port);
         
         let response = tokio::time::timeout(
@@ -198,17 +410,22 @@ impl SyncManager {
         }
     }
     
-    // Obtenir le port RPC basé sur le port P2P
-    fn get_rpc_port_for_peer(&self, peer_addr: std::net::SocketAddr) -> u16 {
-        match peer_addr.port() {
-            3001 => 8001,
-            3002 => 8002,
-            3003 => 8003,
-            _ => 8001,
-        }
+    /// Port RPC d'un peer pour le réseau de la chaîne locale
+    async fn rpc_port_for_peer(&self, peer_addr: SocketAddr) -> u16 {
+        let network = self.blockchain.read().await.params.network;
+        rpc_port_for(network, peer_addr.port())
     }
 }
 
+/// Port RPC correspondant au port P2P `p2p_port` : même décalage par rapport aux ports par
+/// défaut du réseau (3002 → 8002 sur le mainnet, 13002 → 18002 sur le testnet). Un port
+/// sous le port P2P par défaut retombe sur le port RPC par défaut.
+fn rpc_port_for(network: Network, p2p_port: u16) -> u16 {
+    p2p_port.checked_sub(network.default_p2p_port())
+        .and_then(|offset| network.default_rpc_port().checked_add(offset))
+        .unwrap_or(network.default_rpc_port())
+}
+
 pub struct BlockchainSync {
     blockchain: Arc<RwLock<Blockchain>>,
     peers: Vec<String>,
@@ -221,4 +438,184 @@ impl BlockchainSync {
             peers: Vec::new(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adresse valide propre à `name`, pour les coinbases des blocs de test
+    fn miner_address(name: &str) -> String {
+        crate::wallet::address::generate_address(name.as_bytes(), Network::Mainnet)
+    }
+
+    #[test]
+    fn test_relay_waves_respect_fanout() {
+        let peers: Vec<SocketAddr> = (0..20)
+            .map(|i| format!("10.0.0.{}:3001", i + 1).parse().unwrap())
+            .collect();
+
+        let waves = SyncManager::relay_waves(&peers, 8);
+
+        assert_eq!(waves.len(), 3);
+        assert_eq!(waves[0], peers[..8].to_vec());
+        assert_eq!(waves[1], peers[8..16].to_vec());
+        assert_eq!(waves[2], peers[16..].to_vec());
+    }
+
+    #[test]
+    fn test_relay_waves_small_network() {
+        let peers: Vec<SocketAddr> = vec!["10.0.0.1:3001".parse().unwrap()];
+
+        assert_eq!(SyncManager::relay_waves(&peers, 8), vec![peers.clone()]);
+        assert_eq!(SyncManager::relay_waves(&peers, 0), vec![peers]);
+        assert!(SyncManager::relay_waves(&[], 8).is_empty());
+    }
+
+    #[test]
+    fn test_rpc_port_follows_network_defaults() {
+        assert_eq!(rpc_port_for(Network::Mainnet, 3001), 8001);
+        assert_eq!(rpc_port_for(Network::Mainnet, 3003), 8003);
+        assert_eq!(rpc_port_for(Network::Testnet, 13001), 18001);
+        assert_eq!(rpc_port_for(Network::Testnet, 13002), 18002);
+        assert_eq!(rpc_port_for(Network::Devnet, 23005), 28005);
+        // Port hors convention : port RPC par défaut du réseau
+        assert_eq!(rpc_port_for(Network::Testnet, 3001), 18001);
+        assert_eq!(rpc_port_for(Network::Mainnet, u16::MAX), 8001);
+    }
+
+    #[tokio::test]
+    async fn test_synced_blocks_notify_tip_subscribers() {
+        let params = crate::utils::config::ConsensusParams { difficulty: 1, ..Default::default() };
+        let genesis = crate::blockchain::genesis::create_genesis_block_with_params(&params);
+        let mut source = Blockchain::with_params(params.clone());
+        source.chain.push(genesis.clone());
+        for index in 0..3 {
+            source.mine_pending_transactions(miner_address(&format!("Miner{}", index)));
+        }
+
+        let mut local = Blockchain::with_params(params);
+        local.chain.push(genesis);
+        let mut tips = local.subscribe_tips();
+        let sync_manager = SyncManager::new(
+            Arc::new(RwLock::new(local)),
+            Arc::new(crate::p2p::PeerManager::new(8, 8)),
+        );
+
+        let applied = sync_manager.apply_blocks("10.0.0.1:3001".parse().unwrap(), source.chain[1..].to_vec()).await;
+        assert_eq!(applied, 3);
+        for block in &source.chain[1..] {
+            let tip = tips.try_recv().unwrap();
+            assert_eq!((tip.index, tip.hash.as_str()), (block.index, block.hash.as_str()));
+        }
+        assert!(tips.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_longer_peer_branch_triggers_reorganization() {
+        let params = crate::utils::config::ConsensusParams { difficulty: 1, ..Default::default() };
+        let mut source = Blockchain::with_params(params.clone());
+        source.chain.push(crate::blockchain::genesis::create_genesis_block_with_params(&params));
+        source.mine_pending_transactions(miner_address("Shared"));
+
+        let mut local = source.clone();
+        local.mine_pending_transactions(miner_address("Local"));
+        for index in 0..2 {
+            source.mine_pending_transactions(miner_address(&format!("Peer{}", index)));
+        }
+
+        let sync_manager = SyncManager::new(
+            Arc::new(RwLock::new(local)),
+            Arc::new(crate::p2p::PeerManager::new(8, 8)),
+        );
+        let peer = "10.0.0.1:3001".parse().unwrap();
+
+        // Une branche qui n'est pas plus longue est refusée
+        assert_eq!(sync_manager.adopt_fork(peer, source.chain[1..3].to_vec()).await, 0);
+        assert_eq!(sync_manager.metrics.sync_errors(), 1);
+
+        // Le bloc commun est ignoré, les deux blocs du peer remplacent le nôtre
+        assert_eq!(sync_manager.adopt_fork(peer, source.chain[1..].to_vec()).await, 2);
+        let chain = sync_manager.blockchain.read().await;
+        assert_eq!(chain.chain.len(), 4);
+        assert_eq!(chain.get_latest_block().unwrap().hash, source.chain[3].hash);
+    }
+
+    #[tokio::test]
+    async fn test_only_requested_blocks_bypass_block_rate_limit() {
+        let params = crate::utils::config::ConsensusParams { difficulty: 1, ..Default::default() };
+        let genesis = crate::blockchain::genesis::create_genesis_block_with_params(&params);
+        let mut source = Blockchain::with_params(params.clone());
+        source.chain.push(genesis.clone());
+        for index in 0..5 {
+            source.mine_pending_transactions(miner_address(&format!("Miner{}", index)));
+        }
+
+        let mut local = Blockchain::with_params(params);
+        local.chain.push(genesis);
+        let mut limiter = RateLimiter::new();
+        limiter.max_blocks_per_minute = 2;
+        let mut sync_manager = SyncManager::new(
+            Arc::new(RwLock::new(local)),
+            Arc::new(crate::p2p::PeerManager::new(8, 8)),
+        );
+        let rate_limiter = Arc::new(std::sync::Mutex::new(limiter));
+        sync_manager.rate_limiter = Some(rate_limiter.clone());
+        let peer: SocketAddr = "10.0.0.1:3001".parse().unwrap();
+
+        // Lot non sollicité : coupé à la limite, l'excès est compté comme infraction
+        assert_eq!(sync_manager.apply_blocks(peer, source.chain[1..].to_vec()).await, 2);
+        assert_eq!(rate_limiter.lock().unwrap().violation_count(peer.ip()), 1);
+
+        // Plage demandée à ce peer : appliquée malgré la limite atteinte
+        sync_manager.requested.lock().unwrap().insert(peer, 3..=5);
+        assert_eq!(sync_manager.apply_blocks(peer, source.chain[3..].to_vec()).await, 3);
+        assert_eq!(sync_manager.blockchain.read().await.chain.len(), 6);
+
+        // La dérogation ne vaut qu'une fois
+        assert!(sync_manager.requested.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_periodic_sync_uses_configured_interval() {
+        let blockchain = Arc::new(RwLock::new(Blockchain::new()));
+        let network = blockchain.read().await.params.network;
+        // Peer fictif : port RPC éphémère libéré aussitôt, chaque requête est refusée
+        let rpc_port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let p2p_port = rpc_port - (network.default_rpc_port() - network.default_p2p_port());
+        let peer_manager = Arc::new(crate::p2p::PeerManager::new(8, 8));
+        peer_manager.add_peer(SocketAddr::from(([127, 0, 0, 1], p2p_port)), crate::p2p::PeerDirection::Outbound).await;
+        
+        let mut sync_manager = SyncManager::new(blockchain, peer_manager);
+        assert_eq!(sync_manager.sync_interval, Duration::from_secs(DEFAULT_SYNC_INTERVAL_SECS));
+        let interval = Duration::from_secs(45);
+        sync_manager.sync_interval = interval;
+        let sync_manager = Arc::new(sync_manager);
+        
+        let start = tokio::time::Instant::now();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let task = tokio::spawn({
+            let sync_manager = sync_manager.clone();
+            let shutdown = shutdown.clone();
+            async move { sync_manager.run(shutdown).await }
+        });
+        // Laisser la tâche armer sa première attente avant d'avancer l'horloge
+        tokio::task::yield_now().await;
+        
+        // Horloge figée : une tournée (une erreur de synchronisation) par intervalle, pas avant
+        for round in 1..=3u32 {
+            tokio::time::advance(interval - Duration::from_millis(1)).await;
+            assert_eq!(sync_manager.metrics.sync_errors(), u64::from(round - 1));
+            tokio::time::advance(Duration::from_millis(1)).await;
+            while sync_manager.metrics.sync_errors() < u64::from(round) {
+                tokio::task::yield_now().await;
+            }
+            assert_eq!(start.elapsed(), interval * round);
+        }
+        
+        shutdown.store(true, Ordering::SeqCst);
+        tokio::time::advance(interval).await;
+        task.await.unwrap();
+        assert_eq!(sync_manager.metrics.sync_errors(), 3);
+    }
+}
@@ -0,0 +1,168 @@
+use serde_json::{json, Value};
+use crate::rpc::server::{self, RpcState};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// Point d'entrée JSON-RPC 2.0 (`POST /rpc`) : requête unique ou batch.
+/// Les notifications (sans `id`) ne reçoivent pas de réponse.
+pub async fn handle_jsonrpc(state: &RpcState, body: &str) -> String {
+    let request: Value = match serde_json::from_str(body) {
+        Ok(value) => value,
+        Err(_) => return error_response(Value::Null, PARSE_ERROR, "Parse error").to_string(),
+    };
+
+    match request {
+        Value::Array(batch) if batch.is_empty() => {
+            error_response(Value::Null, INVALID_REQUEST, "Invalid Request").to_string()
+        },
+        Value::Array(batch) => {
+            let mut responses = Vec::new();
+            for call in batch {
+                if let Some(response) = handle_call(state, call).await {
+                    responses.push(response);
+                }
+            }
+            if responses.is_empty() {
+                String::new()
+            } else {
+                Value::Array(responses).to_string()
+            }
+        },
+        call => handle_call(state, call).await
+            .map(|response| response.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+async fn handle_call(state: &RpcState, call: Value) -> Option<Value> {
+    let id = call.get("id").cloned();
+    let response_id = id.clone().unwrap_or(Value::Null);
+
+    let method = match (call.get("jsonrpc"), call.get("method")) {
+        (Some(version), Some(Value::String(method))) if version == "2.0" => method.clone(),
+        _ => return Some(error_response(response_id, INVALID_REQUEST, "Invalid Request")),
+    };
+    let params = call.get("params").cloned().unwrap_or(Value::Array(vec![]));
+
+    let response = match dispatch(state, &method, &params).await {
+        Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": response_id }),
+        Err((code, message)) => error_response(response_id, code, &message),
+    };
+
+    // Notification : aucune réponse
+    id.map(|_| response)
+}
+
+/// Appeler le handler REST correspondant et convertir sa réponse JSON
+async fn dispatch(state: &RpcState, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+    let raw = match method {
+        "getstatus" => server::get_status(state.blockchain.clone()).await,
+        "getchaininfo" => server::get_chain_info(state.blockchain.clone()).await,
+        "getenergy" => server::get_energy_stats(state.energy.clone()).await,
+        "getbalance" => {
+            let address = string_param(params, 0)?;
+            server::handle_balance_request(state.blockchain.clone(), &format!("/balance/{}", address)).await
+        },
+        "getblocksfrom" => {
+            let from_height = params.get(0)
+                .and_then(Value::as_u64)
+                .ok_or((INVALID_PARAMS, "Expected block height".to_string()))?;
+            server::get_blocks_from(state.blockchain.clone(), from_height).await
+        },
+        _ => return Err((METHOD_NOT_FOUND, "Method not found".to_string())),
+    };
+
+    let result: Value = serde_json::from_str(&raw)
+        .map_err(|_| (SERVER_ERROR, "Invalid handler response".to_string()))?;
+
+    match result.get("error").and_then(Value::as_str) {
+        Some(message) => Err((SERVER_ERROR, message.to_string())),
+        None => Ok(result),
+    }
+}
+
+fn string_param(params: &Value, index: usize) -> Result<String, (i64, String)> {
+    params.get(index)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or((INVALID_PARAMS, format!("Expected string parameter at position {}", index)))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message },
+        "id": id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::Blockchain;
+    use crate::mining::EnergyTracker;
+    use crate::security::SecurityMonitor;
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::RwLock;
+
+    fn state() -> RpcState {
+        let mut chain = Blockchain::new();
+        chain.chain.push(crate::blockchain::create_genesis_block());
+        RpcState::new(
+            Arc::new(RwLock::new(chain)),
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_single_call() {
+        let body = r#"{"jsonrpc":"2.0","method":"getbalance","params":["AUR1Nobody"],"id":1}"#;
+        let response: Value = serde_json::from_str(&handle_jsonrpc(&state(), body).await).unwrap();
+
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["address"], "AUR1Nobody");
+        assert_eq!(response["result"]["confirmed"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_method_and_bad_params() {
+        let state = state();
+
+        let body = r#"{"jsonrpc":"2.0","method":"mineblock","id":"a"}"#;
+        let response: Value = serde_json::from_str(&handle_jsonrpc(&state, body).await).unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(response["id"], "a");
+
+        let body = r#"{"jsonrpc":"2.0","method":"getbalance","params":[],"id":2}"#;
+        let response: Value = serde_json::from_str(&handle_jsonrpc(&state, body).await).unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response: Value = serde_json::from_str(&handle_jsonrpc(&state, "{not json").await).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_batch() {
+        let body = r#"[
+            {"jsonrpc":"2.0","method":"getchaininfo","id":1},
+            {"jsonrpc":"2.0","method":"getstatus"},
+            {"jsonrpc":"2.0","method":"nope","id":3},
+            {"method":"getstatus","id":4}
+        ]"#;
+        let response: Value = serde_json::from_str(&handle_jsonrpc(&state(), body).await).unwrap();
+        let responses = response.as_array().unwrap();
+
+        // La notification (sans id) n'a pas de réponse
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["result"]["height"], 1);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["error"]["code"], INVALID_REQUEST);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod jsonrpc;
 pub mod server;
 
-pub use server::start_rpc_server;
+pub use server::{start_rpc_server, CorsPolicy, RpcState};
@@ -1,21 +1,180 @@
-use crate::blockchain::Blockchain;
-use std::sync::Arc;
+use crate::blockchain::{BlockOutcome, Blockchain};
+use crate::mining::EnergyTracker;
+use crate::mining::pow::ProofOfWork;
+use crate::p2p::{PeerManager, SyncManager, TxRelay};
+use crate::security::{RateLimiter, SecurityEventType, SecurityLogger, SecurityMonitor};
+use crate::security::monitor::AlertLevel;
+use crate::utils::metrics::Metrics;
+use crate::wallet::{NodeWallet, WalletError, WalletTxBuilder};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tokio::net::TcpListener;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
+/// Blocs sérialisés par chunk HTTP de `/blocks`
+const BLOCKS_PER_CHUNK: usize = 100;
+const NOT_FOUND: &str = r#"{"error":"not_found"}"#;
+/// Corps JSON illisible : répondu en HTTP 400
+const INVALID_JSON: &str = r#"{"error":"invalid_json"}"#;
+/// Routes POST ; toutes les autres routes connues sont en GET (voir `route_method`)
+const POST_ROUTES: &[&str] = &[
+    "/rpc", "/transaction", "/wallet/unlock", "/wallet/send", "/submitblock",
+    "/validate/block", "/validate/transaction", "/new_block",
+];
+const GET_ROUTES: &[&str] = &[
+    "/status", "/chain_info", "/banned", "/energy", "/alerts", "/getblocktemplate",
+    "/blocks", "/subscribe/blocks", "/metrics",
+];
+const GET_PREFIXES: &[&str] = &[
+    "/alerts?", "/balance/", "/nonce/", "/getblocktemplate?", "/tx/", "/blocks?", "/block/", "/blockstats/", "/blocks_from/",
+];
+/// Routes qui modifient l'état du nœud, refusées (403) en mode lecture seule
+const STATE_CHANGING_ROUTES: &[&str] = &["/transaction", "/wallet/unlock", "/wallet/send", "/submitblock", "/new_block"];
+/// Requêtes RPC acceptées par IP et par minute
+const RPC_REQUESTS_PER_MINUTE: usize = 120;
+/// Taille des lectures successives d'une requête HTTP
+const READ_CHUNK_SIZE: usize = 8192;
+/// Commentaire SSE envoyé sans nouveau bloc : détecte les abonnés déconnectés
+const SSE_KEEPALIVE_SECS: u64 = 15;
+
+/// En-têtes CORS des réponses RPC
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CorsPolicy {
+    /// `Access-Control-Allow-Origin: *`
+    #[default]
+    AnyOrigin,
+    /// Seulement ces origines, renvoyées quand la requête en provient
+    Origins(Vec<String>),
+    /// Aucun en-tête CORS : les navigateurs ne peuvent pas lire les réponses
+    Disabled,
+}
+
+impl CorsPolicy {
+    /// Lignes d'en-tête CORS pour une requête portant l'en-tête `Origin` donné (vide si refusée)
+    pub fn headers(&self, origin: Option<&str>) -> String {
+        match (self, origin) {
+            (CorsPolicy::AnyOrigin, _) => "Access-Control-Allow-Origin: *\r\n".to_string(),
+            (CorsPolicy::Origins(allowed), Some(origin)) if allowed.iter().any(|allowed| allowed == origin) => {
+                format!("Access-Control-Allow-Origin: {}\r\nVary: Origin\r\n", origin)
+            },
+            _ => String::new(),
+        }
+    }
+}
+
+/// État partagé du nœud accessible depuis les handlers RPC
+#[derive(Clone)]
+pub struct RpcState {
+    pub blockchain: Arc<RwLock<Blockchain>>,
+    pub energy: Arc<Mutex<EnergyTracker>>,
+    pub monitor: Arc<Mutex<SecurityMonitor>>,
+    /// Propagation P2P des transactions soumises ; `None` pour un nœud isolé
+    pub tx_relay: Option<Arc<TxRelay>>,
+    /// Bans du serveur P2P, exposés par `/banned`
+    pub rate_limiter: Arc<Mutex<RateLimiter>>,
+    /// Limitation des requêtes RPC par IP cliente (HTTP 429 au-delà)
+    pub request_limiter: Arc<Mutex<RateLimiter>>,
+    pub security_logger: Arc<SecurityLogger>,
+    /// Propagation des blocs soumis par `/submitblock` ; `None` pour un nœud isolé
+    pub sync_manager: Option<Arc<SyncManager>>,
+    /// Fichier où la chaîne est sauvegardée après un bloc accepté
+    pub data_file: String,
+    /// Peers connus, comptés par `/metrics` ; `None` pour un nœud isolé
+    pub peer_manager: Option<Arc<PeerManager>>,
+    /// Compteurs du nœud partagés avec le mining et la synchronisation
+    pub metrics: Arc<Metrics>,
+    /// Wallet servi par `/wallet/unlock` et `/wallet/send` ; `None` (défaut) les désactive
+    pub wallet: Option<Arc<NodeWallet>>,
+    pub cors: CorsPolicy,
+    /// Refuser les routes de `STATE_CHANGING_ROUTES` (HTTP 403)
+    pub read_only: bool,
+}
+
+impl RpcState {
+    pub fn new(
+        blockchain: Arc<RwLock<Blockchain>>,
+        energy: Arc<Mutex<EnergyTracker>>,
+        monitor: Arc<Mutex<SecurityMonitor>>,
+    ) -> Self {
+        let mut request_limiter = RateLimiter::new();
+        request_limiter.max_messages_per_minute = RPC_REQUESTS_PER_MINUTE;
+        
+        Self {
+            blockchain,
+            energy,
+            monitor,
+            tx_relay: None,
+            rate_limiter: Arc::new(Mutex::new(RateLimiter::new())),
+            request_limiter: Arc::new(Mutex::new(request_limiter)),
+            security_logger: Arc::new(SecurityLogger::new()),
+            sync_manager: None,
+            data_file: "/tmp/auriumchain.json".to_string(),
+            peer_manager: None,
+            metrics: Arc::new(Metrics::new()),
+            wallet: None,
+            cors: CorsPolicy::default(),
+            read_only: false,
+        }
+    }
+
+    /// Vérifier qu'une requête de `size` octets peut être servie à cette IP ;
+    /// un refus est compté comme infraction et journalisé
+    pub fn allow_request(&self, ip: IpAddr, size: usize) -> bool {
+        let mut limiter = match self.request_limiter.lock() {
+            Ok(limiter) => limiter,
+            Err(_) => return true,
+        };
+        if limiter.allow_message(ip, size) {
+            return true;
+        }
+
+        let violations = limiter.record_violation(ip);
+        drop(limiter);
+        self.security_logger.log_event(
+            SecurityEventType::RateLimitExceeded,
+            &ip.to_string(),
+            &format!("RPC request refused ({} violations)", violations),
+        );
+        false
+    }
+
+    /// Vérifier qu'un bloc poussé par `/new_block` peut être accepté de cette IP
+    /// (`max_blocks_per_minute`) ; un refus est compté comme infraction et journalisé
+    pub fn allow_block(&self, ip: IpAddr) -> bool {
+        let mut limiter = match self.request_limiter.lock() {
+            Ok(limiter) => limiter,
+            Err(_) => return true,
+        };
+        if limiter.allow_block(ip) {
+            return true;
+        }
+
+        let violations = limiter.record_violation(ip);
+        drop(limiter);
+        self.security_logger.log_event(
+            SecurityEventType::RateLimitExceeded,
+            &ip.to_string(),
+            &format!("block rate limit exceeded ({} violations)", violations),
+        );
+        false
+    }
+}
+
+/// Serveur RPC sur `bind_addr` ; une adresse loopback (défaut du nœud) le rend
+/// inaccessible depuis l'extérieur
 pub async fn start_rpc_server(
-    blockchain: Arc<RwLock<Blockchain>>, 
-    port: u16
+    state: RpcState,
+    bind_addr: SocketAddr,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let listener = TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
-    println!("RPC Server listening on http://0.0.0.0:{}", port);
+    let listener = TcpListener::bind(bind_addr).await?;
+    println!("RPC Server listening on http://{}", bind_addr);
     
     loop {
         if let Ok((stream, _)) = listener.accept().await {
-            let chain = blockchain.clone();
+            let state = state.clone();
             tokio::spawn(async move {
-                if let Err(e) = handle_connection(stream, chain).await {
+                if let Err(e) = handle_connection(stream, state).await {
                     eprintln!("Connection error: {}", e);
                 }
             });
@@ -34,35 +193,120 @@ pub async fn get_status(blockchain: Arc<RwLock<Blockchain>>) -> String {
     )
 }
 
-async fn get_all_blocks(
+/// `/blocks` : tableau JSON envoyé en `Transfer-Encoding: chunked`, par lots de
+/// `BLOCKS_PER_CHUNK` blocs. Le verrou n'est tenu que le temps de copier un lot.
+pub(crate) async fn stream_all_blocks<W>(
+    writer: &mut W,
     blockchain: Arc<RwLock<Blockchain>>,
-) -> String {
-    let chain = blockchain.read().await;
+    cors: &str,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(
+        format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}Transfer-Encoding: chunked\r\n\r\n", cors).as_bytes()
+    ).await?;
     
-    match serde_json::to_string(&chain.chain) {
-        Ok(json) => json,
-        Err(_) => r#"{"error":"Serialization failed"}"#.to_string(),
+    let mut start = 0;
+    loop {
+        let batch = {
+            let chain = blockchain.read().await;
+            chain.chain.iter().skip(start).take(BLOCKS_PER_CHUNK).cloned().collect::<Vec<_>>()
+        };
+        
+        let mut chunk = String::from(if start == 0 { "[" } else { "" });
+        for (i, block) in batch.iter().enumerate() {
+            if start + i > 0 {
+                chunk.push(',');
+            }
+            chunk.push_str(&serde_json::to_string(block)?);
+        }
+        if batch.len() < BLOCKS_PER_CHUNK {
+            chunk.push(']');
+        }
+        
+        writer.write_all(format!("{:x}\r\n{}\r\n", chunk.len(), chunk).as_bytes()).await?;
+        if batch.len() < BLOCKS_PER_CHUNK {
+            break;
+        }
+        start += batch.len();
+    }
+    
+    writer.write_all(b"0\r\n\r\n").await?;
+    Ok(())
+}
+
+/// `/subscribe/blocks` : flux SSE d'un événement `{"index","hash"}` par nouveau sommet.
+/// Se termine (et libère l'abonnement) dès qu'une écriture vers le client échoue.
+pub(crate) async fn stream_tip_events<W>(
+    writer: &mut W,
+    blockchain: Arc<RwLock<Blockchain>>,
+    cors: &str,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut tips = blockchain.read().await.subscribe_tips();
+    writer.write_all(
+        format!("HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n{}Connection: keep-alive\r\n\r\n", cors).as_bytes()
+    ).await?;
+    writer.flush().await?;
+    
+    loop {
+        let event = match tokio::time::timeout(std::time::Duration::from_secs(SSE_KEEPALIVE_SECS), tips.recv()).await {
+            Ok(Ok(tip)) => format!("data: {}\n\n", serde_json::to_string(&tip)?),
+            // Abonné trop lent : les sommets intermédiaires sont perdus, le suivant sera envoyé
+            Ok(Err(tokio::sync::broadcast::error::RecvError::Lagged(_))) => continue,
+            Ok(Err(tokio::sync::broadcast::error::RecvError::Closed)) => return Ok(()),
+            Err(_) => ": keepalive\n\n".to_string(),
+        };
+        writer.write_all(event.as_bytes()).await?;
+        writer.flush().await?;
     }
 }
 
-async fn handle_balance_request(
+pub(crate) async fn handle_balance_request(
     blockchain: Arc<RwLock<Blockchain>>,
     path: &str,
 ) -> String {
     if let Some(address) = path.strip_prefix("/balance/") {
         let chain = blockchain.read().await;
-        let balance = chain.get_balance(address);
+        let utxos = chain.get_utxos(address);
+        let confirmed = utxos.iter().fold(0u64, |acc, utxo| acc.saturating_add(utxo.value));
         
-        format!(
-            r#"{{"address":"{}","balance":{},"currency":"AUR"}}"#,
-            address, balance
-        )
+        serde_json::json!({
+            "address": address,
+            "confirmed": confirmed,
+            "pending": chain.get_pending_balance_change(address),
+            "utxo_count": utxos.len(),
+            "currency": "AUR",
+        }).to_string()
     } else {
         r#"{"error":"Invalid balance request"}"#.to_string()
     }
 }
 
-async fn get_chain_info(
+/// Prochain numéro de séquence d'une adresse : transactions envoyées confirmées
+/// plus celles en attente dans le mempool
+pub(crate) async fn handle_nonce_request(
+    blockchain: Arc<RwLock<Blockchain>>,
+    path: &str,
+) -> String {
+    match path.strip_prefix("/nonce/") {
+        Some(address) if !address.is_empty() => {
+            let (confirmed, pending) = blockchain.read().await.sent_transaction_counts(address);
+            serde_json::json!({
+                "address": address,
+                "confirmed": confirmed,
+                "pending": pending,
+                "next_nonce": confirmed + pending,
+            }).to_string()
+        }
+        _ => r#"{"error":"Invalid nonce request"}"#.to_string(),
+    }
+}
+
+pub(crate) async fn get_chain_info(
     blockchain: Arc<RwLock<Blockchain>>,
 ) -> String {
     let chain = blockchain.read().await;
@@ -70,14 +314,77 @@ async fn get_chain_info(
     let latest_hash = chain.get_latest_block()
         .map(|b| b.hash.clone())
         .unwrap_or_default();
+    let pow = ProofOfWork::new(chain.params.difficulty);
     
-    format!(
-        r#"{{"height":{},"latest_hash":"{}","difficulty":{}}}"#,
-        height, latest_hash, chain.get_difficulty()
-    )
+    serde_json::json!({
+        "height": height,
+        "latest_hash": latest_hash,
+        "difficulty": chain.get_difficulty(),
+        "estimated_hashrate": pow.estimate_hashrate(&chain.chain),
+        "average_block_time": ProofOfWork::average_block_time(&chain.chain),
+        "mempool_size": chain.mempool.len(),
+        "utxo_set_hash": chain.utxo_set_hash(),
+    }).to_string()
+}
+
+pub(crate) async fn get_energy_stats(
+    energy: Arc<Mutex<EnergyTracker>>,
+) -> String {
+    let tracker = match energy.lock() {
+        Ok(tracker) => tracker,
+        Err(_) => return r#"{"error":"Energy tracker unavailable"}"#.to_string(),
+    };
+    let comparison = tracker.compare_with_others();
+    
+    serde_json::json!({
+        "total_blocks_mined": tracker.total_blocks_mined,
+        "total_energy_kwh": tracker.total_energy_kwh(),
+        "average_wh_per_block": tracker.average_energy_per_block(),
+        "total_hash_attempts": tracker.total_hash_attempts,
+        "reduction_vs_bitcoin_percent": comparison.reduction_vs_bitcoin(),
+        "comparison": comparison,
+    }).to_string()
 }
 
-async fn get_blocks_from(
+/// IP actuellement bannies par le serveur P2P : `/banned`
+pub(crate) fn get_banned(state: &RpcState) -> String {
+    let banned: Vec<String> = match state.rate_limiter.lock() {
+        Ok(limiter) => limiter.get_banned_ips().iter().map(|ip| ip.to_string()).collect(),
+        Err(_) => return r#"{"error":"Rate limiter unavailable"}"#.to_string(),
+    };
+
+    serde_json::json!({ "count": banned.len(), "banned": banned }).to_string()
+}
+
+/// Métriques au format texte Prometheus : `/metrics`
+pub(crate) async fn get_metrics(state: &RpcState) -> String {
+    let (height, mempool_size) = {
+        let chain = state.blockchain.read().await;
+        (chain.chain.len().saturating_sub(1), chain.mempool.len())
+    };
+    let peer_count = match &state.peer_manager {
+        Some(peer_manager) => peer_manager.get_peers().await.len(),
+        None => 0,
+    };
+    let energy_wh = state.energy.lock().map_or(0.0, |tracker| tracker.total_energy_wh);
+    let banned = state.rate_limiter.lock().map_or(0, |limiter| limiter.get_banned_ips().len());
+
+    let metrics: [(&str, &str, &str, String); 7] = [
+        ("auriumchain_chain_height", "gauge", "Index of the latest block", height.to_string()),
+        ("auriumchain_mempool_size", "gauge", "Transactions waiting in the mempool", mempool_size.to_string()),
+        ("auriumchain_peer_count", "gauge", "Known peers", peer_count.to_string()),
+        ("auriumchain_blocks_mined_total", "counter", "Blocks mined by this node since start", state.metrics.blocks_mined().to_string()),
+        ("auriumchain_energy_wh_total", "counter", "Estimated mining energy since start (Wh)", energy_wh.to_string()),
+        ("auriumchain_banned_ips", "gauge", "IPs currently banned by the P2P server", banned.to_string()),
+        ("auriumchain_sync_errors_total", "counter", "Failed synchronizations with peers", state.metrics.sync_errors().to_string()),
+    ];
+
+    metrics.iter()
+        .map(|(name, kind, help, value)| format!("# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}\n"))
+        .collect()
+}
+
+pub(crate) async fn get_blocks_from(
     blockchain: Arc<RwLock<Blockchain>>,
     from_height: u64,
 ) -> String {
@@ -92,34 +399,493 @@ async fn get_blocks_from(
     }
 }
 
+fn block_json(block: Option<&crate::blockchain::Block>) -> String {
+    match block.map(serde_json::to_string) {
+        Some(Ok(json)) => json,
+        Some(Err(_)) => r#"{"error":"Serialization failed"}"#.to_string(),
+        None => NOT_FOUND.to_string(),
+    }
+}
+
+/// Bloc à une hauteur : `/block/{index}`
+pub(crate) async fn get_block(
+    blockchain: Arc<RwLock<Blockchain>>,
+    index: &str,
+) -> String {
+    let chain = blockchain.read().await;
+    block_json(index.parse().ok().and_then(|index| chain.get_block(index)))
+}
+
+/// Bloc par hash : `/block/hash/{hash}`
+pub(crate) async fn get_block_by_hash(
+    blockchain: Arc<RwLock<Blockchain>>,
+    hash: &str,
+) -> String {
+    let chain = blockchain.read().await;
+    block_json(chain.get_block_by_hash(hash))
+}
+
+/// Statistiques agrégées d'un bloc pour les explorateurs : `/blockstats/{index}`.
+/// Les frais sont calculés à partir des outputs de la chaîne (inputs - outputs) ;
+/// `total_output` exclut la coinbase, reportée séparément dans `coinbase_reward`.
+pub(crate) async fn get_block_stats(
+    blockchain: Arc<RwLock<Blockchain>>,
+    index: &str,
+) -> String {
+    let chain = blockchain.read().await;
+    let block = match index.parse().ok().and_then(|index| chain.get_block(index)) {
+        Some(block) => block,
+        None => return NOT_FOUND.to_string(),
+    };
+    let size = match bincode::serialize(block) {
+        Ok(bytes) => bytes.len(),
+        Err(_) => return r#"{"error":"Serialization failed"}"#.to_string(),
+    };
+
+    let (coinbase, transfers): (Vec<_>, Vec<_>) = block.transactions.iter()
+        .partition(|tx| tx.is_coinbase());
+    let total_output = transfers.iter()
+        .fold(0u64, |acc, tx| acc.saturating_add(tx.total_output()));
+    let total_fees = transfers.iter()
+        .filter_map(|tx| tx.fee_with(|input| chain.resolve_input(input)))
+        .fold(0u64, u64::saturating_add);
+    let coinbase_reward = coinbase.iter()
+        .fold(0u64, |acc, tx| acc.saturating_add(tx.total_output()));
+
+    serde_json::json!({
+        "index": block.index,
+        "hash": block.hash,
+        "tx_count": block.transactions.len(),
+        "total_output": total_output,
+        "total_fees": total_fees,
+        "size": size,
+        "coinbase_reward": coinbase_reward,
+        "nonce": block.nonce,
+        "difficulty": block.difficulty,
+    }).to_string()
+}
+
+/// Transaction confirmée par son id : `/tx/{txid}`
+pub(crate) async fn get_transaction(
+    blockchain: Arc<RwLock<Blockchain>>,
+    txid: &str,
+) -> String {
+    let chain = blockchain.read().await;
+    match chain.get_transaction(txid) {
+        Some((tx, height)) => serde_json::json!({
+            "txid": tx.id,
+            "block_height": height,
+            "confirmations": (chain.chain.len() as u64).saturating_sub(height),
+            "transaction": tx,
+        }).to_string(),
+        None => NOT_FOUND.to_string(),
+    }
+}
+
+/// Plage de blocs inclusive : `/blocks?start=X&end=Y`, au plus `MAX_BLOCKS_PER_RANGE`
+pub(crate) async fn get_blocks_range(
+    blockchain: Arc<RwLock<Blockchain>>,
+    path: &str,
+) -> String {
+    let query = path.split_once('?').map(|(_, q)| q).unwrap_or("");
+    let mut start = None;
+    let mut end = None;
+    
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "start" => start = value.parse::<u64>().ok(),
+            "end" => end = value.parse::<u64>().ok(),
+            _ => {}
+        }
+    }
+    
+    let chain = blockchain.read().await;
+    let range = match (start, end) {
+        (Some(start), Some(end)) => chain.get_blocks_range(start, end),
+        _ => return r#"{"error":"Invalid range"}"#.to_string(),
+    };
+    let blocks = match range {
+        Ok(range) if range.found() > 0 => range.blocks,
+        Ok(_) => return NOT_FOUND.to_string(),
+        Err(_) => return r#"{"error":"Invalid range"}"#.to_string(),
+    };
+    
+    match serde_json::to_string(blocks) {
+        Ok(json) => json,
+        Err(_) => r#"{"error":"Serialization failed"}"#.to_string(),
+    }
+}
+
+/// Alertes de sécurité récentes : `/alerts?level=critical&limit=50`
+async fn get_alerts(monitor: Arc<Mutex<SecurityMonitor>>, path: &str) -> String {
+    let query = path.split_once('?').map(|(_, q)| q).unwrap_or("");
+    let mut level = None;
+    let mut limit = 50;
+    
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "level" => match AlertLevel::parse(value) {
+                Some(parsed) => level = Some(parsed),
+                None => return r#"{"error":"Invalid alert level"}"#.to_string(),
+            },
+            "limit" => limit = value.parse().unwrap_or(limit),
+            _ => {}
+        }
+    }
+    
+    let monitor = match monitor.lock() {
+        Ok(monitor) => monitor,
+        Err(_) => return r#"{"error":"Monitor unavailable"}"#.to_string(),
+    };
+    let mut alerts = match level {
+        Some(AlertLevel::Critical) => monitor.get_critical_alerts(),
+        Some(level) => monitor.get_recent_alerts(usize::MAX)
+            .into_iter()
+            .filter(|alert| alert.level == level)
+            .collect(),
+        None => monitor.get_recent_alerts(limit),
+    };
+    let start = alerts.len().saturating_sub(limit);
+    alerts.drain(..start);
+    
+    serde_json::json!({
+        "count": alerts.len(),
+        "alerts": alerts,
+    }).to_string()
+}
+
 async fn handle_new_block(
     blockchain: Arc<RwLock<Blockchain>>,
+    monitor: Arc<Mutex<SecurityMonitor>>,
+    data_file: &str,
     body: &str,
 ) -> String {
     match serde_json::from_str::<crate::blockchain::Block>(body) {
         Ok(block) => {
+            if let Ok(mut monitor) = monitor.lock() {
+                monitor.process_block(&block);
+            }
+            
             let mut chain = blockchain.write().await;
-            if chain.validate_new_block(&block) {
-                chain.chain.push(block);
-                if let Err(e) = chain.save_to_file("/tmp/auriumchain.json") {
-                    eprintln!("Failed to save blockchain: {}", e);
-                }
-                r#"{"status":"block_accepted"}"#.to_string()
-            } else {
-                r#"{"error":"invalid_block"}"#.to_string()
+            match chain.receive_block(block) {
+                BlockOutcome::Connected(_) => {
+                    if let Err(e) = chain.save_to_file(data_file) {
+                        eprintln!("Failed to save blockchain: {}", e);
+                    }
+                    r#"{"status":"block_accepted"}"#.to_string()
+                },
+                BlockOutcome::Orphaned => r#"{"status":"block_orphaned"}"#.to_string(),
+                BlockOutcome::Rejected => r#"{"error":"invalid_block"}"#.to_string(),
             }
         },
-        Err(_) => r#"{"error":"invalid_json"}"#.to_string(),
+        Err(_) => INVALID_JSON.to_string(),
+    }
+}
+
+/// `/getblocktemplate?address=` : champs d'en-tête du prochain bloc et transactions à inclure
+/// (coinbase en tête) ; le mineur externe n'a plus qu'à trouver le nonce
+pub(crate) async fn get_block_template(blockchain: Arc<RwLock<Blockchain>>, path: &str) -> String {
+    let query = path.split_once('?').map(|(_, q)| q).unwrap_or("");
+    let address = query.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "address")
+        .map(|(_, value)| value.to_string());
+    let Some(address) = address.filter(|address| !address.is_empty()) else {
+        return r#"{"error":"missing_address"}"#.to_string();
+    };
+    
+    let chain = blockchain.read().await;
+    if chain.chain.is_empty() {
+        return r#"{"error":"no_genesis"}"#.to_string();
+    }
+    let template = chain.block_template(address);
+    
+    serde_json::json!({
+        "index": template.index,
+        "previous_hash": template.previous_hash,
+        "timestamp": template.timestamp,
+        "min_timestamp": chain.median_time_past() + 1,
+        "difficulty": template.difficulty,
+        "target_bits": template.target_bits,
+        "merkle_root": template.merkle_root,
+        "miner_address": template.miner_address,
+        "coinbase_value": template.transactions[0].total_output(),
+        "transactions": template.transactions,
+    }).to_string()
+}
+
+/// `/submitblock` : bloc miné par un mineur externe. Validé strictement contre la chaîne,
+/// ajouté, sauvegardé puis propagé aux peers.
+pub(crate) async fn handle_submit_block(state: &RpcState, body: &str) -> String {
+    let block = match serde_json::from_str::<crate::blockchain::Block>(body) {
+        Ok(block) => block,
+        Err(_) => return INVALID_JSON.to_string(),
+    };
+    
+    {
+        let mut chain = state.blockchain.write().await;
+        if let Err(e) = chain.check_new_block(&block) {
+            return serde_json::json!({ "status": "rejected", "reason": e.to_string() }).to_string();
+        }
+        if !chain.append_if_tip(block.clone()) {
+            return r#"{"status":"rejected","reason":"Block does not extend the current tip"}"#.to_string();
+        }
+        
+        if let Ok(mut monitor) = state.monitor.lock() {
+            monitor.process_block(&block);
+        }
+        if let Err(e) = chain.save_to_file(&state.data_file) {
+            eprintln!("Failed to save blockchain: {}", e);
+        }
     }
+    
+    let hash = block.hash.clone();
+    if let Some(sync_manager) = state.sync_manager.clone() {
+        tokio::spawn(async move {
+            if let Err(e) = sync_manager.broadcast_new_block(block).await {
+                eprintln!("Failed to broadcast submitted block: {}", e);
+            }
+        });
+    }
+    serde_json::json!({ "status": "accepted", "hash": hash }).to_string()
+}
+
+/// Soumission d'une transaction (JSON) : validée, ajoutée au mempool puis relayée aux peers
+pub(crate) async fn handle_new_transaction(state: &RpcState, body: &str) -> String {
+    match serde_json::from_str::<crate::blockchain::Transaction>(body) {
+        Ok(tx) => submit_transaction(state, tx).await,
+        Err(_) => INVALID_JSON.to_string(),
+    }
+}
+
+async fn submit_transaction(state: &RpcState, tx: crate::blockchain::Transaction) -> String {
+    let result = {
+        let mut chain = state.blockchain.write().await;
+        let result = chain.accept_transaction(tx.clone());
+        if result.is_ok() {
+            chain.mempool.mark_seen(&tx.id);
+        }
+        result
+    };
+    
+    match result {
+        Ok(fee) => {
+            if let Some(relay) = state.tx_relay.clone() {
+                let tx = tx.clone();
+                tokio::spawn(async move { relay.broadcast(&tx, None).await });
+            }
+            serde_json::json!({ "status": "accepted", "txid": tx.id, "fee": fee }).to_string()
+        },
+        Err(e) => serde_json::json!({ "error": "rejected", "reason": e.to_string() }).to_string(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct UnlockRequest {
+    password: String,
+}
+
+#[derive(serde::Deserialize)]
+struct SendRequest {
+    to: String,
+    amount: u64,
+}
+
+/// `/wallet/unlock` : déchiffrer la clé du wallet du nœud pour `unlock_timeout`
+pub(crate) async fn handle_wallet_unlock(state: &RpcState, body: &str) -> String {
+    let Some(wallet) = &state.wallet else {
+        return r#"{"error":"wallet_disabled"}"#.to_string();
+    };
+    let request = match serde_json::from_str::<UnlockRequest>(body) {
+        Ok(request) => request,
+        Err(_) => return INVALID_JSON.to_string(),
+    };
+
+    // Argon2 est volontairement coûteux : hors du runtime async
+    let unlocking = wallet.clone();
+    let result = tokio::task::spawn_blocking(move || unlocking.unlock(&request.password)).await;
+    match result {
+        Ok(Ok(())) => serde_json::json!({
+            "status": "unlocked",
+            "address": wallet.address(),
+            "timeout_secs": wallet.unlock_timeout.as_secs(),
+        }).to_string(),
+        Ok(Err(WalletError::WrongPassword)) => r#"{"error":"wrong_password"}"#.to_string(),
+        Ok(Err(e)) => serde_json::json!({ "error": "unlock_failed", "reason": e.to_string() }).to_string(),
+        Err(e) => serde_json::json!({ "error": "unlock_failed", "reason": e.to_string() }).to_string(),
+    }
+}
+
+/// `/wallet/send` : construire, signer avec le wallet déverrouillé et diffuser un paiement
+/// au taux de frais minimum du mempool ; la monnaie revient à l'adresse du wallet
+pub(crate) async fn handle_wallet_send(state: &RpcState, body: &str) -> String {
+    let Some(wallet) = &state.wallet else {
+        return r#"{"error":"wallet_disabled"}"#.to_string();
+    };
+    let request = match serde_json::from_str::<SendRequest>(body) {
+        Ok(request) => request,
+        Err(_) => return INVALID_JSON.to_string(),
+    };
+    let Some(keypair) = wallet.keypair() else {
+        return r#"{"error":"wallet_locked"}"#.to_string();
+    };
+
+    let built = {
+        let chain = state.blockchain.read().await;
+        if !crate::wallet::address::validate_address(&request.to, chain.params.network) {
+            return r#"{"error":"invalid_address"}"#.to_string();
+        }
+        WalletTxBuilder::new().build(
+            &chain.get_utxos(wallet.address()),
+            &request.to,
+            request.amount,
+            chain.mempool.min_fee_rate(),
+        )
+    };
+    let mut tx = match built {
+        Ok(tx) => tx,
+        Err(reason) => return serde_json::json!({ "error": "rejected", "reason": reason }).to_string(),
+    };
+    for index in 0..tx.inputs.len() {
+        if let Err(reason) = keypair.sign_transaction_input(&mut tx, index) {
+            return serde_json::json!({ "error": "rejected", "reason": reason }).to_string();
+        }
+    }
+
+    submit_transaction(state, tx).await
+}
+
+fn validation_json(result: anyhow::Result<()>) -> String {
+    match result {
+        Ok(()) => r#"{"valid":true}"#.to_string(),
+        Err(e) => serde_json::json!({ "valid": false, "reason": e.to_string() }).to_string(),
+    }
+}
+
+/// `/validate/block` : validation complète d'un bloc contre le sommet actuel
+/// (règles de consensus, signatures, inputs) sans rien ajouter à la chaîne
+pub(crate) async fn handle_validate_block(state: &RpcState, body: &str) -> String {
+    let block = match serde_json::from_str::<crate::blockchain::Block>(body) {
+        Ok(block) => block,
+        Err(_) => return INVALID_JSON.to_string(),
+    };
+
+    let chain = state.blockchain.read().await;
+    validation_json(chain.check_new_block(&block))
+}
+
+/// `/validate/transaction` : signatures et inputs d'une transaction vérifiés pour le
+/// prochain bloc, sans l'ajouter au mempool
+pub(crate) async fn handle_validate_transaction(state: &RpcState, body: &str) -> String {
+    let tx = match serde_json::from_str::<crate::blockchain::Transaction>(body) {
+        Ok(tx) => tx,
+        Err(_) => return INVALID_JSON.to_string(),
+    };
+
+    let chain = state.blockchain.read().await;
+    validation_json(
+        chain.validate_transaction(&tx)
+    )
+}
+
+/// Lire une requête HTTP complète : en-têtes puis `Content-Length` octets de corps,
+/// sur autant de lectures que nécessaire. La lecture s'arrête au-delà de `max_size`
+/// octets ; la requête tronquée est alors refusée par `allow_request`.
+pub(crate) async fn read_http_request<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    max_size: usize,
+) -> std::io::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+    let mut expected_len = None;
+
+    while buffer.len() <= max_size {
+        if expected_len.is_none() {
+            expected_len = buffer.windows(4)
+                .position(|window| window == b"\r\n\r\n")
+                .map(|header_end| header_end + 4 + content_length(&buffer[..header_end]));
+        }
+        if expected_len.is_some_and(|len| buffer.len() >= len) {
+            break;
+        }
+
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+
+    Ok(buffer)
+}
+
+/// Valeur de l'en-tête `Content-Length` (0 s'il est absent ou invalide)
+fn content_length(headers: &[u8]) -> usize {
+    String::from_utf8_lossy(headers)
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Valeur d'un en-tête de la requête (nom insensible à la casse)
+fn header_value<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request.split("\r\n\r\n").next()?
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(header, _)| header.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// Méthode servie par une route connue ; `None` pour une route inconnue (404)
+fn route_method(path: &str) -> Option<&'static str> {
+    if POST_ROUTES.contains(&path) {
+        Some("POST")
+    } else if GET_ROUTES.contains(&path) || GET_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+        Some("GET")
+    } else {
+        None
+    }
+}
+
+/// Réponse HTTP JSON complète ; `headers` sont des lignes d'en-tête terminées par CRLF
+pub(crate) fn http_response(status: u16, headers: &str, body: &str) -> String {
+    let reason = match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        429 => "Too Many Requests",
+        _ => "Error",
+    };
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\n\r\n{}",
+        status, reason, headers, body.len(), body
+    )
 }
 
 async fn handle_connection(
     stream: tokio::net::TcpStream,
-    blockchain: Arc<RwLock<Blockchain>>,
+    state: RpcState,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut buffer = vec![0u8; 8192];
+    let blockchain = state.blockchain.clone();
+    let client_ip = stream.peer_addr()?.ip();
     let (mut reader, mut writer) = stream.into_split();
-    let n = reader.read(&mut buffer).await?;
+    let max_size = state.request_limiter.lock()
+        .map(|limiter| limiter.max_message_size_bytes)
+        .unwrap_or(READ_CHUNK_SIZE);
+    let buffer = read_http_request(&mut reader, max_size).await?;
+    let n = buffer.len();
+    
+    if !state.allow_request(client_ip, n) {
+        writer.write_all(http_response(429, "Retry-After: 60\r\n", r#"{"error":"rate_limited"}"#).as_bytes()).await?;
+        return Ok(());
+    }
+    
     let request = String::from_utf8_lossy(&buffer[..n]);
     
     let (method, path, body) = if let Some(first_line) = request.lines().next() {
@@ -137,27 +903,889 @@ async fn handle_connection(
     } else {
         ("GET", "/status", "")
     };
+    let cors = state.cors.headers(header_value(&request, "Origin"));
+    
+    // Route inconnue (404), méthode non servie par la route (405, ou pré-requête CORS),
+    // écriture refusée en lecture seule (403)
+    let Some(allowed) = route_method(path) else {
+        writer.write_all(http_response(404, &cors, NOT_FOUND).as_bytes()).await?;
+        return Ok(());
+    };
+    if method == "OPTIONS" && state.cors != CorsPolicy::Disabled {
+        let headers = format!("{}Access-Control-Allow-Methods: {}\r\nAccess-Control-Allow-Headers: Content-Type\r\n", cors, allowed);
+        writer.write_all(http_response(204, &headers, "").as_bytes()).await?;
+        return Ok(());
+    }
+    if method != allowed {
+        let headers = format!("{}Allow: {}\r\n", cors, allowed);
+        writer.write_all(http_response(405, &headers, r#"{"error":"method_not_allowed"}"#).as_bytes()).await?;
+        return Ok(());
+    }
+    if state.read_only && STATE_CHANGING_ROUTES.contains(&path) {
+        writer.write_all(http_response(403, &cors, r#"{"error":"read_only"}"#).as_bytes()).await?;
+        return Ok(());
+    }
+    if path == "/new_block" && !state.allow_block(client_ip) {
+        let headers = format!("{}Retry-After: 60\r\n", cors);
+        writer.write_all(http_response(429, &headers, r#"{"error":"block_rate_limited"}"#).as_bytes()).await?;
+        return Ok(());
+    }
+    
+    if (method, path) == ("GET", "/blocks") {
+        return stream_all_blocks(&mut writer, blockchain, &cors).await;
+    }
+    if (method, path) == ("GET", "/subscribe/blocks") {
+        return stream_tip_events(&mut writer, blockchain, &cors).await;
+    }
+    if (method, path) == ("GET", "/metrics") {
+        let metrics = get_metrics(&state).await;
+        let http_response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+            metrics.len(),
+            metrics
+        );
+        writer.write_all(http_response.as_bytes()).await?;
+        return Ok(());
+    }
     
     let response = match (method, path) {
         ("GET", "/status") => get_status(blockchain).await,
-        ("GET", "/blocks") => get_all_blocks(blockchain).await,
         ("GET", "/chain_info") => get_chain_info(blockchain).await,
+        ("GET", "/banned") => get_banned(&state),
+        ("GET", "/energy") => get_energy_stats(state.energy.clone()).await,
+        ("GET", path) if path == "/alerts" || path.starts_with("/alerts?") => {
+            get_alerts(state.monitor.clone(), path).await
+        },
         ("GET", path) if path.starts_with("/balance/") => handle_balance_request(blockchain, path).await,
+        ("GET", path) if path.starts_with("/nonce/") => handle_nonce_request(blockchain, path).await,
+        ("GET", path) if path == "/getblocktemplate" || path.starts_with("/getblocktemplate?") => {
+            get_block_template(blockchain, path).await
+        },
+        ("GET", path) if path.starts_with("/tx/") => get_transaction(blockchain, path.trim_start_matches("/tx/")).await,
+        ("GET", path) if path.starts_with("/blocks?") => get_blocks_range(blockchain, path).await,
+        ("GET", path) if path.starts_with("/block/hash/") => {
+            get_block_by_hash(blockchain, path.trim_start_matches("/block/hash/")).await
+        },
+        ("GET", path) if path.starts_with("/blockstats/") => {
+            get_block_stats(blockchain, path.trim_start_matches("/blockstats/")).await
+        },
+        ("GET", path) if path.starts_with("/block/") => {
+            get_block(blockchain, path.trim_start_matches("/block/")).await
+        },
         ("GET", path) if path.starts_with("/blocks_from/") => {
             let height_str = path.strip_prefix("/blocks_from/").unwrap_or("0");
             let from_height = height_str.parse().unwrap_or(0);
             get_blocks_from(blockchain, from_height).await
         },
-        ("POST", "/new_block") => handle_new_block(blockchain, body).await,
-        _ => r#"{"error":"Not found"}"#.to_string(),
+        ("POST", "/rpc") => crate::rpc::jsonrpc::handle_jsonrpc(&state, body).await,
+        ("POST", "/transaction") => handle_new_transaction(&state, body).await,
+        ("POST", "/wallet/unlock") => handle_wallet_unlock(&state, body).await,
+        ("POST", "/wallet/send") => handle_wallet_send(&state, body).await,
+        ("POST", "/submitblock") => handle_submit_block(&state, body).await,
+        ("POST", "/validate/block") => handle_validate_block(&state, body).await,
+        ("POST", "/validate/transaction") => handle_validate_transaction(&state, body).await,
+        ("POST", "/new_block") => handle_new_block(blockchain, state.monitor.clone(), &state.data_file, body).await,
+        _ => NOT_FOUND.to_string(),
     };
     
-    let http_response = format!(
-        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\n\r\n{}",
-        response.len(),
-        response
-    );
-    writer.write_all(http_response.as_bytes()).await?;
+    let status = if response == INVALID_JSON { 400 } else { 200 };
+    writer.write_all(http_response(status, &cors, &response).as_bytes()).await?;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mining::BlockEnergyStats;
+
+    #[tokio::test]
+    async fn test_balance_endpoint_confirmed_and_pending() {
+        use crate::blockchain::{Transaction, TxInput, TxOutput};
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::ConsensusParams;
+        
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        chain.mine_pending_transactions("AUR1Alice".to_string());
+        
+        let funding = chain.chain[1].transactions[0].clone();
+        let reward = funding.total_output();
+        let fee = 50_000;
+        let spend = Transaction::new(
+            vec![TxInput {
+                prev_tx_id: funding.id.clone(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![
+                TxOutput { value: 10_0000_0000, address: "AUR1Bob".to_string() },
+                TxOutput { value: reward - 10_0000_0000 - fee, address: "AUR1Alice".to_string() },
+            ],
+        );
+        chain.mempool.add_transaction(spend, fee).unwrap();
+        
+        let blockchain = Arc::new(RwLock::new(chain));
+        let alice: serde_json::Value =
+            serde_json::from_str(&handle_balance_request(blockchain.clone(), "/balance/AUR1Alice").await).unwrap();
+        assert_eq!(alice["confirmed"], reward);
+        assert_eq!(alice["utxo_count"], 1);
+        assert_eq!(alice["pending"], -(10_0000_0000 + fee as i64));
+        
+        let bob: serde_json::Value =
+            serde_json::from_str(&handle_balance_request(blockchain.clone(), "/balance/AUR1Bob").await).unwrap();
+        assert_eq!(bob["confirmed"], 0);
+        assert_eq!(bob["pending"], 10_0000_0000);
+        
+        // Une fois minée, la dépense devient confirmée
+        blockchain.write().await.mine_pending_transactions("AUR1Carol".to_string());
+        let alice: serde_json::Value =
+            serde_json::from_str(&handle_balance_request(blockchain.clone(), "/balance/AUR1Alice").await).unwrap();
+        assert_eq!(alice["confirmed"], reward - 10_0000_0000 - fee);
+        assert_eq!(alice["pending"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_nonce_endpoint_counts_confirmed_and_pending_sends() {
+        use crate::blockchain::{Transaction, TxInput, TxOutput};
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::{ConsensusParams, Network};
+        use crate::wallet::address::address_from_keypair;
+        use crate::wallet::keys::KeyPair;
+        
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        let alice_key = KeyPair::generate();
+        let alice_address = address_from_keypair(&alice_key, Network::Mainnet);
+        chain.mine_pending_transactions(alice_address.clone());
+        let fee = 100_000;
+        let send = |funding: &Transaction, output_index: usize, outputs: Vec<TxOutput>| {
+            let mut tx = Transaction::new(
+                vec![TxInput {
+                    prev_tx_id: funding.id.clone(),
+                    output_index,
+                    signature: String::new(),
+                    public_key: String::new(),
+                }],
+                outputs,
+            );
+            tx.sign_input(0, &alice_key).unwrap();
+            tx
+        };
+        
+        // Première dépense confirmée : Alice découpe sa récompense en trois
+        let reward = chain.chain[1].transactions[0].total_output();
+        let part = (reward - fee) / 3;
+        let split = send(
+            &chain.chain[1].transactions[0],
+            0,
+            (0..3).map(|_| TxOutput { value: part, address: alice_address.clone() }).collect(),
+        );
+        chain.accept_transaction(split.clone()).unwrap();
+        chain.mine_pending_transactions("AUR1Carol".to_string());
+        
+        // Deux dépenses en attente
+        for output_index in [1, 2] {
+            let tx = send(&split, output_index, vec![TxOutput { value: part - fee, address: "AUR1Bob".to_string() }]);
+            chain.accept_transaction(tx).unwrap();
+        }
+        
+        let blockchain = Arc::new(RwLock::new(chain));
+        let alice: serde_json::Value =
+            serde_json::from_str(&handle_nonce_request(blockchain.clone(), &format!("/nonce/{}", alice_address)).await).unwrap();
+        assert_eq!(alice["confirmed"], 1);
+        assert_eq!(alice["pending"], 2);
+        assert_eq!(alice["next_nonce"], 3);
+        
+        // Bob n'a fait que recevoir
+        let bob: serde_json::Value =
+            serde_json::from_str(&handle_nonce_request(blockchain.clone(), "/nonce/AUR1Bob").await).unwrap();
+        assert_eq!(bob["next_nonce"], 0);
+        assert!(handle_nonce_request(blockchain, "/nonce/").await.contains("error"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_transaction_surfaces_alert() {
+        use crate::blockchain::{Block, Transaction, TxInput, TxOutput};
+        
+        let mut chain = Blockchain::new();
+        chain.chain.push(crate::blockchain::create_genesis_block());
+        let genesis_hash = chain.chain[0].hash.clone();
+        let blockchain = Arc::new(RwLock::new(chain));
+        let monitor = Arc::new(Mutex::new(SecurityMonitor::new()));
+        
+        let outputs = (0..1001)
+            .map(|i| TxOutput { value: 1, address: format!("AUR1Spam{}", i) })
+            .collect();
+        let spam = Transaction::new(
+            vec![TxInput {
+                prev_tx_id: "aa".to_string(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            outputs,
+        );
+        let coinbase = Transaction::coinbase("AUR1Miner".to_string(), 1, 1_730_000_000);
+        let block = Block::new(1, vec![coinbase, spam], genesis_hash, 1, "AUR1Miner".to_string());
+        
+        let data_file = std::env::temp_dir().join(format!("aur_new_block_{}.json", std::process::id()));
+        handle_new_block(blockchain, monitor.clone(), data_file.to_str().unwrap(), &serde_json::to_string(&block).unwrap()).await;
+        assert!(!data_file.exists());
+        
+        let json: serde_json::Value =
+            serde_json::from_str(&get_alerts(monitor.clone(), "/alerts?level=warning&limit=10").await).unwrap();
+        let alerts = json["alerts"].as_array().unwrap();
+        assert!(alerts.iter().any(|a| a["message"] == "Transaction with many outputs" && a["level"] == "Warning"));
+        
+        let json: serde_json::Value =
+            serde_json::from_str(&get_alerts(monitor, "/alerts?level=critical").await).unwrap();
+        assert_eq!(json["count"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_energy_endpoint_reports_recorded_blocks() {
+        let energy = Arc::new(Mutex::new(EnergyTracker::new()));
+        
+        for i in 1..=2 {
+            let stats = energy.lock().unwrap().calculator.calculate_block_energy(500_000, 3.0);
+            energy.lock().unwrap().record_block(BlockEnergyStats { block_index: i, ..stats });
+        }
+        
+        let json: serde_json::Value = serde_json::from_str(&get_energy_stats(energy).await).unwrap();
+        
+        assert_eq!(json["total_blocks_mined"], 2);
+        assert!(json["total_energy_kwh"].as_f64().unwrap() > 0.0);
+        assert!(json["average_wh_per_block"].as_f64().unwrap() > 0.0);
+        
+        let reduction = json["reduction_vs_bitcoin_percent"].as_f64().unwrap();
+        assert!(reduction > 0.0 && reduction <= 100.0);
+        assert!(json["comparison"]["bitcoin_wh_per_tx"].as_f64().is_some());
+    }
+
+    async fn chain_of_three() -> Arc<RwLock<Blockchain>> {
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::ConsensusParams;
+        
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        chain.mine_pending_transactions("AUR1Alice".to_string());
+        chain.mine_pending_transactions("AUR1Bob".to_string());
+        Arc::new(RwLock::new(chain))
+    }
+
+    #[tokio::test]
+    async fn test_block_by_index() {
+        let blockchain = chain_of_three().await;
+        let json: serde_json::Value = serde_json::from_str(&get_block(blockchain.clone(), "1").await).unwrap();
+        assert_eq!(json["index"], 1);
+        assert_eq!(json["hash"], blockchain.read().await.chain[1].hash);
+    }
+
+    #[tokio::test]
+    async fn test_block_by_hash() {
+        let blockchain = chain_of_three().await;
+        let hash = blockchain.read().await.chain[2].hash.clone();
+        let json: serde_json::Value = serde_json::from_str(&get_block_by_hash(blockchain, &hash).await).unwrap();
+        assert_eq!(json["index"], 2);
+        assert_eq!(json["hash"], hash);
+    }
+
+    #[tokio::test]
+    async fn test_blocks_range() {
+        let blockchain = chain_of_three().await;
+        let json: serde_json::Value =
+            serde_json::from_str(&get_blocks_range(blockchain.clone(), "/blocks?start=1&end=5").await).unwrap();
+        let blocks = json.as_array().unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["index"], 1);
+        assert_eq!(blocks[1]["index"], 2);
+        
+        let invalid = get_blocks_range(blockchain, "/blocks?start=2&end=1").await;
+        assert!(invalid.contains("Invalid range"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_block_not_found() {
+        let blockchain = chain_of_three().await;
+        assert_eq!(get_block(blockchain.clone(), "3").await, NOT_FOUND);
+        assert_eq!(get_block(blockchain.clone(), "abc").await, NOT_FOUND);
+        assert_eq!(get_block_by_hash(blockchain.clone(), "deadbeef").await, NOT_FOUND);
+        assert_eq!(get_blocks_range(blockchain, "/blocks?start=10&end=20").await, NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_lookup_by_id() {
+        use crate::blockchain::{Transaction, TxInput, TxOutput};
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::{ConsensusParams, Network};
+        use crate::wallet::address::address_from_keypair;
+        use crate::wallet::keys::KeyPair;
+        
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        let alice = KeyPair::generate();
+        chain.mine_pending_transactions(address_from_keypair(&alice, Network::Mainnet));
+        
+        let funding = chain.chain[1].transactions[0].clone();
+        let mut spend = Transaction::new(
+            vec![TxInput {
+                prev_tx_id: funding.id.clone(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![TxOutput { value: funding.total_output() - 50_000, address: "AUR1Bob".to_string() }],
+        );
+        spend.sign_input(0, &alice).unwrap();
+        chain.accept_transaction(spend.clone()).unwrap();
+        chain.mine_pending_transactions("AUR1Carol".to_string());
+        chain.mine_pending_transactions("AUR1Dave".to_string());
+        
+        let blockchain = Arc::new(RwLock::new(chain));
+        let json: serde_json::Value =
+            serde_json::from_str(&get_transaction(blockchain.clone(), &spend.id).await).unwrap();
+        assert_eq!(json["txid"], spend.id);
+        assert_eq!(json["block_height"], 2);
+        assert_eq!(json["confirmations"], 2);
+        assert_eq!(json["transaction"]["outputs"][0]["address"], "AUR1Bob");
+        
+        assert_eq!(get_transaction(blockchain, "unknown").await, NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_block_stats_reports_totals_and_fees() {
+        use crate::blockchain::{Transaction, TxInput, TxOutput};
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::{ConsensusParams, Network};
+        use crate::wallet::address::address_from_keypair;
+        use crate::wallet::keys::KeyPair;
+
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        let alice = KeyPair::generate();
+        chain.mine_pending_transactions(address_from_keypair(&alice, Network::Mainnet));
+        chain.mine_pending_transactions(address_from_keypair(&alice, Network::Mainnet));
+
+        let subsidy = chain.chain[2].transactions[0].total_output();
+        let fees = [80_000, 60_000];
+        let mut sent = 0;
+        for (height, fee) in [1, 2].into_iter().zip(fees) {
+            let funding = chain.chain[height].transactions[0].clone();
+            let value = funding.total_output() - fee;
+            sent += value;
+            let mut spend = Transaction::new(
+                vec![TxInput {
+                    prev_tx_id: funding.id.clone(),
+                    output_index: 0,
+                    signature: String::new(),
+                    public_key: String::new(),
+                }],
+                vec![TxOutput { value, address: "AUR1Bob".to_string() }],
+            );
+            spend.sign_input(0, &alice).unwrap();
+            chain.accept_transaction(spend).unwrap();
+        }
+        chain.mine_pending_transactions("AUR1Carol".to_string());
+
+        let block = chain.chain[3].clone();
+        let blockchain = Arc::new(RwLock::new(chain));
+        let json: serde_json::Value =
+            serde_json::from_str(&get_block_stats(blockchain.clone(), "3").await).unwrap();
+        assert_eq!(json["tx_count"], 3);
+        assert_eq!(json["total_output"], sent);
+        assert_eq!(json["total_fees"], 140_000);
+        assert_eq!(json["coinbase_reward"], subsidy + 140_000);
+        assert_eq!(json["size"], bincode::serialize(&block).unwrap().len());
+        assert_eq!(json["nonce"], block.nonce);
+        assert_eq!(json["difficulty"], block.difficulty);
+
+        assert_eq!(get_block_stats(blockchain.clone(), "4").await, NOT_FOUND);
+        assert_eq!(get_block_stats(blockchain, "abc").await, NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rapid_requests_throttled_with_429() {
+        let blockchain = Arc::new(RwLock::new(Blockchain::new()));
+        let state = RpcState::new(
+            blockchain,
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        );
+        state.request_limiter.lock().unwrap().max_messages_per_minute = 3;
+        
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                handle_connection(stream, server_state.clone()).await.unwrap();
+            }
+        });
+        
+        let mut statuses = Vec::new();
+        for _ in 0..5 {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream.write_all(b"GET /chain_info HTTP/1.1\r\n\r\n").await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            statuses.push(response.split_whitespace().nth(1).unwrap().to_string());
+        }
+        
+        assert_eq!(statuses, vec!["200", "200", "200", "429", "429"]);
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+        assert_eq!(state.request_limiter.lock().unwrap().violation_count(ip), 2);
+    }
+    
+    /// Envoyer `request` à un serveur RPC servant `state` et retourner la réponse brute
+    async fn raw_request(state: RpcState, request: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            handle_connection(stream, state).await.unwrap();
+        });
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_status_codes_and_cors_policy() {
+        let mut state = RpcState::new(
+            Arc::new(RwLock::new(Blockchain::new())),
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        );
+
+        let response = raw_request(state.clone(), "GET /nowhere HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"), "{}", response);
+
+        let response = raw_request(state.clone(), "POST /chain_info HTTP/1.1\r\nContent-Length: 0\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"), "{}", response);
+        assert!(response.contains("Allow: GET\r\n"), "{}", response);
+
+        let body = "{not json";
+        let request = format!("POST /transaction HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        let response = raw_request(state.clone(), &request).await;
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"), "{}", response);
+        assert!(response.contains("Access-Control-Allow-Origin: *\r\n"), "{}", response);
+
+        state.read_only = true;
+        let response = raw_request(state.clone(), &request).await;
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden\r\n"), "{}", response);
+        let response = raw_request(state.clone(), "GET /status HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+
+        state.cors = CorsPolicy::Origins(vec!["https://explorer.example".to_string()]);
+        let allowed = raw_request(state.clone(), "GET /status HTTP/1.1\r\nOrigin: https://explorer.example\r\n\r\n").await;
+        assert!(allowed.contains("Access-Control-Allow-Origin: https://explorer.example\r\n"), "{}", allowed);
+        let other = raw_request(state.clone(), "GET /status HTTP/1.1\r\nOrigin: https://evil.example\r\n\r\n").await;
+        assert!(!other.contains("Access-Control-Allow-Origin"), "{}", other);
+
+        state.cors = CorsPolicy::Disabled;
+        let response = raw_request(state, "GET /status HTTP/1.1\r\n\r\n").await;
+        assert!(!response.contains("Access-Control"), "{}", response);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_scraped_in_prometheus_format() {
+        let blockchain = chain_of_three().await;
+        let mut state = RpcState::new(
+            blockchain.clone(),
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        );
+        let peer_manager = Arc::new(PeerManager::new(10, 10));
+        peer_manager.add_peer("10.0.0.1:3001".parse().unwrap(), crate::p2p::PeerDirection::Outbound).await;
+        state.peer_manager = Some(peer_manager);
+        state.metrics.record_block_mined();
+        state.metrics.record_sync_error();
+        state.metrics.record_sync_error();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                handle_connection(stream, server_state.clone()).await.unwrap();
+            }
+        });
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.contains("Content-Type: text/plain"), "{}", response);
+
+        let body = response.split_once("\r\n\r\n").unwrap().1;
+        let value = |name: &str| body.lines()
+            .find_map(|line| line.strip_prefix(name).and_then(|rest| rest.strip_prefix(' ')))
+            .unwrap_or_else(|| panic!("missing metric {}", name))
+            .to_string();
+        let height = blockchain.read().await.chain.len() - 1;
+        assert_eq!(value("auriumchain_chain_height"), height.to_string());
+        assert_eq!(value("auriumchain_mempool_size"), "0");
+        assert_eq!(value("auriumchain_peer_count"), "1");
+        assert_eq!(value("auriumchain_blocks_mined_total"), "1");
+        assert_eq!(value("auriumchain_energy_wh_total"), "0");
+        assert_eq!(value("auriumchain_banned_ips"), "0");
+        assert_eq!(value("auriumchain_sync_errors_total"), "2");
+        assert!(body.contains("# TYPE auriumchain_sync_errors_total counter"));
+    }
+
+    #[tokio::test]
+    async fn test_blocks_streamed_in_chunks() {
+        use crate::blockchain::Block;
+        
+        let mut chain = Blockchain::new();
+        for index in 0..(BLOCKS_PER_CHUNK as u64 * 3 + 7) {
+            chain.chain.push(Block::new(index, Vec::new(), "0".repeat(64), 1, format!("AUR1Miner{}", index)));
+        }
+        let blockchain = Arc::new(RwLock::new(chain));
+        
+        let mut output = Vec::new();
+        stream_all_blocks(&mut output, blockchain, "").await.unwrap();
+        let response = String::from_utf8(output).unwrap();
+        let (head, mut rest) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.contains("Transfer-Encoding: chunked"));
+        assert!(!head.contains("Content-Length"));
+        
+        // Décodage du corps chunked
+        let mut body = String::new();
+        let mut chunks = 0;
+        loop {
+            let (size, after) = rest.split_once("\r\n").unwrap();
+            let size = usize::from_str_radix(size, 16).unwrap();
+            if size == 0 {
+                break;
+            }
+            body.push_str(&after[..size]);
+            rest = &after[size + 2..];
+            chunks += 1;
+        }
+        assert_eq!(chunks, 4);
+        
+        let blocks: Vec<Block> = serde_json::from_str(&body).unwrap();
+        assert_eq!(blocks.len(), BLOCKS_PER_CHUNK * 3 + 7);
+        assert!(blocks.iter().enumerate().all(|(i, block)| block.index == i as u64));
+        
+        // Chaîne vide : un tableau vide
+        let mut output = Vec::new();
+        stream_all_blocks(&mut output, Arc::new(RwLock::new(Blockchain::new())), "").await.unwrap();
+        assert!(String::from_utf8(output).unwrap().ends_with("\r\n2\r\n[]\r\n0\r\n\r\n"));
+    }
+    
+    #[tokio::test]
+    async fn test_submitblock_accepts_mined_template_and_rejects_invalid() {
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::ConsensusParams;
+        
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        let blockchain = Arc::new(RwLock::new(chain));
+        let mut state = RpcState::new(
+            blockchain.clone(),
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        );
+        state.data_file = std::env::temp_dir()
+            .join(format!("aur_submitblock_{}.json", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        
+        assert!(get_block_template(blockchain.clone(), "/getblocktemplate").await.contains("missing_address"));
+        let miner = crate::wallet::address::generate_address(b"external", crate::utils::config::Network::Mainnet);
+        let template: serde_json::Value = serde_json::from_str(
+            &get_block_template(blockchain.clone(), &format!("/getblocktemplate?address={}", miner)).await
+        ).unwrap();
+        assert_eq!(template["index"], 1);
+        assert_eq!(template["previous_hash"], blockchain.read().await.chain[0].hash);
+        assert_eq!(template["transactions"][0]["outputs"][0]["address"], miner);
+        
+        // Un mineur externe trouve le nonce du modèle
+        let mut block = blockchain.read().await.block_template(miner);
+        block.mine();
+        
+        let mut invalid = block.clone();
+        invalid.transactions[0].outputs[0].value += 1;
+        invalid.transactions[0] = invalid.transactions[0].clone().with_locktime(0);
+        let reply: serde_json::Value = serde_json::from_str(&handle_submit_block(&state, &serde_json::to_string(&invalid).unwrap()).await).unwrap();
+        assert_eq!(reply["status"], "rejected");
+        assert!(reply["reason"].as_str().unwrap().contains("Merkle"), "{}", reply);
+        assert_eq!(blockchain.read().await.chain.len(), 1);
+        
+        // Les règles de consensus renvoient leur propre raison
+        let mut future = block.clone();
+        future.timestamp += 3 * 3600;
+        future.mine();
+        let reply: serde_json::Value = serde_json::from_str(&handle_submit_block(&state, &serde_json::to_string(&future).unwrap()).await).unwrap();
+        assert!(reply["reason"].as_str().unwrap().contains("too far in future"), "{}", reply);
+        
+        let reply: serde_json::Value = serde_json::from_str(&handle_submit_block(&state, &serde_json::to_string(&block).unwrap()).await).unwrap();
+        assert_eq!(reply["status"], "accepted");
+        assert_eq!(reply["hash"], block.hash);
+        assert_eq!(blockchain.read().await.chain.len(), 2);
+        
+        // Le même bloc ne prolonge plus le sommet
+        let reply: serde_json::Value = serde_json::from_str(&handle_submit_block(&state, &serde_json::to_string(&block).unwrap()).await).unwrap();
+        assert_eq!(reply["status"], "rejected");
+        
+        let saved = Blockchain::load_from_file_with_params(&state.data_file, params).unwrap();
+        assert_eq!(saved.chain.len(), 2);
+        let _ = std::fs::remove_file(&state.data_file);
+    }
+
+    #[tokio::test]
+    async fn test_block_subscription_receives_new_tip() {
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::ConsensusParams;
+
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        let blockchain = Arc::new(RwLock::new(chain));
+
+        let (server_side, mut client_side) = tokio::io::duplex(4096);
+        let subscription = tokio::spawn({
+            let blockchain = blockchain.clone();
+            async move {
+                let mut server_side = server_side;
+                stream_tip_events(&mut server_side, blockchain, "").await.is_err()
+            }
+        });
+
+        let mut received = Vec::new();
+        let mut chunk = [0u8; 1024];
+        while !String::from_utf8_lossy(&received).contains("\r\n\r\n") {
+            let n = client_side.read(&mut chunk).await.unwrap();
+            received.extend_from_slice(&chunk[..n]);
+        }
+        assert!(String::from_utf8_lossy(&received).contains("text/event-stream"));
+        assert_eq!(blockchain.read().await.tip_subscribers(), 1);
+
+        blockchain.write().await.mine_pending_transactions("AUR1Miner".to_string());
+        let tip = blockchain.read().await.get_latest_block().unwrap().hash.clone();
+        received.clear();
+        while !String::from_utf8_lossy(&received).contains("\n\n") {
+            let n = client_side.read(&mut chunk).await.unwrap();
+            received.extend_from_slice(&chunk[..n]);
+        }
+        let event = String::from_utf8_lossy(&received).to_string();
+        let data: serde_json::Value = serde_json::from_str(event.trim().trim_start_matches("data: ")).unwrap();
+        assert_eq!(data["index"], 1);
+        assert_eq!(data["hash"], tip);
+
+        // Client parti : l'écriture suivante échoue et l'abonnement est libéré
+        drop(client_side);
+        blockchain.write().await.mine_pending_transactions("AUR1Miner".to_string());
+        assert!(subscription.await.unwrap());
+        assert_eq!(blockchain.read().await.tip_subscribers(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wallet_send_requires_unlock() {
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::{ConsensusParams, Network};
+        use crate::wallet::{KdfParams, SecureWallet};
+        use crate::wallet::address::generate_address;
+
+        let fast = KdfParams { memory_kib: 1024, iterations: 1, parallelism: 1 };
+        let mnemonic = bip39::Mnemonic::from_entropy(&[5u8; 32]).unwrap();
+        let wallet = SecureWallet::from_mnemonic("node".to_string(), "pass", "AUR1", &mnemonic, fast).unwrap();
+
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        chain.mine_pending_transactions(wallet.address.clone());
+        let blockchain = Arc::new(RwLock::new(chain));
+        let mut state = RpcState::new(
+            blockchain.clone(),
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        );
+        let send = serde_json::json!({ "to": generate_address(b"payee", Network::Mainnet), "amount": 1_0000_0000 }).to_string();
+
+        // Désactivé tant que le nœud n'a pas de wallet configuré
+        assert!(handle_wallet_send(&state, &send).await.contains("wallet_disabled"));
+
+        state.wallet = Some(Arc::new(NodeWallet::new(wallet)));
+        assert!(handle_wallet_send(&state, &send).await.contains("wallet_locked"));
+        assert!(handle_wallet_unlock(&state, r#"{"password":"wrong"}"#).await.contains("wrong_password"));
+        assert!(handle_wallet_send(&state, &send).await.contains("wallet_locked"));
+        assert!(blockchain.read().await.mempool.is_empty());
+
+        assert!(handle_wallet_unlock(&state, r#"{"password":"pass"}"#).await.contains("unlocked"));
+        let reply: serde_json::Value = serde_json::from_str(&handle_wallet_send(&state, &send).await).unwrap();
+        assert_eq!(reply["status"], "accepted", "{}", reply);
+
+        let chain = blockchain.read().await;
+        let tx = &chain.mempool.entries().iter()
+            .find(|entry| entry.tx.id == reply["txid"])
+            .unwrap()
+            .tx;
+        assert_eq!(tx.outputs[0].value, 1_0000_0000);
+        assert!(crate::security::SignatureValidator::verify_transaction(tx).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_endpoints_do_not_mutate_state() {
+        use crate::blockchain::{Transaction, TxInput, TxOutput};
+        use crate::blockchain::genesis::create_genesis_block_with_params;
+        use crate::utils::config::{ConsensusParams, Network};
+        use crate::wallet::address::address_from_keypair;
+        use crate::wallet::keys::KeyPair;
+
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        let keypair = KeyPair::generate();
+        let miner = address_from_keypair(&keypair, Network::Mainnet);
+        chain.mine_pending_transactions(miner.clone());
+        let blockchain = Arc::new(RwLock::new(chain));
+        let state = RpcState::new(
+            blockchain.clone(),
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        );
+
+        let funding = blockchain.read().await.chain[1].transactions[0].clone();
+        let mut spend = Transaction::new(
+            vec![TxInput {
+                prev_tx_id: funding.id.clone(),
+                output_index: 0,
+                signature: String::new(),
+                public_key: String::new(),
+            }],
+            vec![TxOutput { value: funding.total_output() - 50_000, address: miner.clone() }],
+        );
+        spend.sign_input(0, &keypair).unwrap();
+
+        let reply = handle_validate_transaction(&state, &serde_json::to_string(&spend).unwrap()).await;
+        assert_eq!(reply, r#"{"valid":true}"#);
+        let mut tampered = spend.clone();
+        tampered.outputs[0].value -= 1;
+        let reply: serde_json::Value =
+            serde_json::from_str(&handle_validate_transaction(&state, &serde_json::to_string(&tampered).unwrap()).await).unwrap();
+        assert_eq!(reply["valid"], false);
+        assert!(reply["reason"].as_str().unwrap().contains("signature"), "{}", reply);
+        assert!(blockchain.read().await.mempool.is_empty());
+
+        let mut block = blockchain.read().await.block_template(miner);
+        block.mine();
+        let reply = handle_validate_block(&state, &serde_json::to_string(&block).unwrap()).await;
+        assert_eq!(reply, r#"{"valid":true}"#);
+        let mut invalid = block.clone();
+        invalid.transactions[0].outputs[0].value += 1;
+        invalid.transactions[0] = invalid.transactions[0].clone().with_locktime(0);
+        let reply: serde_json::Value =
+            serde_json::from_str(&handle_validate_block(&state, &serde_json::to_string(&invalid).unwrap()).await).unwrap();
+        assert_eq!(reply["valid"], false);
+        assert!(reply["reason"].as_str().unwrap().contains("Merkle"), "{}", reply);
+        assert_eq!(blockchain.read().await.chain.len(), 2);
+
+        assert!(handle_validate_block(&state, "not json").await.contains("invalid_json"));
+    }
+    
+    #[tokio::test]
+    async fn test_rpc_server_binds_to_loopback() {
+        let state = RpcState::new(
+            Arc::new(RwLock::new(Blockchain::new())),
+            Arc::new(Mutex::new(EnergyTracker::new())),
+            Arc::new(Mutex::new(SecurityMonitor::new())),
+        );
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let bind_addr = SocketAddr::from(([127, 0, 0, 1], port));
+        tokio::spawn(async move {
+            let _ = start_rpc_server(state, bind_addr).await;
+        });
+
+        let mut stream = None;
+        for _ in 0..50 {
+            if let Ok(connected) = tokio::net::TcpStream::connect(bind_addr).await {
+                stream = Some(connected);
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        let mut stream = stream.expect("RPC server not listening on loopback");
+        assert!(stream.peer_addr().unwrap().ip().is_loopback());
+
+        stream.write_all(b"GET /status HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        assert!(String::from_utf8_lossy(&response).contains(r#""status":"running""#));
+    }
+
+    #[tokio::test]
+    async fn test_chain_info_reports_hashrate_and_block_time() {
+        use crate::blockchain::Block;
+        
+        let mut chain = Blockchain::new();
+        for index in 0..20u64 {
+            let mut block = Block::new(index, Vec::new(), "0".repeat(64), 2, format!("AUR1Miner{}", index));
+            block.timestamp = 1_730_000_000 + index as i64 * 30;
+            chain.chain.push(block);
+        }
+        let blockchain = Arc::new(RwLock::new(chain));
+        
+        let info: serde_json::Value = serde_json::from_str(&get_chain_info(blockchain.clone()).await).unwrap();
+        assert_eq!(info["height"], 20);
+        assert_eq!(info["mempool_size"], 0);
+        assert_eq!(info["utxo_set_hash"], blockchain.read().await.utxo_set_hash());
+        assert_eq!(info["average_block_time"].as_f64().unwrap(), 30.0);
+        
+        // Deux zéros hexadécimaux : 256 hashs attendus par bloc, toutes les 30 s
+        let hashrate = info["estimated_hashrate"].as_f64().unwrap();
+        assert!(hashrate.is_finite() && hashrate > 0.0);
+        assert!((hashrate - 256.0 / 30.0).abs() < 1e-9, "{}", hashrate);
+        
+        let empty: serde_json::Value = serde_json::from_str(&get_chain_info(Arc::new(RwLock::new(Blockchain::new()))).await).unwrap();
+        assert_eq!(empty["estimated_hashrate"].as_f64().unwrap(), 0.0);
+    }
+    
+    #[tokio::test]
+    async fn test_request_body_larger_than_one_read() {
+        let body = serde_json::json!({ "padding": "x".repeat(20_000), "index": 7 }).to_string();
+        let request = format!(
+            "POST /new_block HTTP/1.1\r\nHost: localhost\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        let sender = tokio::spawn(async move {
+            // Écrit en plusieurs morceaux, sans fermer la connexion
+            for part in request.as_bytes().chunks(3000) {
+                client.write_all(part).await.unwrap();
+                tokio::task::yield_now().await;
+            }
+            client
+        });
+        
+        let received = read_http_request(&mut server, 5 * 1024 * 1024).await.unwrap();
+        let client = sender.await.unwrap();
+        let received = String::from_utf8(received).unwrap();
+        let (_, parsed_body) = received.split_once("\r\n\r\n").unwrap();
+        assert_eq!(parsed_body.len(), body.len());
+        let value: serde_json::Value = serde_json::from_str(parsed_body).unwrap();
+        assert_eq!(value["index"], 7);
+        drop(client);
+        
+        // Au-delà de la taille maximale, la lecture s'arrête
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+        client.write_all(format!("POST /rpc HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body).as_bytes()).await.unwrap();
+        let truncated = read_http_request(&mut server, 10_000).await.unwrap();
+        assert!(truncated.len() > 10_000 && truncated.len() < body.len());
+    }
+}
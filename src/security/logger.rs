@@ -0,0 +1,282 @@
+use chrono::Utc;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use crate::security::monitor::AlertLevel;
+
+/// Types d'événements de sécurité journalisés par le nœud
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityEventType {
+    NodeStarted,
+    NodeStopped,
+    ConnectionRefused,
+    OversizedMessage,
+    PeerDisconnected,
+    RateLimitExceeded,
+    SuspiciousActivity,
+}
+
+impl SecurityEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityEventType::NodeStarted => "NodeStarted",
+            SecurityEventType::NodeStopped => "NodeStopped",
+            SecurityEventType::ConnectionRefused => "ConnectionRefused",
+            SecurityEventType::OversizedMessage => "OversizedMessage",
+            SecurityEventType::PeerDisconnected => "PeerDisconnected",
+            SecurityEventType::RateLimitExceeded => "RateLimitExceeded",
+            SecurityEventType::SuspiciousActivity => "SuspiciousActivity",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "NodeStarted" => Some(SecurityEventType::NodeStarted),
+            "NodeStopped" => Some(SecurityEventType::NodeStopped),
+            "ConnectionRefused" => Some(SecurityEventType::ConnectionRefused),
+            "OversizedMessage" => Some(SecurityEventType::OversizedMessage),
+            "PeerDisconnected" => Some(SecurityEventType::PeerDisconnected),
+            "RateLimitExceeded" => Some(SecurityEventType::RateLimitExceeded),
+            "SuspiciousActivity" => Some(SecurityEventType::SuspiciousActivity),
+            _ => None,
+        }
+    }
+
+    /// Gravité associée au type d'événement
+    pub fn severity(&self) -> AlertLevel {
+        match self {
+            SecurityEventType::NodeStarted
+            | SecurityEventType::NodeStopped
+            | SecurityEventType::PeerDisconnected => AlertLevel::Info,
+            SecurityEventType::ConnectionRefused
+            | SecurityEventType::RateLimitExceeded => AlertLevel::Warning,
+            SecurityEventType::OversizedMessage
+            | SecurityEventType::SuspiciousActivity => AlertLevel::Critical,
+        }
+    }
+}
+
+/// Événement relu depuis le journal
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityEvent {
+    pub timestamp: i64,
+    pub event_type: SecurityEventType,
+    pub source: String,
+    pub details: String,
+}
+
+impl SecurityEvent {
+    /// Parser une ligne `timestamp|type|source|details` ; `None` si elle est mal formée
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(4, '|');
+        let timestamp = fields.next()?.parse().ok()?;
+        let event_type = SecurityEventType::parse(fields.next()?)?;
+        let source = fields.next()?.to_string();
+        let details = fields.next()?.to_string();
+
+        Some(SecurityEvent { timestamp, event_type, source, details })
+    }
+
+    pub fn severity(&self) -> AlertLevel {
+        self.event_type.severity()
+    }
+
+    /// IP du peer à l'origine de l'événement, si la source est une adresse
+    pub fn peer_ip(&self) -> Option<IpAddr> {
+        self.source.parse::<SocketAddr>().map(|addr| addr.ip())
+            .or_else(|_| self.source.parse::<IpAddr>())
+            .ok()
+    }
+}
+
+/// Critères de `SecurityLogger::query` ; un champ `None` ne filtre pas
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub severity: Option<AlertLevel>,
+    pub event_type: Option<SecurityEventType>,
+    /// Bornes incluses, en secondes Unix
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub peer_ip: Option<IpAddr>,
+    /// Lire aussi les archives `<fichier>.1`, `<fichier>.2`, ... (de la plus ancienne à la plus récente)
+    pub include_archives: bool,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &SecurityEvent) -> bool {
+        self.severity.as_ref().is_none_or(|level| event.severity() == *level)
+            && self.event_type.is_none_or(|event_type| event.event_type == event_type)
+            && self.since.is_none_or(|since| event.timestamp >= since)
+            && self.until.is_none_or(|until| event.timestamp <= until)
+            && self.peer_ip.is_none_or(|ip| event.peer_ip() == Some(ip))
+    }
+}
+
+/// Journal des événements de sécurité.
+///
+/// Chaque événement produit une ligne `timestamp|type|source|details`,
+/// envoyée au logger (`log`) et ajoutée au fichier si un chemin est configuré.
+pub struct SecurityLogger {
+    path: Option<PathBuf>,
+}
+
+impl SecurityLogger {
+    /// Logger sans fichier (sortie `log` uniquement)
+    pub fn new() -> Self {
+        SecurityLogger { path: None }
+    }
+
+    /// Logger qui ajoute aussi chaque ligne au fichier donné
+    pub fn with_file(path: impl Into<PathBuf>) -> Self {
+        SecurityLogger { path: Some(path.into()) }
+    }
+
+    /// Formater une ligne d'événement
+    pub fn format_event(timestamp: i64, event_type: SecurityEventType, source: &str, details: &str) -> String {
+        format!("{}|{}|{}|{}", timestamp, event_type.as_str(), source, details.replace('\n', " "))
+    }
+
+    /// Enregistrer un événement ; retourne la ligne écrite
+    pub fn log_event(&self, event_type: SecurityEventType, source: &str, details: &str) -> String {
+        let line = Self::format_event(Utc::now().timestamp(), event_type, source, details);
+        log::info!("🛡️  {}", line);
+
+        if let Some(path) = &self.path {
+            let written = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", line));
+            if let Err(e) = written {
+                log::error!("Failed to write security log {}: {}", path.display(), e);
+            }
+        }
+
+        line
+    }
+
+    /// Relire les événements du fichier correspondant au filtre, dans l'ordre chronologique.
+    /// Les lignes illisibles sont ignorées ; sans fichier configuré, rien n'est retourné.
+    pub fn query(&self, filter: &EventFilter) -> Vec<SecurityEvent> {
+        let Some(path) = &self.path else {
+            return Vec::new();
+        };
+
+        let mut files = Vec::new();
+        if filter.include_archives {
+            let mut archives: Vec<PathBuf> = (1..)
+                .map(|n| PathBuf::from(format!("{}.{}", path.display(), n)))
+                .take_while(|archive| archive.exists())
+                .collect();
+            archives.reverse();
+            files.extend(archives);
+        }
+        files.push(path.clone());
+
+        files.iter()
+            .filter_map(|file| Self::read_log(file))
+            .flat_map(|content| content.lines().filter_map(SecurityEvent::parse).collect::<Vec<_>>())
+            .filter(|event| filter.matches(event))
+            .collect()
+    }
+
+    fn read_log(path: &Path) -> Option<String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Some(content),
+            Err(e) => {
+                if path.exists() {
+                    log::error!("Failed to read security log {}: {}", path.display(), e);
+                }
+                None
+            }
+        }
+    }
+}
+
+impl Default for SecurityLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_event() {
+        let line = SecurityLogger::format_event(
+            1_730_000_000,
+            SecurityEventType::NodeStopped,
+            "node",
+            "clean shutdown\n12 blocks",
+        );
+        assert_eq!(line, "1730000000|NodeStopped|node|clean shutdown 12 blocks");
+    }
+
+    #[test]
+    fn test_log_event_appends_to_file() {
+        let path = std::env::temp_dir().join(format!("aur_security_{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let logger = SecurityLogger::with_file(&path);
+        logger.log_event(SecurityEventType::NodeStarted, "node", "port 3001");
+        logger.log_event(SecurityEventType::NodeStopped, "node", "clean shutdown");
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("|NodeStopped|node|clean shutdown"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_query_filters_events() {
+        let path = std::env::temp_dir().join(format!("aur_security_query_{}.log", std::process::id()));
+        let archive = PathBuf::from(format!("{}.1", path.display()));
+        let _ = std::fs::remove_file(&path);
+        std::fs::write(
+            &archive,
+            SecurityLogger::format_event(1_000, SecurityEventType::OversizedMessage, "10.0.0.9:3001", "old") + "\n",
+        ).unwrap();
+
+        let logger = SecurityLogger::with_file(&path);
+        logger.log_event(SecurityEventType::NodeStarted, "node", "port 3001");
+        logger.log_event(SecurityEventType::ConnectionRefused, "10.0.0.7:4000", "too many peers");
+        logger.log_event(SecurityEventType::OversizedMessage, "10.0.0.7:4000", "6 MB frame");
+        logger.log_event(SecurityEventType::OversizedMessage, "10.0.0.8:4000", "7 MB frame");
+
+        assert_eq!(logger.query(&EventFilter::default()).len(), 4);
+
+        let critical = logger.query(&EventFilter { severity: Some(AlertLevel::Critical), ..EventFilter::default() });
+        assert_eq!(critical.len(), 2);
+        assert!(critical.iter().all(|event| event.event_type == SecurityEventType::OversizedMessage));
+
+        let from_peer = logger.query(&EventFilter {
+            peer_ip: Some("10.0.0.7".parse().unwrap()),
+            ..EventFilter::default()
+        });
+        assert_eq!(from_peer.len(), 2);
+
+        let warnings = logger.query(&EventFilter {
+            severity: Some(AlertLevel::Warning),
+            event_type: Some(SecurityEventType::ConnectionRefused),
+            ..EventFilter::default()
+        });
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].details, "too many peers");
+
+        let with_archives = logger.query(&EventFilter {
+            until: Some(2_000),
+            include_archives: true,
+            ..EventFilter::default()
+        });
+        assert_eq!(with_archives.len(), 1);
+        assert_eq!(with_archives[0].details, "old");
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&archive).unwrap();
+    }
+}
@@ -1,12 +1,16 @@
 use crate::blockchain::{Block, Transaction};
+use crate::blockchain::genesis::MAX_REORG_DEPTH;
 use chrono::Utc;
+use serde::Serialize;
 
 pub struct SecurityMonitor {
     alerts: Vec<SecurityAlert>,
     max_alerts: usize,
+    /// Recul de hauteur au-delà duquel un changement de chaîne est signalé
+    pub max_reorg_depth: u64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SecurityAlert {
     pub timestamp: i64,
     pub level: AlertLevel,
@@ -14,18 +18,31 @@ pub struct SecurityAlert {
     pub details: String,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum AlertLevel {
     Info,
     Warning,
     Critical,
 }
 
+impl AlertLevel {
+    /// Parser un niveau (insensible à la casse) : "info", "warning", "critical"
+    pub fn parse(level: &str) -> Option<Self> {
+        match level.to_ascii_lowercase().as_str() {
+            "info" => Some(AlertLevel::Info),
+            "warning" => Some(AlertLevel::Warning),
+            "critical" => Some(AlertLevel::Critical),
+            _ => None,
+        }
+    }
+}
+
 impl SecurityMonitor {
     pub fn new() -> Self {
         SecurityMonitor {
             alerts: Vec::new(),
             max_alerts: 1000,
+            max_reorg_depth: MAX_REORG_DEPTH,
         }
     }
 
@@ -61,9 +78,17 @@ impl SecurityMonitor {
         }
     }
 
+    /// Contrôler un bloc reçu ou miné ainsi que toutes ses transactions
+    pub fn process_block(&mut self, block: &Block) {
+        self.check_block(block);
+        for tx in &block.transactions {
+            self.check_transaction(tx);
+        }
+    }
+
     pub fn check_transaction(&mut self, tx: &Transaction) {
         // 1. Transaction avec montant énorme
-        let total_output: u64 = tx.outputs.iter().map(|o| o.value).sum();
+        let total_output = tx.total_output();
         if total_output > 100_000_00000000 {
             self.add_alert(
                 AlertLevel::Warning,
@@ -92,7 +117,7 @@ impl SecurityMonitor {
     }
 
     pub fn detect_51_attack(&mut self, old_height: u64, new_height: u64) {
-        if old_height > new_height + 6 {
+        if old_height > new_height.saturating_add(self.max_reorg_depth) {
             self.add_alert(
                 AlertLevel::Critical,
                 "Potential 51% attack detected",
@@ -146,3 +171,9 @@ impl SecurityMonitor {
         self.alerts.clear();
     }
 }
+
+impl Default for SecurityMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
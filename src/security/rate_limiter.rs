@@ -0,0 +1,270 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use crate::p2p::messages::P2PMessage;
+
+/// Ban persisté : fin du ban en secondes Unix (un `Instant` ne survit pas au redémarrage)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BanRecord {
+    ip: IpAddr,
+    banned_until: i64,
+}
+
+/// Contenu du fichier de bans
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BanList {
+    bans: Vec<BanRecord>,
+    #[serde(default)]
+    violations: HashMap<IpAddr, u32>,
+}
+
+/// Limitation de débit par IP pour les connexions P2P
+pub struct RateLimiter {
+    pub max_message_size_bytes: usize,
+    pub max_messages_per_minute: usize,
+    pub max_blocks_per_minute: usize,
+    /// `RequestBlocks` / `RequestHeaders` : chaque requête fait lire et sérialiser des centaines de blocs
+    pub max_requests_per_minute: usize,
+    pub ban_duration: Duration,
+    message_history: HashMap<IpAddr, Vec<Instant>>,
+    block_history: HashMap<IpAddr, Vec<Instant>>,
+    variant_history: HashMap<(IpAddr, &'static str), Vec<Instant>>,
+    banned: HashMap<IpAddr, Instant>,
+    violations: HashMap<IpAddr, u32>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        RateLimiter {
+            max_message_size_bytes: 5 * 1024 * 1024, // bloc max (4 MB) + enveloppe
+            max_messages_per_minute: 600,
+            max_blocks_per_minute: 30,
+            max_requests_per_minute: 20,
+            ban_duration: Duration::from_secs(3600),
+            message_history: HashMap::new(),
+            block_history: HashMap::new(),
+            variant_history: HashMap::new(),
+            banned: HashMap::new(),
+            violations: HashMap::new(),
+        }
+    }
+
+    /// Vérifier qu'un message de `size` octets peut être accepté de cette IP
+    pub fn allow_message(&mut self, ip: IpAddr, size: usize) -> bool {
+        if self.is_banned(ip) || size > self.max_message_size_bytes {
+            return false;
+        }
+
+        let limit = self.max_messages_per_minute;
+        Self::record_in_window(&mut self.message_history, ip, limit)
+    }
+
+    /// Vérifier qu'un bloc de plus peut être accepté de cette IP
+    pub fn allow_block(&mut self, ip: IpAddr) -> bool {
+        if self.is_banned(ip) {
+            return false;
+        }
+
+        let limit = self.max_blocks_per_minute;
+        Self::record_in_window(&mut self.block_history, ip, limit)
+    }
+
+    /// Vérifier un message décodé selon son type : les requêtes coûteuses
+    /// (`RequestBlocks`, `RequestHeaders`) ont leur propre limite, bien plus basse
+    /// que celle des messages légers comme `Ping` / `Pong`. `NewBlock` est compté
+    /// par `allow_block` au moment de son traitement.
+    pub fn allow_message_variant(&mut self, ip: IpAddr, message: &P2PMessage) -> bool {
+        if self.is_banned(ip) {
+            return false;
+        }
+
+        let (variant, limit) = match message {
+            P2PMessage::RequestBlocks { .. } => ("RequestBlocks", self.max_requests_per_minute),
+            P2PMessage::RequestHeaders { .. } => ("RequestHeaders", self.max_requests_per_minute),
+            _ => ("other", self.max_messages_per_minute),
+        };
+        Self::record_in_window(&mut self.variant_history, (ip, variant), limit)
+    }
+
+    pub fn ban_ip(&mut self, ip: IpAddr) {
+        log::warn!("🚫 IP {} banned for {}s", ip, self.ban_duration.as_secs());
+        self.banned.insert(ip, Instant::now() + self.ban_duration);
+    }
+
+    pub fn is_banned(&mut self, ip: IpAddr) -> bool {
+        match self.banned.get(&ip) {
+            Some(until) if *until > Instant::now() => true,
+            Some(_) => {
+                self.banned.remove(&ip);
+                false
+            }
+            None => false,
+        }
+    }
+
+    pub fn get_banned_ips(&self) -> Vec<IpAddr> {
+        let now = Instant::now();
+        self.banned.iter()
+            .filter(|(_, until)| **until > now)
+            .map(|(ip, _)| *ip)
+            .collect()
+    }
+
+    /// Compter une infraction de cette IP ; retourne le total accumulé
+    pub fn record_violation(&mut self, ip: IpAddr) -> u32 {
+        let count = self.violations.entry(ip).or_insert(0);
+        *count += 1;
+        *count
+    }
+
+    pub fn violation_count(&self, ip: IpAddr) -> u32 {
+        self.violations.get(&ip).copied().unwrap_or(0)
+    }
+
+    /// Sauvegarder les bans actifs et les compteurs d'infractions (JSON)
+    pub fn save_bans(&self, path: &str) -> Result<()> {
+        let now = Instant::now();
+        let now_unix = Utc::now().timestamp();
+        let bans = self.banned.iter()
+            .filter(|(_, until)| **until > now)
+            .map(|(ip, until)| BanRecord {
+                ip: *ip,
+                banned_until: now_unix + until.duration_since(now).as_secs() as i64,
+            })
+            .collect();
+        let list = BanList { bans, violations: self.violations.clone() };
+
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&list)?)?;
+        Ok(())
+    }
+
+    /// Recharger les bans sauvegardés, en ignorant ceux déjà expirés ;
+    /// retourne le nombre de bans restaurés. Un fichier absent ne restaure rien.
+    pub fn load_bans(&mut self, path: &str) -> Result<usize> {
+        if !Path::new(path).exists() {
+            return Ok(0);
+        }
+        let list: BanList = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        let now = Instant::now();
+        let now_unix = Utc::now().timestamp();
+        let mut restored = 0;
+        for ban in list.bans.into_iter().filter(|ban| ban.banned_until > now_unix) {
+            let remaining = Duration::from_secs((ban.banned_until - now_unix) as u64);
+            self.banned.insert(ban.ip, now + remaining);
+            restored += 1;
+        }
+        for (ip, count) in list.violations {
+            *self.violations.entry(ip).or_insert(0) += count;
+        }
+
+        Ok(restored)
+    }
+
+    /// Enregistrer un événement dans la fenêtre glissante d'une minute ; faux si la limite est atteinte
+    fn record_in_window<K: Hash + Eq>(history: &mut HashMap<K, Vec<Instant>>, key: K, limit: usize) -> bool {
+        let now = Instant::now();
+        let window = Duration::from_secs(60);
+
+        let events = history.entry(key).or_default();
+        events.retain(|t| now.duration_since(*t) < window);
+
+        if events.len() >= limit {
+            return false;
+        }
+
+        events.push(now);
+        true
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_size_and_rate() {
+        let mut limiter = RateLimiter::new();
+        limiter.max_messages_per_minute = 2;
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        assert!(!limiter.allow_message(ip, limiter.max_message_size_bytes + 1));
+        assert!(limiter.allow_message(ip, 100));
+        assert!(limiter.allow_message(ip, 100));
+        assert!(!limiter.allow_message(ip, 100));
+
+        // Les autres IP ne sont pas affectées
+        assert!(limiter.allow_message("10.0.0.2".parse().unwrap(), 100));
+    }
+
+    #[test]
+    fn test_expensive_variants_limited_separately() {
+        let mut limiter = RateLimiter::new();
+        limiter.max_requests_per_minute = 3;
+        let ip: IpAddr = "10.0.0.6".parse().unwrap();
+        let request = P2PMessage::RequestBlocks { from_height: 0, to_height: 500 };
+
+        for _ in 0..3 {
+            assert!(limiter.allow_message_variant(ip, &request));
+        }
+        assert!(!limiter.allow_message_variant(ip, &request));
+
+        // Les messages légers et les autres requêtes gardent leur propre budget
+        for _ in 0..50 {
+            assert!(limiter.allow_message_variant(ip, &P2PMessage::Ping));
+        }
+        assert!(limiter.allow_message_variant(ip, &P2PMessage::RequestHeaders { from_height: 0 }));
+        assert!(limiter.allow_message_variant("10.0.0.7".parse().unwrap(), &request));
+    }
+
+    #[test]
+    fn test_ban_blocks_everything() {
+        let mut limiter = RateLimiter::new();
+        let ip: IpAddr = "10.0.0.3".parse().unwrap();
+
+        limiter.ban_ip(ip);
+        assert!(limiter.is_banned(ip));
+        assert!(!limiter.allow_message(ip, 10));
+        assert!(!limiter.allow_block(ip));
+        assert_eq!(limiter.get_banned_ips(), vec![ip]);
+    }
+
+    #[test]
+    fn test_bans_survive_reload() {
+        let path = std::env::temp_dir().join(format!("aur_bans_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let banned: IpAddr = "10.0.0.4".parse().unwrap();
+        let expired: IpAddr = "10.0.0.5".parse().unwrap();
+
+        let mut limiter = RateLimiter::new();
+        limiter.ban_ip(banned);
+        limiter.record_violation(banned);
+        limiter.record_violation(banned);
+        limiter.ban_duration = Duration::ZERO;
+        limiter.ban_ip(expired);
+        limiter.save_bans(path).unwrap();
+
+        let mut fresh = RateLimiter::new();
+        assert_eq!(fresh.load_bans(path).unwrap(), 1);
+        assert!(fresh.is_banned(banned));
+        assert!(!fresh.is_banned(expired));
+        assert_eq!(fresh.violation_count(banned), 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
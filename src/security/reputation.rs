@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use crate::security::RateLimiter;
+
+/// Score à partir duquel un peer est banni
+pub const DEFAULT_BAN_THRESHOLD: i32 = 100;
+/// Crédit maximum accumulé par un peer utile : un long historique ne doit pas
+/// lui permettre d'envoyer ensuite des blocs invalides sans conséquence
+pub const MAX_CREDIT: i32 = 20;
+
+/// Comportement d'un peer observé par le P2P ou la validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerEvent {
+    InvalidBlock,
+    BadSignature,
+    OversizedMessage,
+    /// Bloc valide servi au nœud
+    ValidBlock,
+}
+
+impl PeerEvent {
+    /// Points ajoutés au score du peer (négatif : récompense)
+    pub fn points(self) -> i32 {
+        match self {
+            PeerEvent::InvalidBlock => 25,
+            PeerEvent::BadSignature => 20,
+            PeerEvent::OversizedMessage => 20,
+            PeerEvent::ValidBlock => -1,
+        }
+    }
+}
+
+/// Réputation des peers par IP : les pénalités s'additionnent et le peer est banni
+/// via `RateLimiter::ban_ip` dès que son score atteint `ban_threshold`
+pub struct PeerReputation {
+    rate_limiter: Arc<Mutex<RateLimiter>>,
+    scores: Mutex<HashMap<IpAddr, i32>>,
+    pub ban_threshold: i32,
+}
+
+impl PeerReputation {
+    pub fn new(rate_limiter: Arc<Mutex<RateLimiter>>) -> Self {
+        PeerReputation {
+            rate_limiter,
+            scores: Mutex::new(HashMap::new()),
+            ban_threshold: DEFAULT_BAN_THRESHOLD,
+        }
+    }
+
+    /// Enregistrer un événement ; retourne `true` si le peer vient d'être banni.
+    /// Le score repart de zéro après un ban.
+    pub fn record(&self, ip: IpAddr, event: PeerEvent) -> bool {
+        let mut scores = self.scores.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let score = scores.entry(ip).or_insert(0);
+        *score = score.saturating_add(event.points()).max(-MAX_CREDIT);
+        if *score < self.ban_threshold {
+            return false;
+        }
+
+        log::warn!("Peer {} reached reputation score {} ({:?})", ip, score, event);
+        scores.remove(&ip);
+        drop(scores);
+        self.rate_limiter.lock()
+            .map(|mut limiter| limiter.ban_ip(ip))
+            .is_ok()
+    }
+
+    pub fn score(&self, ip: IpAddr) -> i32 {
+        self.scores.lock()
+            .map(|scores| scores.get(&ip).copied().unwrap_or(0))
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_invalid_blocks_lead_to_ban() {
+        let rate_limiter = Arc::new(Mutex::new(RateLimiter::new()));
+        let reputation = PeerReputation::new(rate_limiter.clone());
+        let peer: IpAddr = "203.0.113.7".parse().unwrap();
+        let honest: IpAddr = "203.0.113.8".parse().unwrap();
+
+        // Le crédit d'un peer utile est plafonné
+        for _ in 0..100 {
+            reputation.record(peer, PeerEvent::ValidBlock);
+        }
+        assert_eq!(reputation.score(peer), -MAX_CREDIT);
+
+        let mut events = 0;
+        while !reputation.record(peer, PeerEvent::InvalidBlock) {
+            events += 1;
+            assert!(!rate_limiter.lock().unwrap().is_banned(peer));
+            assert!(events < 10, "peer never banned");
+        }
+        assert_eq!(events, 4);
+        assert!(rate_limiter.lock().unwrap().is_banned(peer));
+        assert_eq!(reputation.score(peer), 0);
+
+        reputation.record(honest, PeerEvent::BadSignature);
+        assert!(!rate_limiter.lock().unwrap().is_banned(honest));
+    }
+}
@@ -0,0 +1,596 @@
+use anyhow::{anyhow, Result};
+use secp256k1::{ecdsa::Signature, Message, PublicKey, Secp256k1};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use crate::blockchain::{Block, SigHashType, Transaction, TxInput, TxOutput};
+use crate::utils::config::Network;
+use crate::wallet::address::{encode_address, wallet_address};
+use crate::wallet::multisig::{MultisigScript, MAX_MULTISIG_KEYS, MULTISIG_ENTRY_LEN, MULTISIG_SCRIPT_MARKER};
+
+/// Signature compacte secp256k1 (64 octets) suivie de l'octet SIGHASH optionnel
+pub const MAX_SIGNATURE_HEX_LEN: usize = 130;
+/// Signature compacte sans octet SIGHASH
+const COMPACT_SIGNATURE_LEN: usize = 64;
+/// Clé publique non compressée : 65 octets (une clé compressée en fait 33)
+pub const MAX_PUBLIC_KEY_HEX_LEN: usize = 130;
+
+/// Entrées multisig : au plus une par clé du script, 65 octets chacune
+pub const MAX_MULTISIG_SIGNATURE_HEX_LEN: usize = MAX_MULTISIG_KEYS * MULTISIG_ENTRY_LEN * 2;
+
+/// Vérification des signatures ECDSA (secp256k1) des inputs de transaction
+pub struct SignatureValidator;
+
+impl SignatureValidator {
+    /// Hash signé par l'input `index` : `Transaction::signing_hash` pour une signature
+    /// sans type (tout engagé), sinon `Transaction::signing_hash_with`
+    pub fn create_signing_hash(tx: &Transaction, index: usize, sighash: Option<SigHashType>) -> Result<[u8; 32]> {
+        match sighash {
+            None => Ok(tx.signing_hash(index)),
+            Some(sighash) => tx.signing_hash_with(index, sighash)
+                .ok_or_else(|| anyhow!("Input {}: {:?} has nothing to commit", index, sighash)),
+        }
+    }
+
+    /// Vérifier la signature d'un input avec la clé publique qu'il porte
+    pub fn verify_input(tx: &Transaction, index: usize) -> Result<()> {
+        let input = tx.inputs.get(index)
+            .ok_or_else(|| anyhow!("Input {} out of range", index))?;
+
+        if input.public_key.starts_with(&hex::encode([MULTISIG_SCRIPT_MARKER])) {
+            return Self::verify_multisig_input(tx, index);
+        }
+
+        // Bornes vérifiées avant tout décodage : un peer ne doit pas pouvoir gonfler
+        // les blocs avec des signatures ou des clés rembourrées
+        if input.signature.len() > MAX_SIGNATURE_HEX_LEN {
+            return Err(anyhow!(
+                "Input {}: signature too large ({} hex chars, max {})",
+                index, input.signature.len(), MAX_SIGNATURE_HEX_LEN
+            ));
+        }
+        if input.public_key.len() > MAX_PUBLIC_KEY_HEX_LEN {
+            return Err(anyhow!(
+                "Input {}: public key too large ({} hex chars, max {})",
+                index, input.public_key.len(), MAX_PUBLIC_KEY_HEX_LEN
+            ));
+        }
+
+        let public_key_bytes = hex::decode(&input.public_key)
+            .map_err(|_| anyhow!("Input {}: invalid public key encoding", index))?;
+        let public_key = PublicKey::from_slice(&public_key_bytes)
+            .map_err(|_| anyhow!("Input {}: invalid public key", index))?;
+
+        let signature_bytes = hex::decode(&input.signature)
+            .map_err(|_| anyhow!("Input {}: invalid signature encoding", index))?;
+        let (signature_bytes, sighash) = match signature_bytes.split_at_checked(COMPACT_SIGNATURE_LEN) {
+            Some((compact, &[byte])) => {
+                let sighash = SigHashType::from_byte(byte)
+                    .ok_or_else(|| anyhow!("Input {}: unknown sighash type {:#04x}", index, byte))?;
+                (compact, Some(sighash))
+            },
+            _ => (&signature_bytes[..], None),
+        };
+        let message = Message::from_digest(Self::create_signing_hash(tx, index, sighash)?);
+        Self::verify_signature(index, signature_bytes, &public_key, &message)
+    }
+
+    /// Vérifier une signature compacte de `public_key` sur `message`
+    fn verify_signature(index: usize, signature_bytes: &[u8], public_key: &PublicKey, message: &Message) -> Result<()> {
+        let signature = Signature::from_compact(signature_bytes)
+            .map_err(|_| anyhow!("Input {}: invalid signature", index))?;
+
+        // BIP62 : seule la forme low-S est acceptée, sinon (r, n - s) serait une seconde
+        // signature valide pour le même input
+        let mut normalized = signature;
+        normalized.normalize_s();
+        if normalized != signature {
+            return Err(anyhow!("Input {}: non-canonical high-S signature", index));
+        }
+
+        Secp256k1::verification_only()
+            .verify_ecdsa(message, &signature, public_key)
+            .map_err(|_| anyhow!("Input {}: signature verification failed", index))
+    }
+
+    /// Input dépensant une adresse M-of-N : `public_key` porte le script sérialisé,
+    /// `signature` une suite d'entrées (index de clé, signature compacte). Au moins `m`
+    /// clés distinctes du script doivent avoir signé.
+    fn verify_multisig_input(tx: &Transaction, index: usize) -> Result<()> {
+        let input = &tx.inputs[index];
+
+        if input.signature.len() > MAX_MULTISIG_SIGNATURE_HEX_LEN {
+            return Err(anyhow!(
+                "Input {}: signature too large ({} hex chars, max {})",
+                index, input.signature.len(), MAX_MULTISIG_SIGNATURE_HEX_LEN
+            ));
+        }
+        if input.public_key.len() > MultisigScript::max_hex_len() {
+            return Err(anyhow!(
+                "Input {}: multisig script too large ({} hex chars, max {})",
+                index, input.public_key.len(), MultisigScript::max_hex_len()
+            ));
+        }
+
+        let script_bytes = hex::decode(&input.public_key)
+            .map_err(|_| anyhow!("Input {}: invalid multisig script encoding", index))?;
+        let script = MultisigScript::from_bytes(&script_bytes)
+            .map_err(|e| anyhow!("Input {}: {}", index, e))?;
+
+        let signature_bytes = hex::decode(&input.signature)
+            .map_err(|_| anyhow!("Input {}: invalid signature encoding", index))?;
+        if signature_bytes.len() % MULTISIG_ENTRY_LEN != 0 {
+            return Err(anyhow!("Input {}: invalid multisig signature length", index));
+        }
+
+        let message = Message::from_digest(Self::create_signing_hash(tx, index, None)?);
+        let mut signed = vec![false; script.pubkeys.len()];
+        for entry in signature_bytes.chunks(MULTISIG_ENTRY_LEN) {
+            let key_index = entry[0] as usize;
+            match signed.get(key_index) {
+                None => return Err(anyhow!("Input {}: multisig key index {} out of range", index, key_index)),
+                Some(true) => return Err(anyhow!("Input {}: duplicate signature for multisig key {}", index, key_index)),
+                Some(false) => {}
+            }
+
+            // Clés déjà validées par `MultisigScript::from_bytes`
+            let public_key = PublicKey::from_slice(&script.pubkeys[key_index])
+                .map_err(|_| anyhow!("Input {}: invalid public key", index))?;
+            Self::verify_signature(index, &entry[1..], &public_key, &message)?;
+            signed[key_index] = true;
+        }
+
+        let valid = signed.iter().filter(|&&signed| signed).count();
+        if valid < script.m as usize {
+            return Err(anyhow!("Input {}: {} of {} required multisig signatures", index, valid, script.m));
+        }
+        Ok(())
+    }
+
+    /// Vérifier l'input `index` qui dépense `spent` : signature valide, et clé publique
+    /// (ou script multisig) dont l'adresse sur `network` est celle de l'output dépensé.
+    /// Sans ce lien, n'importe quelle paire de clés pourrait « autoriser » n'importe quel UTXO.
+    pub fn verify_spend(tx: &Transaction, index: usize, spent: &TxOutput, network: Network) -> Result<()> {
+        Self::verify_input(tx, index)?;
+
+        if !Self::input_owns(&tx.inputs[index], &spent.address, network) {
+            return Err(anyhow!("Input {}: key does not own the spent output (address {})", index, spent.address));
+        }
+        Ok(())
+    }
+
+    /// La clé publique (ou le script multisig) que porte l'input contrôle `address` :
+    /// adresse de type 1 à 3 sur `network`, adresse `SecureWallet` sur le mainnet,
+    /// ou adresse du script pour un multisig
+    fn input_owns(input: &TxInput, address: &str, network: Network) -> bool {
+        let Ok(bytes) = hex::decode(&input.public_key) else {
+            return false;
+        };
+        if bytes.first() == Some(&MULTISIG_SCRIPT_MARKER) {
+            return MultisigScript::from_bytes(&bytes).is_ok_and(|script| script.address(network) == address);
+        }
+        (1..=3).any(|address_type| encode_address(&bytes, address_type, network) == address)
+            || (network == Network::Mainnet
+                && ["AUR1", "AUR2", "AUR3"].iter().any(|addr_type| wallet_address(&bytes, addr_type) == address))
+    }
+
+    /// Vérifier chaque input d'une transaction contre l'output qu'il dépense, retrouvé
+    /// par `resolve` (voir `verify_spend`) ; un input introuvable est refusé
+    pub fn verify_transaction_spends<'a, F>(tx: &Transaction, resolve: F, network: Network) -> Result<()>
+    where
+        F: Fn(&TxInput) -> Option<&'a TxOutput>,
+    {
+        for (index, input) in tx.inputs.iter().enumerate() {
+            let spent = resolve(input)
+                .ok_or_else(|| anyhow!("Input {}: unknown output {}:{}", index, input.prev_tx_id, input.output_index))?;
+            Self::verify_spend(tx, index, spent, network)?;
+        }
+        Ok(())
+    }
+
+    /// Vérifier toutes les signatures d'une transaction (la coinbase n'en a pas),
+    /// sans savoir à qui appartiennent les outputs dépensés : voir `verify_transaction_spends`
+    pub fn verify_transaction(tx: &Transaction) -> Result<()> {
+        if tx.is_coinbase() {
+            return Ok(());
+        }
+
+        for index in 0..tx.inputs.len() {
+            Self::verify_input(tx, index)?;
+        }
+
+        Ok(())
+    }
+
+    /// Vérifier les dépenses de toutes les transactions d'un bloc (`verify_transaction_spends`),
+    /// réparties sur les cœurs. Les workers prennent les index dans l'ordre et s'arrêtent après
+    /// le premier échec connu : l'erreur retournée est celle de la plus petite transaction
+    /// invalide, comme en série.
+    pub fn verify_block_transactions<'a, F>(block: &Block, resolve: F, network: Network) -> Result<()>
+    where
+        F: Fn(&TxInput) -> Option<&'a TxOutput> + Sync,
+    {
+        let transactions = &block.transactions;
+        let workers = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(transactions.len());
+
+        let next = AtomicUsize::new(0);
+        let first_failure = AtomicUsize::new(usize::MAX);
+        let failure: Mutex<Option<(usize, anyhow::Error)>> = Mutex::new(None);
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    if index >= transactions.len() || index > first_failure.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    if let Err(e) = Self::verify_transaction_spends(&transactions[index], &resolve, network) {
+                        first_failure.fetch_min(index, Ordering::SeqCst);
+                        let mut failure = failure.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                        if failure.as_ref().is_none_or(|(failed, _)| index < *failed) {
+                            *failure = Some((index, e));
+                        }
+                    }
+                });
+            }
+        });
+
+        match failure.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner()) {
+            Some((index, e)) => Err(anyhow!("Transaction {} ({}): {}", index, transactions[index].id, e)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::{TxInput, TxOutput};
+    use crate::wallet::address::address_from_keypair;
+    use crate::wallet::keys::KeyPair;
+
+    fn unsigned_tx() -> Transaction {
+        let input = |prev: &str| TxInput {
+            prev_tx_id: prev.to_string(),
+            output_index: 0,
+            signature: String::new(),
+            public_key: String::new(),
+        };
+        Transaction::new(
+            vec![input("aa"), input("bb")],
+            vec![TxOutput { value: 1_000, address: "AUR1Bob".to_string() }],
+        )
+    }
+
+    #[test]
+    fn test_sign_and_verify_round_trip() {
+        let keypair = KeyPair::generate();
+        let mut tx = unsigned_tx();
+
+        for index in 0..tx.inputs.len() {
+            tx.sign_input(index, &keypair).unwrap();
+        }
+
+        assert!(SignatureValidator::verify_transaction(&tx).is_ok());
+    }
+
+    #[test]
+    fn test_wallet_signature_accepted_by_validator() {
+        let keypair = KeyPair::generate();
+        let mut tx = unsigned_tx();
+
+        keypair.sign_transaction_input(&mut tx, 0).unwrap();
+        keypair.sign_transaction_input(&mut tx, 1).unwrap();
+        assert!(SignatureValidator::verify_transaction(&tx).is_ok());
+
+        // Signer et vérifieur hachent le même préimage, propre à chaque input
+        assert_eq!(SignatureValidator::create_signing_hash(&tx, 1, None).unwrap(), tx.signing_hash(1));
+        assert_ne!(tx.signing_hash(0), tx.signing_hash(1));
+
+        // Une signature valide ne peut pas être recopiée sur un autre input
+        tx.inputs[1].signature = tx.inputs[0].signature.clone();
+        assert!(SignatureValidator::verify_input(&tx, 1).is_err());
+        assert!(keypair.sign_transaction_input(&mut tx, 2).is_err());
+    }
+
+    #[test]
+    fn test_padded_signature_rejected() {
+        let keypair = KeyPair::generate();
+        let mut tx = unsigned_tx();
+        for index in 0..tx.inputs.len() {
+            tx.sign_input(index, &keypair).unwrap();
+        }
+        assert!(SignatureValidator::verify_input(&tx, 0).is_ok());
+
+        let mut padded = tx.clone();
+        padded.inputs[0].signature.push_str(&"00".repeat(26_000));
+        let err = SignatureValidator::verify_input(&padded, 0).unwrap_err();
+        assert!(err.to_string().contains("signature too large"), "{}", err);
+
+        let mut padded = tx;
+        padded.inputs[1].public_key.push_str(&"00".repeat(100));
+        let err = SignatureValidator::verify_transaction(&padded).unwrap_err();
+        assert!(err.to_string().contains("public key too large"), "{}", err);
+    }
+
+    #[test]
+    fn test_tampered_transaction_rejected() {
+        let keypair = KeyPair::generate();
+        let mut tx = unsigned_tx();
+        for index in 0..tx.inputs.len() {
+            tx.sign_input(index, &keypair).unwrap();
+        }
+
+        tx.outputs[0].value = 1_000_000;
+        assert!(SignatureValidator::verify_transaction(&tx).is_err());
+    }
+
+    #[test]
+    fn test_two_of_three_multisig_spend() {
+        let keypairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+        let pubkeys = keypairs.iter().map(|k| k.public_key.serialize().to_vec()).collect();
+        let script = MultisigScript::new(2, pubkeys).unwrap();
+
+        let mut tx = unsigned_tx();
+        tx.inputs.truncate(1);
+        keypairs[0].sign_multisig_input(&mut tx, 0, &script).unwrap();
+        let err = SignatureValidator::verify_transaction(&tx).unwrap_err();
+        assert!(err.to_string().contains("1 of 2 required multisig signatures"), "{}", err);
+
+        keypairs[2].sign_multisig_input(&mut tx, 0, &script).unwrap();
+        assert!(SignatureValidator::verify_transaction(&tx).is_ok());
+
+        // La même clé comptée deux fois n'atteint pas le seuil
+        let mut repeated = tx.clone();
+        repeated.inputs[0].signature.truncate(MULTISIG_ENTRY_LEN * 2);
+        keypairs[0].sign_multisig_input(&mut repeated, 0, &script).unwrap();
+        assert!(SignatureValidator::verify_transaction(&repeated).is_err());
+
+        // Une clé hors du script ne peut pas cosigner
+        assert!(KeyPair::generate().sign_multisig_input(&mut tx, 0, &script).is_err());
+
+        tx.outputs[0].value += 1;
+        assert!(SignatureValidator::verify_transaction(&tx).is_err());
+    }
+
+    /// Deux inputs, deux outputs : assez pour distinguer ALL, SINGLE et NONE
+    fn two_output_tx() -> Transaction {
+        let mut tx = unsigned_tx();
+        tx.outputs.push(TxOutput { value: 2_000, address: "AUR1Carol".to_string() });
+        tx
+    }
+
+    #[test]
+    fn test_sighash_all_commits_every_output() {
+        let keypair = KeyPair::generate();
+        let mut tx = two_output_tx();
+        keypair.sign_transaction_input_with(&mut tx, 0, SigHashType::ALL).unwrap();
+        keypair.sign_transaction_input_with(&mut tx, 1, SigHashType::ALL).unwrap();
+        assert!(SignatureValidator::verify_transaction(&tx).is_ok());
+
+        // L'octet de type est engagé : la même signature ne vaut pas pour un autre type
+        assert_ne!(tx.signing_hash_with(0, SigHashType::ALL), Some(tx.signing_hash(0)));
+        let mut retyped = tx.clone();
+        retyped.inputs[0].signature.replace_range(128.., "03");
+        assert!(SignatureValidator::verify_input(&retyped, 0).is_err());
+
+        for output in 0..tx.outputs.len() {
+            let mut tampered = tx.clone();
+            tampered.outputs[output].value += 1;
+            assert!(SignatureValidator::verify_input(&tampered, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_sighash_single_commits_matching_output_only() {
+        let keypair = KeyPair::generate();
+        let mut tx = two_output_tx();
+        keypair.sign_transaction_input_with(&mut tx, 0, SigHashType::SINGLE).unwrap();
+        assert!(SignatureValidator::verify_input(&tx, 0).is_ok());
+
+        let mut other_output_changed = tx.clone();
+        other_output_changed.outputs[1].value += 1;
+        assert!(SignatureValidator::verify_input(&other_output_changed, 0).is_ok());
+
+        let mut matching_output_changed = tx.clone();
+        matching_output_changed.outputs[0].value += 1;
+        assert!(SignatureValidator::verify_input(&matching_output_changed, 0).is_err());
+
+        // Sans output de même index, SINGLE n'a rien à engager
+        tx.outputs.truncate(1);
+        assert!(keypair.sign_transaction_input_with(&mut tx, 1, SigHashType::SINGLE).is_err());
+
+        // ANYONECANPAY : un autre participant peut ajouter son input avant le nôtre
+        let mut tx = two_output_tx();
+        let sighash = SigHashType::SINGLE.with_anyone_can_pay();
+        keypair.sign_transaction_input_with(&mut tx, 0, sighash).unwrap();
+        let mut joined = tx.clone();
+        let ours = joined.inputs.remove(0);
+        joined.inputs.insert(1, ours);
+        joined.outputs.swap(0, 1);
+        assert!(SignatureValidator::verify_input(&joined, 1).is_ok());
+    }
+
+    #[test]
+    fn test_sighash_none_commits_no_output() {
+        let keypair = KeyPair::generate();
+        let mut tx = two_output_tx();
+        keypair.sign_transaction_input_with(&mut tx, 0, SigHashType::NONE).unwrap();
+
+        let mut redirected = tx.clone();
+        redirected.outputs[0].address = "AUR1Mallory".to_string();
+        redirected.outputs.pop();
+        assert!(SignatureValidator::verify_input(&redirected, 0).is_ok());
+
+        // Les inputs restent engagés
+        let mut other_input = tx.clone();
+        other_input.inputs[1].prev_tx_id = "cc".to_string();
+        assert!(SignatureValidator::verify_input(&other_input, 0).is_err());
+    }
+
+    /// (r, s) -> (r, n - s) : l'équivalent high-S d'une signature compacte
+    fn to_high_s(signature_hex: &str) -> String {
+        let mut bytes = hex::decode(signature_hex).unwrap();
+        let order = secp256k1::constants::CURVE_ORDER;
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let diff = order[i] as i16 - bytes[32 + i] as i16 - borrow;
+            borrow = if diff < 0 { 1 } else { 0 };
+            bytes[32 + i] = diff.rem_euclid(256) as u8;
+        }
+        hex::encode(bytes)
+    }
+
+    #[test]
+    fn test_high_s_signature_rejected() {
+        let keypair = KeyPair::generate();
+        let mut tx = unsigned_tx();
+        tx.sign_input(0, &keypair).unwrap();
+        assert!(SignatureValidator::verify_input(&tx, 0).is_ok());
+
+        let low_s = tx.inputs[0].signature.clone();
+        tx.inputs[0].signature = to_high_s(&low_s);
+        assert_ne!(tx.inputs[0].signature, low_s);
+
+        let err = SignatureValidator::verify_input(&tx, 0).unwrap_err();
+        assert!(err.to_string().contains("high-S"), "{}", err);
+    }
+
+    #[test]
+    fn test_unsigned_input_rejected() {
+        let keypair = KeyPair::generate();
+        let mut tx = unsigned_tx();
+        tx.sign_input(0, &keypair).unwrap();
+
+        assert!(SignatureValidator::verify_input(&tx, 0).is_ok());
+        assert!(SignatureValidator::verify_transaction(&tx).is_err());
+        assert!(tx.sign_input(5, &keypair).is_err());
+    }
+
+    #[test]
+    fn test_parallel_block_verification_matches_serial() {
+        let input = |prev: String| TxInput {
+            prev_tx_id: prev,
+            output_index: 0,
+            signature: String::new(),
+            public_key: String::new(),
+        };
+        let keypair = KeyPair::generate();
+        let owned = TxOutput { value: 5_000, address: address_from_keypair(&keypair, Network::Mainnet) };
+        let resolve = |_: &TxInput| Some(&owned);
+        let mut transactions = vec![Transaction::coinbase("AUR1Miner".to_string(), 50_0000_0000, 1_730_000_000)];
+        for n in 0..12 {
+            let mut tx = Transaction::new(
+                vec![input(format!("{:064x}", n)), input(format!("{:064x}", n + 100))],
+                vec![TxOutput { value: 1_000 + n, address: "AUR1Bob".to_string() }],
+            );
+            tx.sign_input(0, &keypair).unwrap();
+            tx.sign_input(1, &keypair).unwrap();
+            transactions.push(tx);
+        }
+
+        let serial = |block: &Block| block.transactions.iter()
+            .position(|tx| SignatureValidator::verify_transaction(tx).is_err());
+
+        let block = Block::new(1, transactions, "0".repeat(64), 1, "AUR1Miner".to_string());
+        assert_eq!(serial(&block), None);
+        assert!(SignatureValidator::verify_block_transactions(&block, resolve, Network::Mainnet).is_ok());
+
+        // Deux transactions invalides : la plus petite est signalée
+        let mut tampered = block.clone();
+        tampered.transactions[9].outputs[0].value += 1;
+        tampered.transactions[4].inputs[1].signature = tampered.transactions[4].inputs[0].signature.clone();
+        assert_eq!(serial(&tampered), Some(4));
+
+        for _ in 0..5 {
+            let err = SignatureValidator::verify_block_transactions(&tampered, resolve, Network::Mainnet).unwrap_err().to_string();
+            assert!(err.starts_with(&format!("Transaction 4 ({})", tampered.transactions[4].id)), "{}", err);
+            assert!(err.contains("Input 1"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_txid_unchanged_by_signature_changes() {
+        let keypair = KeyPair::generate();
+        let mut tx = unsigned_tx();
+        let unsigned_id = tx.id.clone();
+
+        tx.sign_input(0, &keypair).unwrap();
+        tx.sign_input(1, &keypair).unwrap();
+        assert_eq!(tx.id, unsigned_id);
+
+        // Autre encodage valide de la signature (octet SIGHASH explicite) : même id, toujours valide
+        keypair.sign_transaction_input_with(&mut tx, 0, SigHashType::ALL).unwrap();
+        assert_eq!(tx.id, unsigned_id);
+        assert!(SignatureValidator::verify_transaction(&tx).is_ok());
+
+        // Signature altérée : l'id ne bouge pas, la vérification échoue
+        let mut tampered = tx.clone();
+        let flipped = if tampered.inputs[1].signature.starts_with("00") { "01" } else { "00" };
+        tampered.inputs[1].signature.replace_range(0..2, flipped);
+        assert_eq!(tampered.id, unsigned_id);
+        assert!(SignatureValidator::verify_transaction(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_foreign_key_cannot_spend_output() {
+        let owner = KeyPair::generate();
+        let thief = KeyPair::generate();
+        let spent = TxOutput { value: 1_000, address: address_from_keypair(&owner, Network::Mainnet) };
+
+        // Signature valide pour la clé qu'il porte, mais cette clé ne possède pas l'output
+        let mut tx = unsigned_tx();
+        tx.sign_input(0, &thief).unwrap();
+        assert!(SignatureValidator::verify_input(&tx, 0).is_ok());
+        let err = SignatureValidator::verify_spend(&tx, 0, &spent, Network::Mainnet).unwrap_err();
+        assert!(err.to_string().contains("does not own"), "{}", err);
+
+        tx.sign_input(0, &owner).unwrap();
+        assert!(SignatureValidator::verify_spend(&tx, 0, &spent, Network::Mainnet).is_ok());
+        // Même clé, autre réseau : autre adresse
+        assert!(SignatureValidator::verify_spend(&tx, 0, &spent, Network::Testnet).is_err());
+    }
+
+    #[test]
+    fn test_secure_wallet_address_spendable_by_its_key() {
+        let keypair = KeyPair::generate();
+        let public_key = keypair.public_key.serialize();
+        let mut tx = unsigned_tx();
+        tx.sign_input(0, &keypair).unwrap();
+
+        for addr_type in ["AUR1", "AUR2", "AUR3"] {
+            let spent = TxOutput { value: 1_000, address: wallet_address(&public_key, addr_type) };
+            assert!(SignatureValidator::verify_spend(&tx, 0, &spent, Network::Mainnet).is_ok());
+        }
+        let foreign = TxOutput { value: 1_000, address: wallet_address(&KeyPair::generate().public_key.serialize(), "AUR1") };
+        assert!(SignatureValidator::verify_spend(&tx, 0, &foreign, Network::Mainnet).is_err());
+    }
+
+    #[test]
+    fn test_foreign_multisig_script_cannot_spend_output() {
+        let keypairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+        let script = |keys: &[KeyPair]| {
+            MultisigScript::new(2, keys.iter().map(|k| k.public_key.serialize().to_vec()).collect()).unwrap()
+        };
+        let owned = script(&keypairs);
+        let spent = TxOutput { value: 1_000, address: owned.address(Network::Mainnet) };
+
+        // Script 2-of-2 de l'attaquant, correctement signé, mais d'une autre adresse
+        let mut tx = unsigned_tx();
+        tx.inputs.truncate(1);
+        let attackers: Vec<KeyPair> = (0..2).map(|_| KeyPair::generate()).collect();
+        let foreign_script = script(&attackers);
+        attackers[0].sign_multisig_input(&mut tx, 0, &foreign_script).unwrap();
+        attackers[1].sign_multisig_input(&mut tx, 0, &foreign_script).unwrap();
+        assert!(SignatureValidator::verify_input(&tx, 0).is_ok());
+        assert!(SignatureValidator::verify_spend(&tx, 0, &spent, Network::Mainnet).is_err());
+
+        let mut tx = unsigned_tx();
+        tx.inputs.truncate(1);
+        keypairs[0].sign_multisig_input(&mut tx, 0, &owned).unwrap();
+        keypairs[1].sign_multisig_input(&mut tx, 0, &owned).unwrap();
+        assert!(SignatureValidator::verify_spend(&tx, 0, &spent, Network::Mainnet).is_ok());
+    }
+}
@@ -1,38 +1,113 @@
-use crate::blockchain::{Block, Transaction};
+use crate::blockchain::{Block, Blockchain, TxInput};
+use crate::security::{SecurityEventType, SecurityLogger};
+use crate::utils::config::ConsensusParams;
+use crate::wallet::address::validate_address;
 use anyhow::{Result, anyhow};
 use chrono::Utc;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 pub struct SecurityValidator {
-    pub max_block_size: usize,
-    pub max_transactions_per_block: usize,
-    pub max_future_timestamp: i64,
+    pub params: ConsensusParams,
+    /// Journal des tentatives suspectes (réorganisations trop profondes)
+    pub logger: Option<Arc<SecurityLogger>>,
 }
 
 impl SecurityValidator {
     pub fn new() -> Self {
+        Self::with_params(&ConsensusParams::default())
+    }
+
+    pub fn with_params(params: &ConsensusParams) -> Self {
         SecurityValidator {
-            max_block_size: 4_000_000,
-            max_transactions_per_block: 10_000,
-            max_future_timestamp: 7200,
+            params: params.clone(),
+            logger: None,
+        }
+    }
+
+    /// Vérifier qu'un fork (blocs consécutifs) se raccroche à `chain` sans annuler plus de
+    /// `max_reorg_depth` blocs ; retourne le nombre de blocs annulés. Une tentative plus
+    /// profonde est journalisée comme activité suspecte.
+    pub fn validate_reorg(&self, chain: &Blockchain, fork: &[Block]) -> Result<u64> {
+        let first = fork.first().ok_or_else(|| anyhow!("Empty fork"))?;
+        let fork_point = chain.get_block_by_hash(&first.previous_hash)
+            .filter(|parent| parent.index + 1 == first.index)
+            .ok_or_else(|| anyhow!("Fork does not connect to the chain"))?;
+        let tip = chain.get_latest_block()
+            .ok_or_else(|| anyhow!("Cannot reorganize an empty chain"))?;
+
+        let depth = tip.index - fork_point.index;
+        if depth > self.params.max_reorg_depth {
+            if let Some(logger) = &self.logger {
+                logger.log_event(
+                    SecurityEventType::SuspiciousActivity,
+                    &first.miner_address,
+                    &format!("Reorg of depth {} from height {} refused", depth, fork_point.index),
+                );
+            }
+            return Err(anyhow!(
+                "Reorg of depth {} exceeds the limit of {} blocks",
+                depth, self.params.max_reorg_depth
+            ));
         }
+
+        Ok(depth)
     }
 
     pub fn validate_block_strict(&self, block: &Block, previous: &Block) -> Result<()> {
+        self.validate_block_with_inputs(block, previous, |_| None)
+    }
+
+    /// Validation stricte avec résolution des inputs (valeur de l'output dépensé),
+    /// nécessaire pour accepter une coinbase qui réclame des frais.
+    pub fn validate_block_with_inputs<F>(&self, block: &Block, previous: &Block, resolve_input: F) -> Result<()>
+    where
+        F: Fn(&TxInput) -> Option<u64>,
+    {
+        self.validate_block_with_subsidy(block, previous, resolve_input, self.params.block_reward(block.index))
+    }
+
+    fn validate_block_with_subsidy<F>(&self, block: &Block, previous: &Block, resolve_input: F, subsidy: u64) -> Result<()>
+    where
+        F: Fn(&TxInput) -> Option<u64>,
+    {
         self.validate_timestamp(block)?;
         self.validate_size(block)?;
         self.validate_transactions(block)?;
-        self.validate_reward(block)?;
+        self.validate_merkle_root(block)?;
+        self.validate_reward(block, &resolve_input, subsidy)?;
         self.validate_pow(block)?;
         self.validate_chain_link(block, previous)?;
         self.detect_double_spend(block)?;
+        self.validate_output_addresses(block)?;
         Ok(())
     }
 
+    /// Validation stricte d'un bloc candidat au sommet de `chain` : inputs résolus
+    /// dans la chaîne, timestamp postérieur au median-time-past et subvention
+    /// limitée à ce qui reste sous le plafond d'émission
+    pub fn validate_block_against_chain(&self, block: &Block, chain: &Blockchain) -> Result<()> {
+        let previous = chain.get_latest_block()
+            .ok_or_else(|| anyhow!("Cannot validate a block against an empty chain"))?;
+        
+        let median_time_past = chain.median_time_past();
+        if block.timestamp <= median_time_past {
+            return Err(anyhow!(
+                "Block timestamp {} not after median time past {}",
+                block.timestamp, median_time_past
+            ));
+        }
+        
+        chain.check_utxo_conflicts(block)?;
+        
+        let subsidy = self.params.capped_reward(block.index, chain.total_issued());
+        self.validate_block_with_subsidy(block, previous, |input| chain.resolve_input(input), subsidy)
+    }
+
     fn validate_timestamp(&self, block: &Block) -> Result<()> {
         let now = Utc::now().timestamp();
         
-        if block.timestamp > now + self.max_future_timestamp {
+        if block.timestamp > now.saturating_add(self.params.max_future_timestamp) {
             return Err(anyhow!("Block timestamp too far in future"));
         }
         
@@ -48,11 +123,11 @@ impl SecurityValidator {
             .map_err(|e| anyhow!("Failed to serialize block: {}", e))?
             .len();
         
-        if block_size > self.max_block_size {
+        if block_size > self.params.max_block_size {
             return Err(anyhow!("Block size exceeds maximum"));
         }
         
-        if block.transactions.len() > self.max_transactions_per_block {
+        if block.transactions.len() > self.params.max_transactions_per_block {
             return Err(anyhow!("Too many transactions"));
         }
         
@@ -74,40 +149,77 @@ impl SecurityValidator {
             }
         }
         
+        for tx in &block.transactions {
+            if tx.checked_total_output().is_none() {
+                return Err(anyhow!("Transaction {} output sum overflows", tx.id));
+            }
+            if !tx.is_final(block.index, block.timestamp) {
+                return Err(anyhow!("Transaction {} is locked until {}", tx.id, tx.locktime));
+            }
+        }
+        
         Ok(())
     }
 
-    fn validate_reward(&self, block: &Block) -> Result<()> {
-        use crate::blockchain::genesis::calculate_block_reward;
-        
-        let expected_reward = calculate_block_reward(block.index);
-        let coinbase = &block.transactions[0];
-        let actual_reward: u64 = coinbase.outputs.iter().map(|o| o.value).sum();
-        
-        if actual_reward > expected_reward {
-            return Err(anyhow!("Excessive mining reward"));
+    /// Chaque output doit payer une adresse valide du réseau ; seul le genesis (dont l'output
+    /// porte le message de lancement) en est exempté
+    fn validate_output_addresses(&self, block: &Block) -> Result<()> {
+        if block.index == 0 {
+            return Ok(());
+        }
+
+        for tx in &block.transactions {
+            if let Some(output) = tx.outputs.iter().find(|output| !validate_address(&output.address, self.params.network)) {
+                return Err(anyhow!("Transaction {} pays malformed address {:?}", tx.id, output.address));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Le hash couvre `merkle_root` mais pas les transactions : la racine doit être recalculée
+    fn validate_merkle_root(&self, block: &Block) -> Result<()> {
+        if Block::calculate_merkle_root(&block.transactions) != block.merkle_root {
+            return Err(anyhow!("Merkle root does not match block transactions"));
         }
         
         Ok(())
     }
 
-    fn validate_pow(&self, block: &Block) -> Result<()> {
-        let calculated_hash = block.calculate_hash();
+    fn validate_reward<F>(&self, block: &Block, resolve_input: &F, subsidy: u64) -> Result<()>
+    where
+        F: Fn(&TxInput) -> Option<u64>,
+    {
+        let coinbase = &block.transactions[0];
+        let actual_reward = coinbase.checked_total_output()
+            .ok_or_else(|| anyhow!("Coinbase output sum overflows"))?;
+        
+        if actual_reward <= subsidy {
+            return Ok(());
+        }
         
-        if calculated_hash != block.hash {
-            return Err(anyhow!("Block hash mismatch"));
+        let mut fees: u64 = 0;
+        for tx in &block.transactions[1..] {
+            let fee = tx.fee_with(resolve_input)
+                .ok_or_else(|| anyhow!("Cannot verify claimed fees: unresolved inputs in tx {}", tx.id))?;
+            fees = fees.checked_add(fee)
+                .ok_or_else(|| anyhow!("Block fee sum overflows"))?;
         }
         
-        let target = "0".repeat(block.difficulty as usize);
-        if !block.hash.starts_with(&target) {
-            return Err(anyhow!("Block hash does not meet difficulty"));
+        if subsidy.checked_add(fees).is_none_or(|max_reward| actual_reward > max_reward) {
+            return Err(anyhow!("Excessive mining reward"));
         }
         
         Ok(())
     }
 
+    fn validate_pow(&self, block: &Block) -> Result<()> {
+        // La preuve de travail ne vaut que si la difficulté annoncée est celle du réseau
+        block.verify_consensus_pow(&self.params)
+    }
+
     fn validate_chain_link(&self, block: &Block, previous: &Block) -> Result<()> {
-        if block.index != previous.index + 1 {
+        if previous.index.checked_add(1) != Some(block.index) {
             return Err(anyhow!("Invalid block index"));
         }
         
@@ -140,3 +252,9 @@ impl SecurityValidator {
         Ok(())
     }
 }
+
+impl Default for SecurityValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
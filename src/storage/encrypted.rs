@@ -0,0 +1,235 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use argon2::password_hash::{rand_core::RngCore, SaltString};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use zeroize::Zeroize;
+use crate::blockchain::Blockchain;
+use crate::utils::config::ConsensusParams;
+use crate::wallet::KdfParams;
+
+/// Valeur chiffrée avec la clé du fichier pour reconnaître un mauvais mot de passe
+const PASSPHRASE_CHECK: &[u8] = b"auriumchain-store";
+
+/// Chiffrement AES-256-GCM des valeurs stockées, clé dérivée par Argon2 comme pour
+/// `SecureWallet`. Chaque valeur a son propre nonce, placé devant le chiffré.
+pub struct StoreCipher {
+    cipher: Aes256Gcm,
+    salt: String,
+    kdf_params: KdfParams,
+}
+
+impl StoreCipher {
+    /// Nouvelle clé avec un sel aléatoire
+    pub fn new(passphrase: &str, kdf_params: KdfParams) -> Result<Self> {
+        let salt = SaltString::generate(&mut OsRng);
+        Self::with_salt(passphrase, salt.as_str(), kdf_params)
+    }
+
+    pub fn with_salt(passphrase: &str, salt: &str, kdf_params: KdfParams) -> Result<Self> {
+        let mut key = kdf_params.derive_key(passphrase, salt).map_err(|e| anyhow!(e))?;
+        let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow!("Cipher creation failed: {}", e));
+        key.zeroize();
+
+        Ok(StoreCipher { cipher: cipher?, salt: salt.to_string(), kdf_params })
+    }
+
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend(
+            self.cipher.encrypt(&Nonce::from(nonce), plaintext)
+                .map_err(|e| anyhow!("Encryption failed: {}", e))?,
+        );
+        Ok(sealed)
+    }
+
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < 12 {
+            return Err(anyhow!("Encrypted value too short"));
+        }
+        let (nonce, ciphertext) = sealed.split_at(12);
+        let nonce: [u8; 12] = nonce.try_into()?;
+        self.cipher.decrypt(&Nonce::from(nonce), ciphertext)
+            .map_err(|_| anyhow!("Decryption failed"))
+    }
+}
+
+impl fmt::Debug for StoreCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreCipher")
+            .field("salt", &self.salt)
+            .field("kdf_params", &self.kdf_params)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Bloc stocké : hauteur et hash en clair pour le parcours, contenu chiffré
+#[derive(Serialize, Deserialize)]
+struct EncryptedEntry {
+    index: u64,
+    hash: String,
+    data: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EncryptedStore {
+    kdf_params: KdfParams,
+    salt: String,
+    check: String,
+    blocks: Vec<EncryptedEntry>,
+}
+
+impl Blockchain {
+    /// Chiffrer désormais chaque sauvegarde (`save_to_file`) avec une clé dérivée de `passphrase`
+    pub fn encrypt_storage(&mut self, passphrase: &str, kdf_params: KdfParams) -> Result<()> {
+        self.store_cipher = Some(Arc::new(StoreCipher::new(passphrase, kdf_params)?));
+        Ok(())
+    }
+
+    pub fn is_storage_encrypted(&self) -> bool {
+        self.store_cipher.is_some()
+    }
+
+    /// Sauvegarder la chaîne chiffrée avec une clé dérivée de `passphrase`
+    pub fn save_to_file_encrypted(&self, path: &str, passphrase: &str, kdf_params: KdfParams) -> Result<()> {
+        self.write_encrypted(path, &StoreCipher::new(passphrase, kdf_params)?)
+    }
+
+    pub(crate) fn write_encrypted(&self, path: &str, cipher: &StoreCipher) -> Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let blocks = self.chain.iter()
+            .map(|block| Ok(EncryptedEntry {
+                index: block.index,
+                hash: block.hash.clone(),
+                data: hex::encode(cipher.seal(&serde_json::to_vec(block)?)?),
+            }))
+            .collect::<Result<Vec<_>>>()?;
+        let store = EncryptedStore {
+            kdf_params: cipher.kdf_params,
+            salt: cipher.salt.clone(),
+            check: hex::encode(cipher.seal(PASSPHRASE_CHECK)?),
+            blocks,
+        };
+
+        fs::write(path, serde_json::to_string_pretty(&store)?)?;
+        println!("Blockchain saved (encrypted): {} blocks to {}", self.chain.len(), path);
+        Ok(())
+    }
+
+    /// Charger une chaîne chiffrée ; mêmes contrôles que `load_from_file_with_params`.
+    /// Les sauvegardes suivantes de la chaîne chargée restent chiffrées avec la même clé.
+    pub fn load_from_file_encrypted(path: &str, passphrase: &str, params: ConsensusParams) -> Result<Self> {
+        if !Path::new(path).exists() {
+            println!("No blockchain file found, creating new encrypted chain");
+            let mut blockchain = Self::with_params(params);
+            blockchain.encrypt_storage(passphrase, KdfParams::default())?;
+            return Ok(blockchain);
+        }
+
+        let store: EncryptedStore = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let cipher = StoreCipher::with_salt(passphrase, &store.salt, store.kdf_params)?;
+
+        let check = hex::decode(&store.check).map_err(|e| anyhow!("Invalid passphrase check: {}", e))?;
+        if cipher.open(&check).ok().as_deref() != Some(PASSPHRASE_CHECK) {
+            return Err(anyhow!("Wrong passphrase for {}", path));
+        }
+
+        let mut entries = Vec::with_capacity(store.blocks.len());
+        for (height, entry) in store.blocks.iter().enumerate() {
+            let value: serde_json::Value = hex::decode(&entry.data).ok()
+                .and_then(|sealed| cipher.open(&sealed).ok())
+                .and_then(|plaintext| serde_json::from_slice(&plaintext).ok())
+                .ok_or_else(|| anyhow!("Corrupt block at height {}: cannot decrypt", height))?;
+            if value["hash"] != entry.hash.as_str() || value["index"] != entry.index {
+                return Err(anyhow!("Corrupt block at height {}: key does not match its value", height));
+            }
+            entries.push(value);
+        }
+
+        let mut blockchain = Self::from_entries(entries, params)?;
+        blockchain.store_cipher = Some(Arc::new(cipher));
+        println!("Blockchain loaded (encrypted): {} blocks from {}", blockchain.chain.len(), path);
+        Ok(blockchain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::genesis::create_genesis_block_with_params;
+
+    #[test]
+    fn test_encrypted_store_hides_blocks_and_round_trips() {
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        for miner in ["AUR1Alice", "AUR1Bob"] {
+            chain.mine_pending_transactions(miner.to_string());
+        }
+
+        let path = std::env::temp_dir()
+            .join(format!("aur_encrypted_{}.json", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let fast = KdfParams { memory_kib: 8, iterations: 1, parallelism: 1 };
+        chain.save_to_file_encrypted(&path, "correct horse", fast).unwrap();
+
+        // Sur disque : hauteurs et hashs lisibles, contenu des blocs illisible
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(raw.contains(&chain.chain[2].hash));
+        assert!(!raw.contains("AUR1Alice"));
+        assert!(!raw.contains(&chain.chain[1].merkle_root));
+        assert!(!raw.contains(&chain.chain[1].transactions[0].id));
+
+        let loaded = Blockchain::load_from_file_encrypted(&path, "correct horse", params.clone()).unwrap();
+        assert_eq!(loaded.chain.len(), 3);
+        assert_eq!(loaded.chain[1].miner_address, "AUR1Alice");
+        assert_eq!(loaded.chain[2].hash, chain.chain[2].hash);
+
+        let err = Blockchain::load_from_file_encrypted(&path, "wrong", params).unwrap_err();
+        assert!(err.to_string().contains("Wrong passphrase"));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_save_to_file_stays_encrypted_once_enabled() {
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        let fast = KdfParams { memory_kib: 8, iterations: 1, parallelism: 1 };
+        chain.encrypt_storage("correct horse", fast).unwrap();
+        assert!(chain.is_storage_encrypted());
+
+        let path = std::env::temp_dir()
+            .join(format!("aur_encrypted_saves_{}.json", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        chain.save_to_file(&path).unwrap();
+        assert!(Blockchain::load_from_file_with_params(&path, params.clone()).is_err());
+
+        // Chaîne rechargée : la sauvegarde suivante (minage, sync, RPC, arrêt) reste chiffrée
+        let mut loaded = Blockchain::load_from_file_encrypted(&path, "correct horse", params.clone()).unwrap();
+        assert!(loaded.is_storage_encrypted());
+        loaded.mine_pending_transactions("AUR1Alice".to_string());
+        loaded.save_to_file(&path).unwrap();
+        assert!(!fs::read_to_string(&path).unwrap().contains("AUR1Alice"));
+
+        let reloaded = Blockchain::load_from_file_encrypted(&path, "correct horse", params.clone()).unwrap();
+        assert_eq!(reloaded.chain.len(), 2);
+        assert_eq!(reloaded.chain[1].miner_address, "AUR1Alice");
+        assert!(Blockchain::load_from_file_encrypted(&path, "wrong", params).is_err());
+        let _ = fs::remove_file(&path);
+    }
+}
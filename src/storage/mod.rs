@@ -1,11 +1,32 @@
 pub mod db;
+pub mod encrypted;
+pub mod snapshot;
 
 use std::fs;
 use std::path::Path;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use crate::blockchain::Block;
+use crate::blockchain::genesis::{validate_genesis, LEGACY_GENESIS_HASH};
+use crate::utils::config::{ConsensusParams, GenesisConfig};
+
+/// Parcours bloc par bloc d'un fichier de chaîne (voir `Blockchain::scan_file`)
+pub struct ChainScan {
+    /// Plus long préfixe valide
+    pub valid: crate::blockchain::Blockchain,
+    /// Nombre de blocs présents dans le fichier
+    pub stored_blocks: usize,
+    /// Premier bloc invalide : hauteur et raison
+    pub first_invalid: Option<(usize, String)>,
+}
 
 impl crate::blockchain::Blockchain {
+    /// Sauvegarder la chaîne en JSON, chiffrée si une clé de stockage est active
+    /// (voir `encrypt_storage`)
     pub fn save_to_file(&self, path: &str) -> Result<()> {
+        if let Some(cipher) = &self.store_cipher {
+            return self.write_encrypted(path, cipher);
+        }
+        
         if let Some(parent) = Path::new(path).parent() {
             fs::create_dir_all(parent)?;
         }
@@ -17,18 +38,210 @@ impl crate::blockchain::Blockchain {
     }
 
     pub fn load_from_file(path: &str) -> Result<Self> {
+        Self::load_from_file_with_params(path, ConsensusParams::default())
+    }
+
+    /// Charger la chaîne sauvegardée. Un bloc illisible, une hauteur manquante ou un
+    /// chaînage rompu font échouer le chargement avec la hauteur fautive, plutôt que
+    /// de produire une chaîne tronquée d'apparence valide.
+    pub fn load_from_file_with_params(path: &str, params: ConsensusParams) -> Result<Self> {
         if !Path::new(path).exists() {
             println!("No blockchain file found, creating new chain");
-            return Ok(Self::new());
+            return Ok(Self::with_params(params));
         }
         
         let json_data = fs::read_to_string(path)?;
-        let blocks: Vec<crate::blockchain::Block> = serde_json::from_str(&json_data)?;
-        
-        let mut blockchain = Self::new();
-        blockchain.chain = blocks;
+        let blockchain = Self::from_entries(serde_json::from_str(&json_data)?, params)?;
         
         println!("Blockchain loaded: {} blocks from {}", blockchain.chain.len(), path);
         Ok(blockchain)
     }
+
+    /// Reconstituer la chaîne à partir des blocs sérialisés, dans l'ordre des hauteurs
+    pub(crate) fn from_entries(entries: Vec<serde_json::Value>, params: ConsensusParams) -> Result<Self> {
+        let mut blocks = Vec::with_capacity(entries.len());
+        for (height, entry) in entries.into_iter().enumerate() {
+            let block: Block = serde_json::from_value(entry)
+                .map_err(|e| anyhow!("Corrupt block at height {}: {}", height, e))?;
+            if block.index != height as u64 {
+                return Err(anyhow!("Missing block at height {} (found block {})", height, block.index));
+            }
+            blocks.push(block);
+        }
+        
+        let mut blockchain = Self::with_params(params);
+        blockchain.chain = blocks;
+        blockchain.verify_integrity()?;
+        Ok(blockchain)
+    }
+
+    /// Parcourir un fichier de chaîne sans s'arrêter à la première erreur de lecture :
+    /// hauteurs, hashes, chaînage et preuve de travail sont vérifiés bloc par bloc, et le
+    /// préfixe valide est conservé. Seul un fichier illisible dans son ensemble est une erreur.
+    pub fn scan_file(path: &str, params: ConsensusParams) -> Result<ChainScan> {
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let stored_blocks = entries.len();
+        let mut valid = Self::with_params(params);
+        let mut first_invalid = None;
+
+        for (height, entry) in entries.into_iter().enumerate() {
+            let checked = serde_json::from_value::<Block>(entry)
+                .map_err(|e| format!("unreadable block: {}", e))
+                .and_then(|block| {
+                    let reason = if block.index != height as u64 {
+                        format!("index {} at height {}", block.index, height)
+                    } else if block.hash != block.calculate_hash() {
+                        "hash does not match block contents".to_string()
+                    } else if !block.meets_difficulty() {
+                        "proof of work below block difficulty".to_string()
+                    } else if valid.chain.last().is_some_and(|previous| block.previous_hash != previous.hash) {
+                        format!("does not link to block {}", height - 1)
+                    } else {
+                        return Ok(block);
+                    };
+                    Err(reason)
+                });
+
+            match checked {
+                Ok(block) => valid.chain.push(block),
+                Err(reason) => {
+                    first_invalid = Some((height, reason));
+                    break;
+                },
+            }
+        }
+
+        Ok(ChainScan { valid, stored_blocks, first_invalid })
+    }
+
+    /// Le genesis chargé est celui de `config` (voir `validate_genesis`) ; sans quoi le
+    /// nœud partirait d'une histoire falsifiée. L'erreur dit comment repartir du bon genesis,
+    /// en particulier pour une chaîne enregistrée avant l'encodage canonique des transactions.
+    pub fn verify_genesis(&self, config: &GenesisConfig) -> Result<()> {
+        let Some(genesis) = self.chain.first() else {
+            return Ok(());
+        };
+        if genesis.hash == LEGACY_GENESIS_HASH {
+            return Err(anyhow!(
+                "chain starts from the genesis {} used before the canonical transaction encoding; \
+                 this history is no longer valid: move the data file aside and restart to resync from peers",
+                genesis.hash
+            ));
+        }
+        validate_genesis(genesis, config).map_err(|e| anyhow!(
+            "{} (stored genesis {}): the chain belongs to another network or genesis config; \
+             pass the matching --network / --genesis-config, or move the data file aside and restart to resync from peers",
+            e, genesis.hash
+        ))
+    }
+
+    /// Parcourir la chaîne : hauteurs consécutives, `previous_hash` égal au hash du
+    /// bloc précédent et hash de chaque bloc recalculable
+    pub fn verify_integrity(&self) -> Result<()> {
+        for (height, block) in self.chain.iter().enumerate() {
+            if block.index != height as u64 {
+                return Err(anyhow!("Block at height {} has index {}", height, block.index));
+            }
+            if block.hash != block.calculate_hash() {
+                return Err(anyhow!("Block at height {} has an invalid hash", height));
+            }
+            if let Some(previous) = height.checked_sub(1).map(|h| &self.chain[h]) {
+                if block.previous_hash != previous.hash {
+                    return Err(anyhow!("Block at height {} does not link to block {}", height, height - 1));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blockchain::Blockchain;
+    use crate::blockchain::genesis::create_genesis_block_with_params;
+    use crate::utils::config::ConsensusParams;
+
+    fn saved_chain(name: &str) -> (String, ConsensusParams) {
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        for miner in ["AUR1Alice", "AUR1Bob", "AUR1Carol"] {
+            chain.mine_pending_transactions(miner.to_string());
+        }
+
+        let path = std::env::temp_dir()
+            .join(format!("aur_{}_{}.json", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        chain.save_to_file(&path).unwrap();
+        (path, params)
+    }
+
+    fn rewrite(path: &str, edit: impl FnOnce(&mut Vec<serde_json::Value>)) {
+        let mut blocks: Vec<serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        edit(&mut blocks);
+        std::fs::write(path, serde_json::to_string(&blocks).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_load_reports_failing_height() {
+        let (path, params) = saved_chain("corrupt");
+        assert_eq!(Blockchain::load_from_file_with_params(&path, params.clone()).unwrap().chain.len(), 4);
+
+        rewrite(&path, |blocks| blocks[2]["nonce"] = serde_json::json!("garbage"));
+        let err = Blockchain::load_from_file_with_params(&path, params.clone()).unwrap_err();
+        assert!(err.to_string().contains("Corrupt block at height 2"), "{}", err);
+
+        let (path, params) = saved_chain("gap");
+        rewrite(&path, |blocks| { blocks.remove(1); });
+        let err = Blockchain::load_from_file_with_params(&path, params.clone()).unwrap_err();
+        assert!(err.to_string().contains("Missing block at height 1"), "{}", err);
+
+        let (path, params) = saved_chain("relinked");
+        rewrite(&path, |blocks| blocks[3]["previous_hash"] = serde_json::json!("f".repeat(64)));
+        let err = Blockchain::load_from_file_with_params(&path, params).unwrap_err();
+        assert!(err.to_string().contains("height 3"), "{}", err);
+
+        for name in ["corrupt", "gap", "relinked"] {
+            let _ = std::fs::remove_file(std::env::temp_dir().join(format!("aur_{}_{}.json", name, std::process::id())));
+        }
+    }
+
+    #[test]
+    fn test_tampered_genesis_refused_on_load() {
+        use crate::blockchain::genesis::create_genesis_block_with_config;
+        use crate::utils::config::GenesisConfig;
+
+        let (path, params) = saved_chain("genesis");
+        let config = GenesisConfig { difficulty: 1, ..GenesisConfig::default() };
+        let loaded = Blockchain::load_from_file_with_params(&path, params.clone()).unwrap();
+        assert!(loaded.verify_genesis(&config).is_ok());
+
+        // Genesis cohérent (hash et preuve de travail valides) mais d'un autre réseau
+        let forged = GenesisConfig { message: "Forged genesis".to_string(), ..config.clone() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_config(&forged, &params));
+        chain.save_to_file(&path).unwrap();
+
+        let loaded = Blockchain::load_from_file_with_params(&path, params).unwrap();
+        let err = loaded.verify_genesis(&config).unwrap_err();
+        assert!(err.to_string().contains("Genesis message"), "{}", err);
+        assert!(err.to_string().contains("--genesis-config"), "{}", err);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_legacy_genesis_refused_with_resync_hint() {
+        use crate::blockchain::genesis::{create_genesis_block_with_params, LEGACY_GENESIS_HASH};
+        use crate::utils::config::{ConsensusParams, GenesisConfig};
+
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        chain.chain[0].hash = LEGACY_GENESIS_HASH.to_string();
+
+        let err = chain.verify_genesis(&GenesisConfig { difficulty: 1, ..GenesisConfig::default() }).unwrap_err();
+        assert!(err.to_string().contains("canonical transaction encoding"), "{}", err);
+        assert!(err.to_string().contains("resync from peers"), "{}", err);
+    }
 }
@@ -0,0 +1,130 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use crate::blockchain::{Block, Blockchain, Utxo};
+use crate::p2p::compression::{self, Compression};
+use crate::utils::config::ConsensusParams;
+
+/// Version du format de snapshot
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// En-tête lisible d'un snapshot (première ligne du fichier, en JSON)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    pub version: u32,
+    pub height: u64,
+    pub tip_hash: String,
+    /// Taille de la charge décompressée
+    pub payload_size: usize,
+    /// SHA-256 hex de la charge décompressée
+    pub checksum: String,
+}
+
+#[derive(Serialize)]
+struct SnapshotPayload<'a> {
+    blocks: &'a [Block],
+    utxos: Vec<Utxo>,
+}
+
+#[derive(Deserialize)]
+struct StoredPayload {
+    blocks: Vec<serde_json::Value>,
+    utxos: Vec<Utxo>,
+}
+
+impl Blockchain {
+    /// Exporter la chaîne et l'ensemble des UTXO dans un seul fichier compressé (LZ4),
+    /// pour amorcer un nouveau nœud sans synchronisation P2P. Retourne l'en-tête écrit.
+    pub fn export_snapshot(&self, path: &str) -> Result<SnapshotHeader> {
+        let tip = self.get_latest_block().ok_or_else(|| anyhow!("Cannot snapshot an empty chain"))?;
+        let payload = serde_json::to_vec(&SnapshotPayload { blocks: &self.chain, utxos: self.utxo_set() })?;
+        let header = SnapshotHeader {
+            version: SNAPSHOT_VERSION,
+            height: tip.index,
+            tip_hash: tip.hash.clone(),
+            payload_size: payload.len(),
+            checksum: hex::encode(Sha256::digest(&payload)),
+        };
+
+        let mut data = serde_json::to_vec(&header)?;
+        data.push(b'\n');
+        data.extend(compression::encode_frame(&payload, Compression::Lz4));
+
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, data)?;
+        println!("Snapshot exported: height {} ({}) to {}", header.height, header.tip_hash, path);
+        Ok(header)
+    }
+
+    /// Importer un snapshot : somme de contrôle, intégrité de la chaîne (comme
+    /// `load_from_file_with_params`), sommet annoncé et ensemble des UTXO recalculé
+    pub fn import_snapshot(path: &str, params: ConsensusParams) -> Result<Self> {
+        let data = fs::read(path)?;
+        let separator = data.iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or_else(|| anyhow!("Snapshot {} has no header", path))?;
+        let header: SnapshotHeader = serde_json::from_slice(&data[..separator])?;
+        if header.version != SNAPSHOT_VERSION {
+            return Err(anyhow!("Unsupported snapshot version {}", header.version));
+        }
+
+        let payload = compression::decode_frame(&data[separator + 1..], header.payload_size)
+            .map_err(|e| anyhow!("Corrupt snapshot payload: {}", e))?;
+        if payload.len() != header.payload_size || hex::encode(Sha256::digest(&payload)) != header.checksum {
+            return Err(anyhow!("Snapshot checksum mismatch"));
+        }
+
+        let stored: StoredPayload = serde_json::from_slice(&payload)?;
+        let blockchain = Self::from_entries(stored.blocks, params)?;
+        match blockchain.get_latest_block() {
+            Some(tip) if tip.index == header.height && tip.hash == header.tip_hash => {},
+            _ => return Err(anyhow!("Snapshot tip does not match its header")),
+        }
+        if blockchain.utxo_set() != stored.utxos {
+            return Err(anyhow!("Snapshot UTXO set does not match its blocks"));
+        }
+
+        println!("Snapshot imported: height {} from {}", header.height, path);
+        Ok(blockchain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::genesis::create_genesis_block_with_params;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        for miner in ["AUR1Alice", "AUR1Bob", "AUR1Carol"] {
+            chain.mine_pending_transactions(miner.to_string());
+        }
+
+        let path = std::env::temp_dir()
+            .join(format!("aur_snapshot_{}.snap", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let header = chain.export_snapshot(&path).unwrap();
+        assert_eq!(header.height, 3);
+
+        let imported = Blockchain::import_snapshot(&path, params.clone()).unwrap();
+        assert_eq!(imported.get_latest_block().unwrap().hash, chain.get_latest_block().unwrap().hash);
+        assert_eq!(imported.utxo_set(), chain.utxo_set());
+        assert_eq!(imported.utxo_set().len(), 4);
+
+        // Un octet altéré dans la charge est détecté
+        let mut data = fs::read(&path).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        fs::write(&path, data).unwrap();
+        assert!(Blockchain::import_snapshot(&path, params).is_err());
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -1 +1,316 @@
-// Configuration management (à implémenter plus tard)
+use serde::{Deserialize, Serialize};
+use crate::mining::pow::PowAlgorithm;
+
+/// Réseau sur lequel tourne le nœud. Chaque réseau a ses propres versions
+/// d'adresse, son genesis, ses ports par défaut et son identifiant de handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+    Devnet,
+}
+
+impl Network {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+            Network::Devnet => "devnet",
+        }
+    }
+
+    /// Identifiant échangé dans le handshake P2P
+    pub fn network_id(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "auriumchain-mainnet",
+            Network::Testnet => "auriumchain-testnet",
+            Network::Devnet => "auriumchain-devnet",
+        }
+    }
+
+    /// Préfixe lisible des adresses, suivi du type (1, 2 ou 3) : AUR1…, TAR1…, DAR1…
+    pub fn address_prefix(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "AUR",
+            Network::Testnet => "TAR",
+            Network::Devnet => "DAR",
+        }
+    }
+
+    /// Octet de version d'une adresse de type `address_type` (1 à 4, 4 pour le multisig)
+    pub fn address_version(&self, address_type: u8) -> u8 {
+        let base = match self {
+            Network::Mainnet => 0x4F,
+            Network::Testnet => 0x6F,
+            Network::Devnet => 0x8F,
+        };
+        base + address_type.clamp(1, 4) - 1
+    }
+
+    pub fn default_p2p_port(&self) -> u16 {
+        match self {
+            Network::Mainnet => 3001,
+            Network::Testnet => 13001,
+            Network::Devnet => 23001,
+        }
+    }
+
+    pub fn default_rpc_port(&self) -> u16 {
+        match self {
+            Network::Mainnet => 8001,
+            Network::Testnet => 18001,
+            Network::Devnet => 28001,
+        }
+    }
+
+    /// Genesis par défaut du réseau (remplaçable par `--genesis-config`)
+    pub fn genesis_config(&self) -> GenesisConfig {
+        match self {
+            Network::Mainnet => GenesisConfig::mainnet(),
+            Network::Testnet => GenesisConfig {
+                timestamp: 1729468800,
+                difficulty: 3,
+                message: "AuriumChain Testnet Genesis".to_string(),
+                network: Network::Testnet,
+                ..GenesisConfig::mainnet()
+            },
+            Network::Devnet => GenesisConfig {
+                timestamp: 1729468800,
+                difficulty: 1,
+                message: "AuriumChain Devnet Genesis".to_string(),
+                network: Network::Devnet,
+                ..GenesisConfig::mainnet()
+            },
+        }
+    }
+}
+
+impl std::str::FromStr for Network {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            "devnet" => Ok(Network::Devnet),
+            other => Err(format!("unknown network '{}' (mainnet, testnet, devnet)", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Paramètres de consensus partagés par la validation, l'assemblage des blocs,
+/// le genesis et la synchronisation.
+///
+/// Construits une seule fois au démarrage : deux nœuds qui ne partagent pas
+/// exactement les mêmes valeurs produisent des chaînes incompatibles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusParams {
+    pub difficulty: u32,
+    pub target_bits: Option<u32>,
+    pub target_block_time: u64,
+    pub initial_reward: u64,
+    pub halving_interval: u64,
+    pub max_block_size: usize,
+    pub max_transactions_per_block: usize,
+    pub max_future_timestamp: i64,
+    pub coinbase_maturity: u64,
+    /// Profondeur maximale d'une réorganisation ; au-delà le fork est refusé
+    pub max_reorg_depth: u64,
+    /// Émission totale maximale (premine + subventions)
+    pub max_supply: u64,
+    pub network: Network,
+    /// Fonction de hash de la preuve de travail
+    #[serde(default)]
+    pub pow_algorithm: PowAlgorithm,
+}
+
+impl ConsensusParams {
+    pub fn mainnet() -> Self {
+        ConsensusParams {
+            difficulty: 4,
+            target_bits: None,
+            target_block_time: 30,
+            initial_reward: 50_0000_0000,   // 50 AUR
+            halving_interval: 4_204_800,    // ~4 ans à 30s/bloc
+            max_block_size: 4_000_000,
+            max_transactions_per_block: 10_000,
+            max_future_timestamp: 7200,
+            coinbase_maturity: crate::blockchain::genesis::COINBASE_MATURITY,
+            max_reorg_depth: crate::blockchain::genesis::MAX_REORG_DEPTH,
+            max_supply: crate::blockchain::genesis::TOTAL_SUPPLY,
+            network: Network::Mainnet,
+            pow_algorithm: PowAlgorithm::Sha256d,
+        }
+    }
+
+    /// Récompense de bloc (subvention) à une hauteur donnée, après halvings
+    pub fn block_reward(&self, block_height: u64) -> u64 {
+        let halvings = block_height / self.halving_interval.max(1);
+        
+        if halvings >= 64 {
+            return 0;
+        }
+        
+        self.initial_reward >> halvings
+    }
+
+    /// Difficulté `(difficulty, target_bits)` exigée de tout bloc après le genesis.
+    /// Fixe pour un réseau (pas de réajustement) : un bloc qui annonce une autre
+    /// difficulté, même plus basse et correctement minée, est refusé.
+    pub fn required_difficulty(&self) -> (u32, Option<u32>) {
+        (self.difficulty, self.target_bits)
+    }
+
+    /// Subvention à une hauteur donnée, réduite pour que l'émission
+    /// (`already_issued` compris) ne dépasse jamais `max_supply`
+    pub fn capped_reward(&self, block_height: u64, already_issued: u64) -> u64 {
+        self.block_reward(block_height)
+            .min(self.max_supply.saturating_sub(already_issued))
+    }
+}
+
+impl Default for ConsensusParams {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+/// Output pré-miné inscrit dans la transaction genesis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenesisOutput {
+    pub address: String,
+    pub value: u64,
+}
+
+/// Définition du bloc genesis d'un réseau (mainnet, testnet, devnet).
+///
+/// Chargée depuis un fichier TOML ; les champs absents prennent la valeur mainnet.
+/// Le calendrier d'émission (`initial_reward`, `halving_interval`, `max_supply`)
+/// n'est pas inscrit dans le genesis : il alimente les `ConsensusParams` du réseau
+/// via `consensus_params`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenesisConfig {
+    pub timestamp: i64,
+    pub initial_reward: u64,
+    pub halving_interval: u64,
+    pub max_supply: u64,
+    pub premine: Vec<GenesisOutput>,
+    pub difficulty: u32,
+    pub message: String,
+    pub miner_address: String,
+    /// Réseau auquel appartient ce genesis (n'entre pas dans le hash du bloc)
+    pub network: Network,
+    /// `"memory-hard"` pour une preuve de travail Argon2 résistante aux ASIC
+    pub pow_algorithm: PowAlgorithm,
+}
+
+impl GenesisConfig {
+    pub fn mainnet() -> Self {
+        GenesisConfig {
+            timestamp: 1729382400,
+            initial_reward: 50_0000_0000,
+            halving_interval: 4_204_800,
+            max_supply: crate::blockchain::genesis::TOTAL_SUPPLY,
+            premine: Vec::new(),
+            difficulty: 4,
+            message: "AuriumChain Genesis - October 20, 2025 - Autonomous & Decentralized".to_string(),
+            miner_address: "GENESIS_ANONYMOUS".to_string(),
+            network: Network::Mainnet,
+            pow_algorithm: PowAlgorithm::Sha256d,
+        }
+    }
+
+    pub fn from_toml_str(content: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_toml_str(&content)
+    }
+
+    /// Paramètres de consensus du réseau décrit par ce genesis
+    pub fn consensus_params(&self) -> ConsensusParams {
+        ConsensusParams {
+            difficulty: self.difficulty,
+            initial_reward: self.initial_reward,
+            halving_interval: self.halving_interval,
+            max_supply: self.max_supply,
+            network: self.network,
+            pow_algorithm: self.pow_algorithm,
+            ..ConsensusParams::default()
+        }
+    }
+}
+
+impl Default for GenesisConfig {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genesis_config_from_toml() {
+        let config = GenesisConfig::from_toml_str(
+            r#"
+            timestamp = 1735689600
+            initial_reward = 1000
+            halving_interval = 10
+            message = "devnet"
+
+            [[premine]]
+            address = "AUR1Faucet"
+            value = 500
+            "#,
+        ).unwrap();
+
+        assert_eq!(config.timestamp, 1735689600);
+        assert_eq!(config.premine, vec![GenesisOutput { address: "AUR1Faucet".to_string(), value: 500 }]);
+        // Champs absents : valeurs mainnet
+        assert_eq!(config.difficulty, GenesisConfig::mainnet().difficulty);
+        assert_eq!(config.consensus_params().initial_reward, 1000);
+        assert_eq!(config.consensus_params().halving_interval, 10);
+        assert_eq!(config.consensus_params().max_supply, GenesisConfig::mainnet().max_supply);
+        assert_eq!(config.consensus_params().pow_algorithm, PowAlgorithm::Sha256d);
+
+        let config = GenesisConfig::from_toml_str(r#"pow_algorithm = "memory-hard""#).unwrap();
+        assert_eq!(config.consensus_params().pow_algorithm, PowAlgorithm::MemoryHard);
+    }
+
+    #[test]
+    fn test_networks_are_distinct() {
+        assert_eq!("testnet".parse::<Network>(), Ok(Network::Testnet));
+        assert!("regtest".parse::<Network>().is_err());
+
+        let testnet = Network::Testnet.genesis_config();
+        assert_eq!(testnet.consensus_params().network, Network::Testnet);
+        assert_ne!(testnet.message, GenesisConfig::mainnet().message);
+        assert_ne!(Network::Testnet.default_p2p_port(), Network::Mainnet.default_p2p_port());
+        assert_ne!(Network::Testnet.address_version(1), Network::Mainnet.address_version(1));
+
+        let config = GenesisConfig::from_toml_str(r#"network = "devnet""#).unwrap();
+        assert_eq!(config.network, Network::Devnet);
+    }
+
+    #[test]
+    fn test_block_reward_halving() {
+        let params = ConsensusParams::mainnet();
+        
+        assert_eq!(params.block_reward(0), 50_0000_0000);
+        assert_eq!(params.block_reward(params.halving_interval), 25_0000_0000);
+        assert_eq!(params.block_reward(params.halving_interval * 64), 0);
+    }
+}
@@ -0,0 +1,40 @@
+/// Couper `s` après `at` caractères (et non octets) : ne panique jamais,
+/// même si `s` est plus court ou contient des caractères multi-octets
+pub fn split_at_chars(s: &str, at: usize) -> (&str, &str) {
+    match s.char_indices().nth(at) {
+        Some((index, _)) => s.split_at(index),
+        None => (s, ""),
+    }
+}
+
+/// Aperçu des `max_chars` premiers caractères de `s` (ids, adresses, dates)
+pub fn truncate(s: &str, max_chars: usize) -> &str {
+    split_at_chars(s, max_chars).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_id_not_truncated() {
+        assert_eq!(truncate("genesis", 16), "genesis");
+        assert_eq!(truncate("", 16), "");
+        assert_eq!(truncate("0123456789abcdef0123", 16), "0123456789abcdef");
+    }
+
+    #[test]
+    fn test_short_address_split() {
+        assert_eq!(split_at_chars("AUR1abc", 40), ("AUR1abc", ""));
+        let address = format!("AUR3{}", "x".repeat(46));
+        let (first, rest) = split_at_chars(&address, 40);
+        assert_eq!(first.len(), 40);
+        assert_eq!(rest, "x".repeat(10));
+    }
+
+    #[test]
+    fn test_multibyte_respects_char_boundaries() {
+        assert_eq!(truncate("éèà€", 2), "éè");
+        assert_eq!(split_at_chars("€€€", 1), ("€", "€€"));
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Compteurs du nœud depuis son démarrage, exposés par `/metrics`.
+/// Partagés sans verrou entre les tâches de mining, de synchronisation et le RPC.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Blocs minés localement et ajoutés à la chaîne
+    pub blocks_mined: AtomicU64,
+    /// Synchronisations avec un peer qui ont échoué (hauteur, téléchargement ou bloc invalide)
+    pub sync_errors: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_block_mined(&self) {
+        self.blocks_mined.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_sync_error(&self) {
+        self.sync_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn blocks_mined(&self) -> u64 {
+        self.blocks_mined.load(Ordering::Relaxed)
+    }
+
+    pub fn sync_errors(&self) -> u64 {
+        self.sync_errors.load(Ordering::Relaxed)
+    }
+}
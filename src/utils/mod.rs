@@ -1,2 +1,4 @@
 pub mod crypto;
 pub mod config;
+pub mod display;
+pub mod metrics;
@@ -1,16 +1,28 @@
 use sha2::{Digest, Sha256};
 use ripemd::Ripemd160;
+use crate::utils::config::Network;
 
-pub fn generate_address(public_key: &[u8]) -> String {
+/// Type d'adresse d'une clé publique unique (`AUR1`)
+pub const SINGLE_KEY_ADDRESS_TYPE: u8 = 1;
+/// Type d'adresse d'un script multisig M-of-N (`AUR4`, voir `MultisigScript`)
+pub const MULTISIG_ADDRESS_TYPE: u8 = 4;
+
+/// Adresse de type 1 (clé publique unique) sur `network`
+pub fn generate_address(public_key: &[u8], network: Network) -> String {
+    encode_address(public_key, SINGLE_KEY_ADDRESS_TYPE, network)
+}
+
+/// Adresse de type `address_type` pour `data` (clé publique ou script) sur `network`
+pub(crate) fn encode_address(data: &[u8], address_type: u8, network: Network) -> String {
     // Double SHA-256
-    let hash1 = Sha256::digest(public_key);
+    let hash1 = Sha256::digest(data);
     let hash2 = Sha256::digest(&hash1);
     
     // RIPEMD-160
     let hash160 = Ripemd160::digest(&hash2);
     
-    // Ajouter préfixe réseau (AUR1)
-    let mut payload = vec![0x4F]; // 'O' en ASCII = AUR1
+    // Ajouter l'octet de version du réseau (0x4F = AUR1 sur mainnet)
+    let mut payload = vec![network.address_version(address_type)];
     payload.extend_from_slice(&hash160);
     
     // Calculer checksum (4 premiers bytes du double SHA-256)
@@ -21,34 +33,56 @@ pub fn generate_address(public_key: &[u8]) -> String {
     // Encoder en Base58
     let encoded = bs58::encode(payload).into_string();
     
-    format!("AUR1{}", encoded)
+    format!("{}{}{}", network.address_prefix(), address_type, encoded)
 }
 
-pub fn validate_address(address: &str) -> bool {
-    // Vérifier le préfixe (AUR1, AUR2 ou AUR3)
-    if !address.starts_with("AUR1") && !address.starts_with("AUR2") && !address.starts_with("AUR3") {
-        return false;
-    }
+/// Adresse des `SecureWallet` (`AUR1`, `AUR2`, `AUR3`) : double SHA-256 tronqué à 20 octets,
+/// sans RIPEMD-160, avec les octets de version mainnet. Un output payé à cette adresse
+/// reste dépensable par sa clé (voir `SignatureValidator::verify_spend`).
+pub fn wallet_address(public_key: &[u8], addr_type: &str) -> String {
+    let hash1 = Sha256::digest(public_key);
+    let hash2 = Sha256::digest(&hash1);
+    
+    let hash160 = &hash2[0..20];
+    
+    let version_byte = match addr_type {
+        "AUR1" => 0x4F,
+        "AUR2" => 0x50,
+        "AUR3" => 0x51,
+        _ => 0x4F,
+    };
+    
+    let mut payload = vec![version_byte];
+    payload.extend_from_slice(hash160);
+    
+    let checksum_hash = Sha256::digest(&Sha256::digest(&payload));
+    let checksum = &checksum_hash[0..4];
+    payload.extend_from_slice(checksum);
+    
+    let encoded = bs58::encode(payload).into_string();
+    
+    format!("{}{}", addr_type, encoded)
+}
 
-    // Extraire la partie après le préfixe
-    let addr_without_prefix = if address.starts_with("AUR1") {
-        &address[4..]
-    } else if address.starts_with("AUR2") {
-        &address[4..]
-    } else if address.starts_with("AUR3") {
-        &address[4..]
-    } else {
+/// Valider une adresse pour `network` : préfixe et octet de version du réseau, checksum
+pub fn validate_address(address: &str, network: Network) -> bool {
+    // Vérifier le préfixe du réseau suivi du type (1 à 4)
+    let Some(rest) = address.strip_prefix(network.address_prefix()) else {
         return false;
     };
+    let address_type = match rest.as_bytes().first() {
+        Some(digit @ b'1'..=b'4') => digit - b'0',
+        _ => return false,
+    };
 
     // Décoder Base58
-    let decoded = match bs58::decode(addr_without_prefix).into_vec() {
+    let decoded = match bs58::decode(&rest[1..]).into_vec() {
         Ok(bytes) => bytes,
         Err(_) => return false,
     };
 
-    // Doit avoir au moins 25 bytes (1 version + 20 hash + 4 checksum)
-    if decoded.len() < 25 {
+    // 1 version + 20 hash + 4 checksum
+    if decoded.len() != 25 || decoded[0] != network.address_version(address_type) {
         return false;
     }
 
@@ -64,9 +98,9 @@ pub fn validate_address(address: &str) -> bool {
     checksum == expected_checksum
 }
 
-pub fn address_from_keypair(keypair: &crate::wallet::keys::KeyPair) -> String {
+pub fn address_from_keypair(keypair: &crate::wallet::keys::KeyPair, network: Network) -> String {
     let public_key = keypair.public_key.serialize();
-    generate_address(&public_key)
+    generate_address(&public_key, network)
 }
 
 #[cfg(test)]
@@ -83,16 +117,32 @@ mod tests {
             0x6c
         ];
         
-        let address = generate_address(&public_key);
+        let address = generate_address(&public_key, Network::Mainnet);
         
         assert!(address.starts_with("AUR1"));
-        assert!(validate_address(&address));
+        assert!(validate_address(&address, Network::Mainnet));
     }
 
     #[test]
     fn test_invalid_address() {
-        assert!(!validate_address("BTC1qxyz..."));
-        assert!(!validate_address("invalid"));
-        assert!(!validate_address(""));
+        assert!(!validate_address("BTC1qxyz...", Network::Mainnet));
+        assert!(!validate_address("invalid", Network::Mainnet));
+        assert!(!validate_address("", Network::Mainnet));
+    }
+
+    #[test]
+    fn test_addresses_bound_to_their_network() {
+        let public_key = [0x03; 33];
+        let mainnet = generate_address(&public_key, Network::Mainnet);
+        let testnet = generate_address(&public_key, Network::Testnet);
+
+        assert!(testnet.starts_with("TAR1"));
+        assert!(validate_address(&testnet, Network::Testnet));
+        assert!(!validate_address(&testnet, Network::Mainnet));
+        assert!(!validate_address(&mainnet, Network::Testnet));
+
+        // Même encodage sous le préfixe mainnet : l'octet de version trahit le testnet
+        let relabeled = format!("AUR{}", &testnet[3..]);
+        assert!(!validate_address(&relabeled, Network::Mainnet));
     }
 }
\ No newline at end of file
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Carnet d'adresses d'un wallet : contacts (libellé → adresse) et libellés de
+/// transactions. Stocké chiffré dans le fichier du `SecureWallet`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AddressBook {
+    pub contacts: BTreeMap<String, String>,
+    /// Libellé libre par txid
+    pub tx_labels: BTreeMap<String, String>,
+}
+
+impl AddressBook {
+    pub fn get(&self, label: &str) -> Option<&str> {
+        self.contacts.get(label).map(String::as_str)
+    }
+
+    /// Libellé du contact associé à `address`, s'il existe
+    pub fn label_for(&self, address: &str) -> Option<&str> {
+        self.contacts.iter()
+            .find(|(_, contact)| contact.as_str() == address)
+            .map(|(label, _)| label.as_str())
+    }
+
+    /// Nom à afficher pour une adresse : le libellé du contact, sinon l'adresse brute
+    pub fn display_name<'a>(&'a self, address: &'a str) -> &'a str {
+        self.label_for(address).unwrap_or(address)
+    }
+
+    pub fn tx_label(&self, txid: &str) -> Option<&str> {
+        self.tx_labels.get(txid).map(String::as_str)
+    }
+}
@@ -0,0 +1,168 @@
+use crate::blockchain::fees::TransactionFees;
+use crate::blockchain::{Transaction, TxInput, TxOutput, Utxo};
+
+/// Monnaie en dessous de laquelle un output coûte plus cher à dépenser qu'il ne vaut
+pub const DUST_THRESHOLD: u64 = 546;
+
+/// Longueurs hex d'une signature compacte secp256k1 et d'une clé publique compressée
+const SIGNATURE_HEX_LEN: usize = 128;
+const PUBLIC_KEY_HEX_LEN: usize = 66;
+
+/// Construction d'une dépense : sélection des UTXO (plus gros d'abord), monnaie rendue
+/// à l'expéditeur et frais calculés sur la taille de la transaction une fois signée
+pub struct WalletTxBuilder {
+    pub fees: TransactionFees,
+    /// Une monnaie inférieure ou égale est abandonnée aux frais plutôt que créée
+    pub dust_threshold: u64,
+}
+
+impl Default for WalletTxBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WalletTxBuilder {
+    pub fn new() -> Self {
+        WalletTxBuilder {
+            fees: TransactionFees::new(),
+            dust_threshold: DUST_THRESHOLD,
+        }
+    }
+
+    /// Transaction non signée envoyant `amount` à `to_address` au taux `fee_rate` (satoshis/byte).
+    /// La monnaie revient à l'adresse du premier UTXO sélectionné ; chaque input reste à signer
+    /// avec `Transaction::sign_input`.
+    pub fn build(
+        &self,
+        from_utxos: &[Utxo],
+        to_address: &str,
+        amount: u64,
+        fee_rate: u64,
+    ) -> Result<Transaction, String> {
+        if amount <= self.dust_threshold {
+            return Err(format!("Amount {} is below the dust threshold {}", amount, self.dust_threshold));
+        }
+
+        let mut candidates: Vec<&Utxo> = from_utxos.iter().collect();
+        candidates.sort_by_key(|utxo| std::cmp::Reverse(utxo.value));
+
+        let payment = TxOutput { value: amount, address: to_address.to_string() };
+        let mut selected: Vec<&Utxo> = Vec::new();
+        let mut total = 0u64;
+
+        for utxo in candidates {
+            selected.push(utxo);
+            total = total.saturating_add(utxo.value);
+
+            let change = TxOutput { value: 0, address: selected[0].address.clone() };
+            let with_change = self.fee_for(&selected, &[payment.clone(), change.clone()], fee_rate);
+            if let Some(change_value) = total.checked_sub(amount.saturating_add(with_change)) {
+                if change_value > self.dust_threshold {
+                    let change = TxOutput { value: change_value, ..change };
+                    return Ok(Self::unsigned(&selected, vec![payment, change]));
+                }
+            }
+
+            // Monnaie nulle ou poussière : laissée aux frais
+            let without_change = self.fee_for(&selected, std::slice::from_ref(&payment), fee_rate);
+            if total >= amount.saturating_add(without_change) {
+                return Ok(Self::unsigned(&selected, vec![payment]));
+            }
+        }
+
+        let needed = amount.saturating_add(self.fee_for(&selected, std::slice::from_ref(&payment), fee_rate));
+        Err(format!("Insufficient funds: available {}, required {}", total, needed))
+    }
+
+    /// Frais d'une transaction dépensant `inputs` vers `outputs`, une fois signée
+    pub fn fee_for(&self, inputs: &[&Utxo], outputs: &[TxOutput], fee_rate: u64) -> u64 {
+        let mut draft = Self::unsigned(inputs, outputs.to_vec());
+        for input in &mut draft.inputs {
+            input.signature = "0".repeat(SIGNATURE_HEX_LEN);
+            input.public_key = "0".repeat(PUBLIC_KEY_HEX_LEN);
+        }
+        let size = bincode::serialize(&draft).map_or(0, |bytes| bytes.len());
+        self.fees.fee_for_rate(size, fee_rate)
+    }
+
+    fn unsigned(inputs: &[&Utxo], outputs: Vec<TxOutput>) -> Transaction {
+        let inputs = inputs.iter()
+            .map(|utxo| TxInput {
+                prev_tx_id: utxo.tx_id.clone(),
+                output_index: utxo.output_index,
+                signature: String::new(),
+                public_key: String::new(),
+            })
+            .collect();
+        Transaction::new(inputs, outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::keys::KeyPair;
+
+    fn utxo(n: u64, value: u64) -> Utxo {
+        Utxo {
+            tx_id: format!("{:064x}", n),
+            output_index: 0,
+            value,
+            address: "AUR1Sender".to_string(),
+        }
+    }
+
+    fn signed_size(mut tx: Transaction) -> u64 {
+        let keys = KeyPair::generate();
+        for index in 0..tx.inputs.len() {
+            keys.sign_transaction_input(&mut tx, index).unwrap();
+        }
+        bincode::serialize(&tx).unwrap().len() as u64
+    }
+
+    #[test]
+    fn test_exact_amount_has_no_change() {
+        let builder = WalletTxBuilder::new();
+        let payment = TxOutput { value: 100_000, address: "AUR1Receiver".to_string() };
+        let fee = builder.fee_for(&[&utxo(1, 0)], &[payment], 10);
+
+        let tx = builder.build(&[utxo(1, 100_000 + fee)], "AUR1Receiver", 100_000, 10).unwrap();
+        assert_eq!(tx.inputs.len(), 1);
+        assert_eq!(tx.outputs.len(), 1);
+        assert_eq!(tx.outputs[0].value, 100_000);
+        assert_eq!(signed_size(tx) * 10, fee);
+    }
+
+    #[test]
+    fn test_change_returned_to_sender() {
+        let builder = WalletTxBuilder::new();
+        let utxos = [utxo(1, 30_000), utxo(2, 500_000), utxo(3, 400_000)];
+
+        let tx = builder.build(&utxos, "AUR1Receiver", 700_000, 10).unwrap();
+        // Plus gros d'abord : 500 000 puis 400 000
+        let spent: Vec<&str> = tx.inputs.iter().map(|input| input.prev_tx_id.as_str()).collect();
+        assert_eq!(spent, vec![utxos[1].tx_id.as_str(), utxos[2].tx_id.as_str()]);
+
+        assert_eq!(tx.outputs.len(), 2);
+        assert_eq!(tx.outputs[1].address, "AUR1Sender");
+        let fee = 900_000 - tx.total_output();
+        assert!(fee >= signed_size(tx) * 10);
+
+        // Une monnaie de poussière part dans les frais
+        let payment = TxOutput { value: 100_000, address: "AUR1Receiver".to_string() };
+        let fee = builder.fee_for(&[&utxo(1, 0)], &[payment], 10);
+        let tx = builder.build(&[utxo(1, 100_000 + fee + 300)], "AUR1Receiver", 100_000, 10).unwrap();
+        assert_eq!(tx.outputs.len(), 1);
+    }
+
+    #[test]
+    fn test_insufficient_funds() {
+        let builder = WalletTxBuilder::new();
+        let err = builder.build(&[utxo(1, 50_000), utxo(2, 40_000)], "AUR1Receiver", 90_000, 10).unwrap_err();
+        assert!(err.contains("Insufficient funds"), "{}", err);
+
+        assert!(builder.build(&[], "AUR1Receiver", 1_000, 10).is_err());
+        assert!(builder.build(&[utxo(1, 50_000)], "AUR1Receiver", DUST_THRESHOLD, 10).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+/// Erreur d'un wallet chiffré (`SecureWallet`), distinguée par cause pour que
+/// la CLI et le RPC puissent réagir (redemander le mot de passe, refuser un fichier...)
+#[derive(Debug)]
+pub enum WalletError {
+    /// Le mot de passe ne déchiffre pas le wallet (l'intégrité du fichier est vérifiée)
+    WrongPassword,
+    /// Empreinte d'intégrité invalide : fichier modifié ou corrompu
+    IntegrityFailure,
+    /// Contenu déchiffré inutilisable
+    DecryptionFailed(String),
+    /// Seed ou clé privée invalide
+    InvalidKey(String),
+    /// Échec de dérivation de clé (Argon2) ou de chiffrement
+    Crypto(String),
+    /// Fichier illisible ou écrit dans un format inconnu
+    InvalidFormat(String),
+    /// Adresse de contact mal formée ou d'un autre réseau
+    InvalidAddress(String),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for WalletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WalletError::WrongPassword => write!(f, "Decryption failed! Wrong password?"),
+            WalletError::IntegrityFailure => write!(f, "SECURITY ALERT: Wallet has been tampered with!"),
+            WalletError::DecryptionFailed(e) => write!(f, "decryption failed: {}", e),
+            WalletError::InvalidKey(e) => write!(f, "invalid key: {}", e),
+            WalletError::Crypto(e) => write!(f, "crypto error: {}", e),
+            WalletError::InvalidFormat(e) => write!(f, "invalid wallet file: {}", e),
+            WalletError::InvalidAddress(address) => write!(f, "invalid address: {}", address),
+            WalletError::Io(e) => write!(f, "io error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WalletError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WalletError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for WalletError {
+    fn from(e: std::io::Error) -> Self {
+        WalletError::Io(e)
+    }
+}
@@ -1,12 +1,21 @@
-use secp256k1::{Secp256k1, SecretKey, PublicKey};
+use secp256k1::{Message, Secp256k1, SecretKey, PublicKey};
+use crate::blockchain::{SigHashType, Transaction};
+use crate::wallet::multisig::MultisigScript;
 use rand::rngs::OsRng;
 use sha2::Digest;
 
+/// Paire de clés secp256k1 ; la clé privée est effacée de la mémoire au drop
 pub struct KeyPair {
     pub private_key: SecretKey,
     pub public_key: PublicKey,
 }
 
+impl Drop for KeyPair {
+    fn drop(&mut self) {
+        self.private_key.non_secure_erase();
+    }
+}
+
 impl KeyPair {
     pub fn generate() -> Self {
         let secp = Secp256k1::new();
@@ -23,4 +32,76 @@ impl KeyPair {
     pub fn public_key_hex(&self) -> String {
         hex::encode(self.public_key.serialize())
     }
+
+    /// Signer l'input `index` de `tx` sur `Transaction::signing_hash`, le hash exact
+    /// que vérifie `SignatureValidator`, et y inscrire signature et clé publique
+    pub fn sign_transaction_input(&self, tx: &mut Transaction, index: usize) -> Result<(), String> {
+        if index >= tx.inputs.len() {
+            return Err(format!("Input {} out of range", index));
+        }
+
+        let message = Message::from_digest(tx.signing_hash(index));
+        let signature = Secp256k1::signing_only().sign_ecdsa(&message, &self.private_key);
+
+        let input = &mut tx.inputs[index];
+        input.signature = hex::encode(signature.serialize_compact());
+        input.public_key = self.public_key_hex();
+        Ok(())
+    }
+
+    /// Signer l'input `index` sur `Transaction::signing_hash_with` : l'octet `sighash`
+    /// suit la signature compacte, et seuls les inputs/outputs qu'il désigne sont engagés
+    pub fn sign_transaction_input_with(&self, tx: &mut Transaction, index: usize, sighash: SigHashType) -> Result<(), String> {
+        let digest = tx.signing_hash_with(index, sighash)
+            .ok_or_else(|| format!("Input {} cannot be signed with {:?}", index, sighash))?;
+        let signature = Secp256k1::signing_only().sign_ecdsa(&Message::from_digest(digest), &self.private_key);
+
+        let mut signature_bytes = signature.serialize_compact().to_vec();
+        signature_bytes.push(sighash.to_byte());
+        let input = &mut tx.inputs[index];
+        input.signature = hex::encode(signature_bytes);
+        input.public_key = self.public_key_hex();
+        Ok(())
+    }
+
+    /// Ajouter la signature de cette clé à l'input `index` qui dépense une adresse
+    /// multisig : l'input porte le script, et chaque cosignataire y ajoute son entrée
+    /// (index de sa clé dans le script + signature compacte)
+    pub fn sign_multisig_input(&self, tx: &mut Transaction, index: usize, script: &MultisigScript) -> Result<(), String> {
+        if index >= tx.inputs.len() {
+            return Err(format!("Input {} out of range", index));
+        }
+        let key_index = script.pubkeys.iter()
+            .position(|key| key.as_slice() == self.public_key.serialize().as_slice())
+            .ok_or("Key is not part of the multisig script")?;
+
+        let message = Message::from_digest(tx.signing_hash(index));
+        let signature = Secp256k1::signing_only().sign_ecdsa(&message, &self.private_key);
+
+        let input = &mut tx.inputs[index];
+        let script_hex = script.to_hex();
+        if input.public_key != script_hex {
+            input.public_key = script_hex;
+            input.signature.clear();
+        }
+        input.signature.push_str(&hex::encode([key_index as u8]));
+        input.signature.push_str(&hex::encode(signature.serialize_compact()));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::ManuallyDrop;
+
+    #[test]
+    fn test_private_key_erased_on_drop() {
+        let mut keypair = ManuallyDrop::new(KeyPair::generate());
+        let original = keypair.private_key;
+
+        // Le destructeur ne libère pas la mémoire de `ManuallyDrop` : on peut relire la clé
+        unsafe { ManuallyDrop::drop(&mut keypair) };
+        assert_ne!(keypair.private_key, original);
+    }
 }
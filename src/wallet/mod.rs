@@ -1,7 +1,19 @@
 pub mod address;
+pub mod address_book;
+pub mod builder;
+pub mod error;
 pub mod keys;
+pub mod multisig;
+pub mod node_wallet;
 pub mod quantum_resistant;
 pub mod secure_wallet;  // ← NOUVEAU
+pub mod watch_only;
 
+pub use address_book::AddressBook;
+pub use builder::WalletTxBuilder;
+pub use error::WalletError;
+pub use multisig::{generate_multisig_address, MultisigScript};
+pub use node_wallet::NodeWallet;
 pub use quantum_resistant::{AddressType, QuantumProtection, AddressGenerator};
-pub use secure_wallet::SecureWallet;  // ← NOUVEAU
+pub use secure_wallet::{KdfParams, SecureWallet};  // ← NOUVEAU
+pub use watch_only::WatchOnlyWallet;
@@ -0,0 +1,138 @@
+use secp256k1::PublicKey;
+use crate::utils::config::Network;
+use crate::wallet::address::{encode_address, MULTISIG_ADDRESS_TYPE};
+
+/// Premier octet d'un script multisig sérialisé : un input dont la clé publique
+/// (hex) commence par `ae` dépense une adresse M-of-N
+pub const MULTISIG_SCRIPT_MARKER: u8 = 0xAE;
+/// Nombre maximal de clés d'un script (comme Bitcoin en P2SH)
+pub const MAX_MULTISIG_KEYS: usize = 15;
+/// Clé publique non compressée : 65 octets
+const MAX_KEY_LEN: usize = 65;
+/// Une entrée de signature : index de la clé (1 octet) + signature compacte (64 octets)
+pub const MULTISIG_ENTRY_LEN: usize = 65;
+
+/// Script M-of-N : `m` signatures valides parmi les clés `pubkeys` sont requises pour dépenser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigScript {
+    pub m: u8,
+    pub pubkeys: Vec<Vec<u8>>,
+}
+
+impl MultisigScript {
+    /// Vérifier 1 <= m <= n <= `MAX_MULTISIG_KEYS`, des clés secp256k1 valides et distinctes
+    pub fn new(m: u8, pubkeys: Vec<Vec<u8>>) -> Result<Self, String> {
+        if pubkeys.is_empty() || pubkeys.len() > MAX_MULTISIG_KEYS {
+            return Err(format!("Multisig needs 1 to {} keys, got {}", MAX_MULTISIG_KEYS, pubkeys.len()));
+        }
+        if m == 0 || m as usize > pubkeys.len() {
+            return Err(format!("Invalid threshold {} of {}", m, pubkeys.len()));
+        }
+        for (index, key) in pubkeys.iter().enumerate() {
+            PublicKey::from_slice(key).map_err(|_| format!("Invalid public key {}", index))?;
+            if pubkeys[..index].contains(key) {
+                return Err(format!("Duplicate public key {}", index));
+            }
+        }
+
+        Ok(MultisigScript { m, pubkeys })
+    }
+
+    /// Sérialisation : marqueur, m, n, puis chaque clé précédée de sa longueur
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![MULTISIG_SCRIPT_MARKER, self.m, self.pubkeys.len() as u8];
+        for key in &self.pubkeys {
+            bytes.push(key.len() as u8);
+            bytes.extend_from_slice(key);
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let (&marker, rest) = bytes.split_first().ok_or("Empty multisig script")?;
+        if marker != MULTISIG_SCRIPT_MARKER {
+            return Err("Not a multisig script".to_string());
+        }
+        let [m, n, rest @ ..] = rest else {
+            return Err("Truncated multisig script".to_string());
+        };
+
+        let mut pubkeys = Vec::with_capacity(*n as usize);
+        let mut rest = rest;
+        for _ in 0..*n {
+            let (&len, tail) = rest.split_first().ok_or("Truncated multisig script")?;
+            let len = len as usize;
+            if len > MAX_KEY_LEN || tail.len() < len {
+                return Err("Truncated multisig script".to_string());
+            }
+            pubkeys.push(tail[..len].to_vec());
+            rest = &tail[len..];
+        }
+        if !rest.is_empty() {
+            return Err("Trailing bytes after multisig script".to_string());
+        }
+
+        Self::new(*m, pubkeys)
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    /// Adresse `AUR4` : hash du script sérialisé, comme une clé publique pour `AUR1`
+    pub fn address(&self, network: Network) -> String {
+        encode_address(&self.to_bytes(), MULTISIG_ADDRESS_TYPE, network)
+    }
+
+    /// Taille hex maximale d'un script, bornée avant décodage par `SignatureValidator`
+    pub const fn max_hex_len() -> usize {
+        (3 + MAX_MULTISIG_KEYS * (1 + MAX_KEY_LEN)) * 2
+    }
+}
+
+/// Adresse M-of-N sur `network` pour les clés `pubkeys`
+pub fn generate_multisig_address(m: u8, pubkeys: &[Vec<u8>], network: Network) -> Result<String, String> {
+    Ok(MultisigScript::new(m, pubkeys.to_vec())?.address(network))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::address::validate_address;
+    use crate::wallet::keys::KeyPair;
+
+    fn pubkeys(n: usize) -> Vec<Vec<u8>> {
+        (0..n).map(|_| KeyPair::generate().public_key.serialize().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_multisig_address_round_trip() {
+        let keys = pubkeys(3);
+        let address = generate_multisig_address(2, &keys, Network::Mainnet).unwrap();
+
+        assert!(address.starts_with("AUR4"));
+        assert!(validate_address(&address, Network::Mainnet));
+        assert!(!validate_address(&address, Network::Testnet));
+        assert_eq!(address, generate_multisig_address(2, &keys, Network::Mainnet).unwrap());
+        // Le seuil fait partie du script : 1-of-3 et 2-of-3 ne partagent pas d'adresse
+        assert_ne!(address, generate_multisig_address(1, &keys, Network::Mainnet).unwrap());
+
+        let script = MultisigScript::new(2, keys).unwrap();
+        assert_eq!(MultisigScript::from_bytes(&script.to_bytes()).unwrap(), script);
+        assert!(script.to_hex().len() <= MultisigScript::max_hex_len());
+    }
+
+    #[test]
+    fn test_invalid_multisig_scripts_rejected() {
+        let keys = pubkeys(3);
+        assert!(MultisigScript::new(0, keys.clone()).is_err());
+        assert!(MultisigScript::new(4, keys.clone()).is_err());
+        assert!(MultisigScript::new(1, vec![keys[0].clone(), keys[0].clone()]).is_err());
+        assert!(MultisigScript::new(1, vec![b"not a key".to_vec()]).is_err());
+
+        let mut bytes = MultisigScript::new(2, keys).unwrap().to_bytes();
+        bytes.push(0);
+        assert!(MultisigScript::from_bytes(&bytes).is_err());
+        assert!(MultisigScript::from_bytes(&bytes[..10]).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use zeroize::Zeroize;
+use crate::wallet::error::WalletError;
+use crate::wallet::keys::KeyPair;
+use crate::wallet::secure_wallet::SecureWallet;
+
+/// Durée par défaut pendant laquelle la clé déverrouillée reste en mémoire
+pub const DEFAULT_UNLOCK_TIMEOUT_SECS: u64 = 300;
+
+/// Wallet chiffré tenu par le nœud pour `/wallet/send`. La clé privée n'est en mémoire
+/// qu'entre `unlock` et l'expiration du délai (ou `lock`).
+pub struct NodeWallet {
+    wallet: SecureWallet,
+    unlocked: Mutex<Option<(SecretKey, Instant)>>,
+    pub unlock_timeout: Duration,
+}
+
+impl NodeWallet {
+    pub fn new(wallet: SecureWallet) -> Self {
+        NodeWallet {
+            wallet,
+            unlocked: Mutex::new(None),
+            unlock_timeout: Duration::from_secs(DEFAULT_UNLOCK_TIMEOUT_SECS),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.wallet.address
+    }
+
+    /// Déchiffrer la clé privée ; elle expire après `unlock_timeout`
+    pub fn unlock(&self, password: &str) -> Result<(), WalletError> {
+        let mut private_key = self.wallet.decrypt_private_key(password)?;
+        let secret_key = SecretKey::from_slice(&private_key)
+            .map_err(|e| WalletError::InvalidKey(e.to_string()));
+        private_key.zeroize();
+
+        let until = Instant::now() + self.unlock_timeout;
+        *self.unlocked.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((secret_key?, until));
+        Ok(())
+    }
+
+    pub fn lock(&self) {
+        let mut unlocked = self.unlocked.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        Self::erase(&mut unlocked);
+    }
+
+    fn erase(unlocked: &mut Option<(SecretKey, Instant)>) {
+        // Effacée sur place : `take` ne ferait que déplacer une copie
+        if let Some((secret_key, _)) = unlocked.as_mut() {
+            secret_key.non_secure_erase();
+        }
+        *unlocked = None;
+    }
+
+    /// Paire de clés si le wallet est déverrouillé et que le délai n'a pas expiré
+    pub fn keypair(&self) -> Option<KeyPair> {
+        let mut unlocked = self.unlocked.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match *unlocked {
+            Some((secret_key, until)) if Instant::now() < until => Some(KeyPair {
+                private_key: secret_key,
+                public_key: PublicKey::from_secret_key(&Secp256k1::signing_only(), &secret_key),
+            }),
+            _ => {
+                Self::erase(&mut unlocked);
+                None
+            }
+        }
+    }
+}
+
+impl Drop for NodeWallet {
+    fn drop(&mut self) {
+        self.lock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::KdfParams;
+    use bip39::Mnemonic;
+
+    #[test]
+    fn test_unlock_expires_after_timeout() {
+        let fast = KdfParams { memory_kib: 1024, iterations: 1, parallelism: 1 };
+        let mnemonic = Mnemonic::from_entropy(&[3u8; 32]).unwrap();
+        let wallet = SecureWallet::from_mnemonic("node".to_string(), "pass", "AUR1", &mnemonic, fast).unwrap();
+        let mut node_wallet = NodeWallet::new(wallet);
+        node_wallet.unlock_timeout = Duration::from_millis(100);
+
+        assert!(node_wallet.keypair().is_none());
+        assert!(matches!(node_wallet.unlock("wrong"), Err(WalletError::WrongPassword)));
+        assert!(node_wallet.keypair().is_none());
+
+        node_wallet.unlock("pass").unwrap();
+        assert!(node_wallet.keypair().is_some());
+        std::thread::sleep(Duration::from_millis(150));
+        assert!(node_wallet.keypair().is_none());
+
+        node_wallet.unlock("pass").unwrap();
+        node_wallet.lock();
+        assert!(node_wallet.keypair().is_none());
+    }
+}
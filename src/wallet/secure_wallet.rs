@@ -2,7 +2,7 @@ use aes_gcm::{
     aead::{Aead, KeyInit, OsRng},
     Aes256Gcm, Nonce,
 };
-use argon2::{Argon2, PasswordHasher, PasswordHash, PasswordVerifier};
+use argon2::{Algorithm, Argon2, Params, PasswordHasher, Version};
 use argon2::password_hash::{rand_core::RngCore, SaltString};
 use bip39::Mnemonic;
 use secp256k1::{Secp256k1, SecretKey, PublicKey};
@@ -12,6 +12,59 @@ use zeroize::Zeroize;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
+use crate::utils::config::Network;
+use crate::wallet::address::{validate_address, wallet_address};
+use crate::wallet::address_book::AddressBook;
+use crate::wallet::error::WalletError;
+use crate::wallet::watch_only::WatchOnlyWallet;
+
+/// Format courant : un nonce distinct par champ chiffré, et une empreinte d'intégrité
+/// qui couvre aussi la version, l'adresse et les paramètres Argon2
+pub const WALLET_FORMAT_VERSION: &str = "2.0.0";
+/// Format d'origine : clé privée et seed chiffrées avec le même nonce
+pub const LEGACY_WALLET_VERSION: &str = "1.0.0-quantum-secure";
+
+/// Paramètres de coût Argon2 utilisés pour dériver la clé de chiffrement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    fn argon2(&self) -> Result<Argon2<'static>, WalletError> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| WalletError::Crypto(format!("Invalid Argon2 parameters: {}", e)))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    /// Dériver une clé AES-256 à partir d'un mot de passe et d'un sel (base64 sans padding).
+    /// Un mauvais mot de passe donne une autre clé : le déchiffrement AES-GCM échoue alors.
+    pub fn derive_key(&self, password: &str, salt: &str) -> Result<Vec<u8>, WalletError> {
+        let salt = SaltString::from_b64(salt)
+            .map_err(|e| WalletError::InvalidFormat(format!("Invalid salt: {}", e)))?;
+        
+        let password_hash = self.argon2()?
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(|e| WalletError::Crypto(format!("Argon2 failed: {}", e)))?;
+        
+        let hash = password_hash.hash
+            .ok_or_else(|| WalletError::Crypto("Argon2 produced no output".to_string()))?;
+        Ok(hash.as_bytes()[0..32].to_vec())
+    }
+}
+
+impl Default for KdfParams {
+    /// Valeurs par défaut d'Argon2 (19 MiB, 2 passes, 1 voie)
+    fn default() -> Self {
+        KdfParams {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
 
 /// Wallet ultra-sécurisé avec protection quantique
 #[derive(Serialize, Deserialize)]
@@ -26,19 +79,40 @@ pub struct SecureWallet {
     pub created_at: String,
     pub version: String,
     nonce: Vec<u8>,
+    /// Nonce de la seed ; vide dans le format d'origine, où `nonce` sert aux deux champs
+    #[serde(default)]
+    seed_nonce: Vec<u8>,
     salt: String,
+    /// Absent des anciens fichiers : ceux-ci ont été chiffrés avec les valeurs par défaut
+    #[serde(default)]
+    pub kdf_params: KdfParams,
+    /// `AddressBook` chiffré (JSON) ; vide tant qu'aucun contact n'a été ajouté
+    #[serde(default)]
+    encrypted_address_book: Vec<u8>,
+    #[serde(default)]
+    address_book_nonce: Vec<u8>,
     
     integrity_hash: String,
     pub quantum_ready: bool,
 }
 
 impl SecureWallet {
-    pub fn generate(name: String, password: &str, address_type: &str) -> Result<Self, String> {
+    pub fn generate(name: String, password: &str, address_type: &str) -> Result<Self, WalletError> {
+        Self::generate_with_params(name, password, address_type, KdfParams::default())
+    }
+    
+    /// Générer un wallet avec un coût Argon2 choisi (résistance au brute-force vs. temps de déverrouillage)
+    pub fn generate_with_params(
+        name: String,
+        password: &str,
+        address_type: &str,
+        kdf_params: KdfParams,
+    ) -> Result<Self, WalletError> {
         let mut entropy = [0u8; 32];
         OsRng.fill_bytes(&mut entropy);
         
         let mnemonic = Mnemonic::from_entropy(&entropy)
-            .map_err(|e| format!("Failed to generate mnemonic: {}", e))?;
+            .map_err(|e| WalletError::InvalidKey(format!("Failed to generate mnemonic: {}", e)))?;
         
         println!("\n╔════════════════════════════════════════════════╗");
         println!("║        🔐 SEED PHRASE (WRITE IT DOWN!)        ║");
@@ -66,6 +140,16 @@ impl SecureWallet {
         let mut input = String::new();
         std::io::stdin().read_line(&mut input).unwrap();
         
+        Self::from_mnemonic(name, password, address_type, &mnemonic, kdf_params)
+    }
+    
+    pub(crate) fn from_mnemonic(
+        name: String,
+        password: &str,
+        address_type: &str,
+        mnemonic: &Mnemonic,
+        kdf_params: KdfParams,
+    ) -> Result<Self, WalletError> {
         let seed = mnemonic.to_seed("");
         let mut private_key_bytes = [0u8; 32];
         let mut hasher = Sha256::new();
@@ -75,135 +159,159 @@ impl SecureWallet {
         
         let secp = Secp256k1::new();
         let secret_key = SecretKey::from_slice(&private_key_bytes)
-            .map_err(|e| format!("Invalid private key: {}", e))?;
+            .map_err(|e| WalletError::InvalidKey(format!("Invalid private key: {}", e)))?;
         let public_key = PublicKey::from_secret_key(&secp, &secret_key);
         
         let address = Self::generate_address(&public_key, address_type);
         
-        let (encrypted_private_key, encrypted_seed, nonce, salt) = 
-            Self::encrypt_sensitive_data(&private_key_bytes, mnemonic.to_string().as_bytes(), password)?;
-        
-        private_key_bytes.zeroize();
-        
-        let integrity_hash = Self::calculate_integrity_hash(
-            &encrypted_private_key,
-            &encrypted_seed,
-            &nonce,
-            &salt,
-        );
-        
         let quantum_ready = matches!(address_type, "AUR2" | "AUR3");
         
-        Ok(SecureWallet {
+        let mut wallet = SecureWallet {
             name,
             address,
             address_type: address_type.to_string(),
-            encrypted_private_key,
-            encrypted_seed,
+            encrypted_private_key: Vec::new(),
+            encrypted_seed: Vec::new(),
             created_at: chrono::Utc::now().to_rfc3339(),
-            version: "1.0.0-quantum-secure".to_string(),
-            nonce,
-            salt,
-            integrity_hash,
+            version: WALLET_FORMAT_VERSION.to_string(),
+            nonce: Vec::new(),
+            seed_nonce: Vec::new(),
+            salt: String::new(),
+            kdf_params,
+            encrypted_address_book: Vec::new(),
+            address_book_nonce: Vec::new(),
+            integrity_hash: String::new(),
             quantum_ready,
-        })
+        };
+        let result = wallet.seal(&private_key_bytes, mnemonic.to_string().as_bytes(), password);
+        private_key_bytes.zeroize();
+        result?;
+        
+        Ok(wallet)
+    }
+    
+    /// Chiffrer clé privée et seed au format courant et recalculer l'empreinte d'intégrité
+    fn seal(&mut self, private_key: &[u8], seed: &[u8], password: &str) -> Result<(), WalletError> {
+        self.encrypt_sensitive_data(private_key, seed, password)?;
+        self.version = WALLET_FORMAT_VERSION.to_string();
+        self.integrity_hash = self.integrity_hash_for_version()
+            .ok_or_else(|| WalletError::InvalidFormat(format!("Unsupported wallet version {}", self.version)))?;
+        Ok(())
+    }
+    
+    /// Un fichier d'un format antérieur, à passer par `migrate` une fois déverrouillé
+    pub fn needs_migration(&self) -> bool {
+        self.version != WALLET_FORMAT_VERSION
+    }
+    
+    /// Re-chiffrer un wallet d'un format antérieur au format courant (nouveaux sel et
+    /// nonces). Le mot de passe doit déchiffrer l'ancien contenu ; sans effet sinon.
+    pub fn migrate(&mut self, password: &str) -> Result<(), WalletError> {
+        if !self.needs_migration() {
+            return Ok(());
+        }
+        
+        let mut private_key = self.decrypt_private_key(password)?;
+        let seed = self.decrypt_seed(password);
+        let result = seed.and_then(|mut seed| {
+            let result = self.seal(&private_key, seed.as_bytes(), password);
+            seed.zeroize();
+            result
+        });
+        private_key.zeroize();
+        result
     }
     
     fn encrypt_sensitive_data(
+        &mut self,
         private_key: &[u8],
         seed: &[u8],
         password: &str,
-    ) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>, String), String> {
+    ) -> Result<(), WalletError> {
         let salt = SaltString::generate(&mut OsRng);
         
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|e| format!("Argon2 failed: {}", e))?;
-        
-        let hash_binding = password_hash.hash.unwrap();
-        let key_bytes = &hash_binding.as_bytes()[0..32];
-        
-        let cipher = Aes256Gcm::new_from_slice(key_bytes)
-            .map_err(|e| format!("Cipher creation failed: {}", e))?;
+        let mut key_bytes = Self::derive_key(&self.kdf_params, password, salt.as_str())?;
+        let cipher = Aes256Gcm::new_from_slice(&key_bytes)
+            .map_err(|e| WalletError::Crypto(format!("Cipher creation failed: {}", e)))?;
         
+        // AES-GCM : un nonce ne doit jamais chiffrer deux messages sous la même clé
         let mut nonce_bytes = [0u8; 12];
         OsRng.fill_bytes(&mut nonce_bytes);
-        let nonce = Nonce::from_slice(&nonce_bytes);
+        let mut seed_nonce_bytes = [0u8; 12];
+        OsRng.fill_bytes(&mut seed_nonce_bytes);
         
         let encrypted_private_key = cipher
-            .encrypt(nonce, private_key)
-            .map_err(|e| format!("Encryption failed: {}", e))?;
+            .encrypt(&Nonce::from(nonce_bytes), private_key)
+            .map_err(|e| WalletError::Crypto(format!("Encryption failed: {}", e)))?;
         
         let encrypted_seed = cipher
-            .encrypt(nonce, seed)
-            .map_err(|e| format!("Seed encryption failed: {}", e))?;
+            .encrypt(&Nonce::from(seed_nonce_bytes), seed)
+            .map_err(|e| WalletError::Crypto(format!("Seed encryption failed: {}", e)))?;
+        
+        key_bytes.zeroize();
         
-        Ok((
-            encrypted_private_key,
-            encrypted_seed,
-            nonce_bytes.to_vec(),
-            salt.to_string(),
-        ))
+        self.encrypted_private_key = encrypted_private_key;
+        self.encrypted_seed = encrypted_seed;
+        self.nonce = nonce_bytes.to_vec();
+        self.seed_nonce = seed_nonce_bytes.to_vec();
+        self.salt = salt.to_string();
+        Ok(())
+    }
+    
+    /// Nonce lu dans le fichier : une longueur autre que 12 octets est un format invalide
+    fn stored_nonce(bytes: &[u8]) -> Result<Nonce<aes_gcm::aead::consts::U12>, WalletError> {
+        let bytes = <[u8; 12]>::try_from(bytes)
+            .map_err(|_| WalletError::InvalidFormat(format!("Invalid nonce length {}", bytes.len())))?;
+        Ok(Nonce::from(bytes))
     }
     
-    pub fn decrypt_private_key(&self, password: &str) -> Result<Vec<u8>, String> {
+    /// Dériver la clé AES à partir du mot de passe et du sel stocké
+    fn derive_key(kdf_params: &KdfParams, password: &str, salt: &str) -> Result<Vec<u8>, WalletError> {
+        kdf_params.derive_key(password, salt)
+    }
+    
+    /// `WrongPassword` si le mot de passe ne convient pas : l'intégrité du fichier
+    /// étant vérifiée d'abord, un échec de déchiffrement ne peut venir que de lui
+    pub fn decrypt_private_key(&self, password: &str) -> Result<Vec<u8>, WalletError> {
         if !self.verify_integrity() {
-            return Err("SECURITY ALERT: Wallet integrity compromised!".to_string());
+            return Err(WalletError::IntegrityFailure);
         }
         
-        let salt = PasswordHash::new(&self.salt)
-            .map_err(|e| format!("Invalid salt: {}", e))?;
-        
-        let argon2 = Argon2::default();
+        let mut key_bytes = Self::derive_key(&self.kdf_params, password, &self.salt)?;
+        let cipher = Aes256Gcm::new_from_slice(&key_bytes)
+            .map_err(|e| WalletError::Crypto(format!("Cipher creation failed: {}", e)))?;
+        key_bytes.zeroize();
         
-        argon2
-            .verify_password(password.as_bytes(), &salt)
-            .map_err(|_| "Invalid password!".to_string())?;
-        
-        let hash_binding = salt.hash.unwrap();
-        let key_bytes = &hash_binding.as_bytes()[0..32];
-        
-        let cipher = Aes256Gcm::new_from_slice(key_bytes)
-            .map_err(|e| format!("Cipher creation failed: {}", e))?;
-        
-        let nonce = Nonce::from_slice(&self.nonce);
+        let nonce = Self::stored_nonce(&self.nonce)?;
         
         let decrypted = cipher
-            .decrypt(nonce, self.encrypted_private_key.as_ref())
-            .map_err(|_| "Decryption failed! Wrong password?".to_string())?;
+            .decrypt(&nonce, self.encrypted_private_key.as_ref())
+            .map_err(|_| WalletError::WrongPassword)?;
         
         Ok(decrypted)
     }
     
-    pub fn decrypt_seed(&self, password: &str) -> Result<String, String> {
+    pub fn decrypt_seed(&self, password: &str) -> Result<String, WalletError> {
         if !self.verify_integrity() {
-            return Err("SECURITY ALERT: Wallet integrity compromised!".to_string());
+            return Err(WalletError::IntegrityFailure);
         }
         
-        let salt = PasswordHash::new(&self.salt)
-            .map_err(|e| format!("Invalid salt: {}", e))?;
-        
-        let argon2 = Argon2::default();
-        argon2
-            .verify_password(password.as_bytes(), &salt)
-            .map_err(|_| "Invalid password!".to_string())?;
+        let mut key_bytes = Self::derive_key(&self.kdf_params, password, &self.salt)?;
+        let cipher = Aes256Gcm::new_from_slice(&key_bytes)
+            .map_err(|e| WalletError::Crypto(format!("Cipher creation failed: {}", e)))?;
+        key_bytes.zeroize();
         
-        let hash_binding = salt.hash.unwrap();
-        let key_bytes = &hash_binding.as_bytes()[0..32];
-        let cipher = Aes256Gcm::new_from_slice(key_bytes)
-            .map_err(|e| format!("Cipher creation failed: {}", e))?;
-        
-        let nonce = Nonce::from_slice(&self.nonce);
+        let nonce = Self::stored_nonce(if self.seed_nonce.is_empty() { &self.nonce } else { &self.seed_nonce })?;
         
         let decrypted = cipher
-            .decrypt(nonce, self.encrypted_seed.as_ref())
-            .map_err(|_| "Decryption failed!".to_string())?;
+            .decrypt(&nonce, self.encrypted_seed.as_ref())
+            .map_err(|_| WalletError::WrongPassword)?;
         
         String::from_utf8(decrypted)
-            .map_err(|_| "Invalid seed data".to_string())
+            .map_err(|_| WalletError::DecryptionFailed("Invalid seed data".to_string()))
     }
     
+    /// Empreinte du format d'origine (`LEGACY_WALLET_VERSION`)
     fn calculate_integrity_hash(
         encrypted_key: &[u8],
         encrypted_seed: &[u8],
@@ -218,76 +326,168 @@ impl SecureWallet {
         hex::encode(hasher.finalize())
     }
     
-    pub fn verify_integrity(&self) -> bool {
-        let calculated_hash = Self::calculate_integrity_hash(
-            &self.encrypted_private_key,
-            &self.encrypted_seed,
-            &self.nonce,
-            &self.salt,
-        );
-        
-        calculated_hash == self.integrity_hash
+    /// Empreinte calculée avec l'algorithme de la version déclarée du fichier ;
+    /// `None` pour une version inconnue
+    fn integrity_hash_for_version(&self) -> Option<String> {
+        match self.version.as_str() {
+            LEGACY_WALLET_VERSION => Some(Self::calculate_integrity_hash(
+                &self.encrypted_private_key,
+                &self.encrypted_seed,
+                &self.nonce,
+                &self.salt,
+            )),
+            WALLET_FORMAT_VERSION => {
+                let mut hasher = Keccak256::new();
+                for field in [
+                    self.version.as_bytes(),
+                    self.address.as_bytes(),
+                    &self.encrypted_private_key,
+                    &self.encrypted_seed,
+                    &self.nonce,
+                    &self.seed_nonce,
+                    self.salt.as_bytes(),
+                ] {
+                    // Champs préfixés par leur longueur : aucune frontière ambiguë
+                    hasher.update((field.len() as u32).to_le_bytes());
+                    hasher.update(field);
+                }
+                hasher.update(self.kdf_params.memory_kib.to_le_bytes());
+                hasher.update(self.kdf_params.iterations.to_le_bytes());
+                hasher.update(self.kdf_params.parallelism.to_le_bytes());
+                // Absent des fichiers sans carnet d'adresses : leur empreinte est inchangée
+                if !self.encrypted_address_book.is_empty() {
+                    for field in [&self.encrypted_address_book, &self.address_book_nonce] {
+                        hasher.update((field.len() as u32).to_le_bytes());
+                        hasher.update(field);
+                    }
+                }
+                Some(hex::encode(hasher.finalize()))
+            }
+            _ => None,
+        }
     }
     
-    fn generate_address(public_key: &PublicKey, addr_type: &str) -> String {
-        let public_key_bytes = public_key.serialize();
-        
-        let hash1 = Sha256::digest(&public_key_bytes);
-        let hash2 = Sha256::digest(&hash1);
-        
-        let hash160 = &hash2[0..20];
+    /// Déchiffrer le carnet d'adresses ; vide si aucun contact n'a encore été ajouté
+    pub fn address_book(&self, password: &str) -> Result<AddressBook, WalletError> {
+        if self.encrypted_address_book.is_empty() {
+            return Ok(AddressBook::default());
+        }
+        if !self.verify_integrity() {
+            return Err(WalletError::IntegrityFailure);
+        }
         
-        let version_byte = match addr_type {
-            "AUR1" => 0x4F,
-            "AUR2" => 0x50,
-            "AUR3" => 0x51,
-            _ => 0x4F,
-        };
+        let mut key_bytes = Self::derive_key(&self.kdf_params, password, &self.salt)?;
+        let cipher = Aes256Gcm::new_from_slice(&key_bytes)
+            .map_err(|e| WalletError::Crypto(format!("Cipher creation failed: {}", e)))?;
+        key_bytes.zeroize();
         
-        let mut payload = vec![version_byte];
-        payload.extend_from_slice(hash160);
+        let nonce: [u8; 12] = self.address_book_nonce.as_slice().try_into()
+            .map_err(|_| WalletError::InvalidFormat("Invalid address book nonce".to_string()))?;
+        let decrypted = cipher
+            .decrypt(&Nonce::from(nonce), self.encrypted_address_book.as_ref())
+            .map_err(|_| WalletError::WrongPassword)?;
+        serde_json::from_slice(&decrypted)
+            .map_err(|e| WalletError::DecryptionFailed(format!("Invalid address book: {}", e)))
+    }
+    
+    /// Re-chiffrer le carnet d'adresses (nouveau nonce) et recalculer l'empreinte.
+    /// Le mot de passe est d'abord vérifié sur la clé privée : le carnet est chiffré
+    /// avec la même clé dérivée qu'elle.
+    fn store_address_book(&mut self, book: &AddressBook, password: &str) -> Result<(), WalletError> {
+        if self.needs_migration() {
+            return Err(WalletError::InvalidFormat(format!(
+                "Wallet format {} must be migrated before storing contacts", self.version
+            )));
+        }
+        self.decrypt_private_key(password)?.zeroize();
         
-        let checksum_hash = Sha256::digest(&Sha256::digest(&payload));
-        let checksum = &checksum_hash[0..4];
-        payload.extend_from_slice(checksum);
+        let mut key_bytes = Self::derive_key(&self.kdf_params, password, &self.salt)?;
+        let cipher = Aes256Gcm::new_from_slice(&key_bytes)
+            .map_err(|e| WalletError::Crypto(format!("Cipher creation failed: {}", e)))?;
+        key_bytes.zeroize();
         
-        let encoded = bs58::encode(payload).into_string();
+        let plaintext = serde_json::to_vec(book)
+            .map_err(|e| WalletError::InvalidFormat(format!("Serialization failed: {}", e)))?;
+        let mut nonce_bytes = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce_bytes);
+        self.encrypted_address_book = cipher
+            .encrypt(&Nonce::from(nonce_bytes), plaintext.as_slice())
+            .map_err(|e| WalletError::Crypto(format!("Address book encryption failed: {}", e)))?;
+        self.address_book_nonce = nonce_bytes.to_vec();
+        self.integrity_hash = self.integrity_hash_for_version()
+            .ok_or_else(|| WalletError::InvalidFormat(format!("Unsupported wallet version {}", self.version)))?;
+        Ok(())
+    }
+    
+    /// Ajouter ou remplacer un contact. L'adresse doit être une adresse mainnet valide
+    /// (préfixe `AUR`, comme celles des wallets chiffrés), checksum compris.
+    pub fn add_contact(&mut self, label: &str, address: &str, password: &str) -> Result<(), WalletError> {
+        if label.trim().is_empty() {
+            return Err(WalletError::InvalidFormat("Contact label cannot be empty".to_string()));
+        }
+        if !validate_address(address, Network::Mainnet) {
+            return Err(WalletError::InvalidAddress(address.to_string()));
+        }
         
-        format!("{}{}", addr_type, encoded)
+        let mut book = self.address_book(password)?;
+        book.contacts.insert(label.to_string(), address.to_string());
+        self.store_address_book(&book, password)
+    }
+    
+    pub fn get_contact(&self, label: &str, password: &str) -> Result<Option<String>, WalletError> {
+        Ok(self.address_book(password)?.get(label).map(str::to_string))
+    }
+    
+    /// Annoter une transaction (affichée par la CLI à la place du txid seul)
+    pub fn label_transaction(&mut self, txid: &str, label: &str, password: &str) -> Result<(), WalletError> {
+        let mut book = self.address_book(password)?;
+        book.tx_labels.insert(txid.to_string(), label.to_string());
+        self.store_address_book(&book, password)
     }
     
-    pub fn save(&self, directory: &str) -> Result<String, String> {
+    pub fn verify_integrity(&self) -> bool {
+        self.integrity_hash_for_version()
+            .is_some_and(|calculated_hash| calculated_hash == self.integrity_hash)
+    }
+    
+    fn generate_address(public_key: &PublicKey, addr_type: &str) -> String {
+        wallet_address(&public_key.serialize(), addr_type)
+    }
+    
+    pub fn save(&self, directory: &str) -> Result<String, WalletError> {
         let wallet_dir = Path::new(directory);
         if !wallet_dir.exists() {
-            fs::create_dir_all(wallet_dir)
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
+            fs::create_dir_all(wallet_dir)?;
         }
         
         let filename = format!("{}/{}.secure.wallet", directory, self.name);
         
         let json = serde_json::to_string_pretty(self)
-            .map_err(|e| format!("Serialization failed: {}", e))?;
+            .map_err(|e| WalletError::InvalidFormat(format!("Serialization failed: {}", e)))?;
         
-        fs::write(&filename, json)
-            .map_err(|e| format!("Failed to write wallet: {}", e))?;
+        fs::write(&filename, json)?;
         
         Ok(filename)
     }
     
-    pub fn load(filename: &str) -> Result<Self, String> {
-        let content = fs::read_to_string(filename)
-            .map_err(|e| format!("Failed to read wallet: {}", e))?;
+    pub fn load(filename: &str) -> Result<Self, WalletError> {
+        let content = fs::read_to_string(filename)?;
         
         let wallet: SecureWallet = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse wallet: {}", e))?;
+            .map_err(|e| WalletError::InvalidFormat(format!("Failed to parse wallet: {}", e)))?;
         
         if !wallet.verify_integrity() {
-            return Err("⚠️  SECURITY ALERT: Wallet has been tampered with!".to_string());
+            return Err(WalletError::IntegrityFailure);
         }
         
         Ok(wallet)
     }
     
+    /// Vue en lecture seule : l'adresse, sans aucune donnée chiffrée
+    pub fn export_watch_only(&self) -> WatchOnlyWallet {
+        WatchOnlyWallet::new(self.name.clone(), self.address.clone())
+    }
+    
     pub fn security_info(&self) {
         println!("\n╔════════════════════════════════════════════════╗");
         println!("║           🔐 WALLET SECURITY INFO 🔐          ║");
@@ -304,4 +504,165 @@ impl SecureWallet {
         println!("║                                                ║");
         println!("╚════════════════════════════════════════════════╝\n");
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn test_mnemonic() -> Mnemonic {
+        Mnemonic::from_entropy(&[7u8; 32]).unwrap()
+    }
+
+    /// Fichier tel qu'écrit avant le format 2.0.0 : un seul nonce, ancienne empreinte
+    fn legacy_wallet_json(password: &str) -> String {
+        let current = SecureWallet::from_mnemonic("old".to_string(), password, "AUR1", &test_mnemonic(), KdfParams::default()).unwrap();
+        let private_key = current.decrypt_private_key(password).unwrap();
+
+        let salt = SaltString::generate(&mut OsRng);
+        let key = KdfParams::default().derive_key(password, salt.as_str()).unwrap();
+        let cipher = Aes256Gcm::new_from_slice(&key).unwrap();
+        let nonce = [9u8; 12];
+        let encrypted_private_key = cipher.encrypt(Nonce::from_slice(&nonce), private_key.as_slice()).unwrap();
+        let encrypted_seed = cipher.encrypt(Nonce::from_slice(&nonce), test_mnemonic().to_string().as_bytes()).unwrap();
+        let integrity_hash = SecureWallet::calculate_integrity_hash(&encrypted_private_key, &encrypted_seed, &nonce, salt.as_str());
+
+        serde_json::json!({
+            "name": "old",
+            "address": current.address,
+            "address_type": "AUR1",
+            "encrypted_private_key": encrypted_private_key,
+            "encrypted_seed": encrypted_seed,
+            "created_at": "2025-01-01T00:00:00+00:00",
+            "version": LEGACY_WALLET_VERSION,
+            "nonce": nonce,
+            "salt": salt.as_str(),
+            "integrity_hash": integrity_hash,
+            "quantum_ready": false,
+        }).to_string()
+    }
+
+    #[test]
+    fn test_legacy_wallet_loads_and_migrates() {
+        let dir = std::env::temp_dir().join(format!("aur-wallet-migrate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("old.secure.wallet");
+        fs::write(&path, legacy_wallet_json("pass")).unwrap();
+
+        let mut wallet = SecureWallet::load(path.to_str().unwrap()).unwrap();
+        assert!(wallet.needs_migration());
+        let private_key = wallet.decrypt_private_key("pass").unwrap();
+
+        assert!(wallet.migrate("wrong").is_err());
+        assert_eq!(wallet.version, LEGACY_WALLET_VERSION);
+
+        wallet.migrate("pass").unwrap();
+        assert!(!wallet.needs_migration());
+        assert_ne!(wallet.nonce, wallet.seed_nonce);
+        wallet.save(dir.to_str().unwrap()).unwrap();
+
+        let migrated = SecureWallet::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(migrated.version, WALLET_FORMAT_VERSION);
+        assert_eq!(migrated.decrypt_private_key("pass").unwrap(), private_key);
+        assert_eq!(migrated.decrypt_seed("pass").unwrap(), test_mnemonic().to_string());
+
+        // L'empreinte courante couvre l'adresse : la modifier est détecté
+        let mut tampered = migrated;
+        tampered.address = "AUR1Attacker".to_string();
+        assert!(!tampered.verify_integrity());
+        tampered.version = "9.9.9".to_string();
+        assert!(!tampered.verify_integrity());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_error_variants_for_wrong_password_and_tampering() {
+        let wallet = SecureWallet::from_mnemonic("errors".to_string(), "pass", "AUR1", &test_mnemonic(), KdfParams::default()).unwrap();
+        assert!(matches!(wallet.decrypt_private_key("wrong"), Err(WalletError::WrongPassword)));
+        assert!(matches!(wallet.decrypt_seed("wrong"), Err(WalletError::WrongPassword)));
+
+        let dir = std::env::temp_dir().join(format!("aur-wallet-errors-{}", std::process::id()));
+        let path = wallet.save(dir.to_str().unwrap()).unwrap();
+        let mut file: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        file["encrypted_seed"][0] = serde_json::json!(file["encrypted_seed"][0].as_u64().unwrap() ^ 1);
+        fs::write(&path, file.to_string()).unwrap();
+        assert!(matches!(SecureWallet::load(&path), Err(WalletError::IntegrityFailure)));
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(SecureWallet::load(&path), Err(WalletError::InvalidFormat(_))));
+        fs::remove_dir_all(&dir).ok();
+        assert!(matches!(SecureWallet::load(&path), Err(WalletError::Io(_))));
+    }
+
+    #[test]
+    fn test_address_book_persisted_encrypted() {
+        let mut wallet = SecureWallet::from_mnemonic("contacts".to_string(), "pass", "AUR1", &test_mnemonic(), KdfParams::default()).unwrap();
+        let alice = crate::wallet::address::generate_address(&[2u8; 33], Network::Mainnet);
+        assert_eq!(wallet.get_contact("Alice", "pass").unwrap(), None);
+
+        wallet.add_contact("Alice", &alice, "pass").unwrap();
+        wallet.label_transaction(&"ab".repeat(32), "Rent", "pass").unwrap();
+        assert!(matches!(wallet.add_contact("Bob", &alice, "wrong"), Err(WalletError::WrongPassword)));
+
+        let dir = std::env::temp_dir().join(format!("aur-wallet-contacts-{}", std::process::id()));
+        let path = wallet.save(dir.to_str().unwrap()).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains(&alice));
+
+        let reloaded = SecureWallet::load(&path).unwrap();
+        assert_eq!(reloaded.get_contact("Alice", "pass").unwrap(), Some(alice.clone()));
+        assert!(matches!(reloaded.get_contact("Alice", "wrong"), Err(WalletError::WrongPassword)));
+        let book = reloaded.address_book("pass").unwrap();
+        assert_eq!(book.display_name(&alice), "Alice");
+        assert_eq!(book.display_name("AUR1Unknown"), "AUR1Unknown");
+        assert_eq!(book.tx_label(&"ab".repeat(32)), Some("Rent"));
+        assert_eq!(reloaded.decrypt_seed("pass").unwrap(), test_mnemonic().to_string());
+
+        // Le carnet chiffré est couvert par l'empreinte d'intégrité
+        let mut tampered = reloaded;
+        tampered.encrypted_address_book[0] ^= 1;
+        assert!(!tampered.verify_integrity());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_invalid_contact_address_rejected() {
+        let mut wallet = SecureWallet::from_mnemonic("contacts".to_string(), "pass", "AUR1", &test_mnemonic(), KdfParams::default()).unwrap();
+        let testnet = crate::wallet::address::generate_address(&[2u8; 33], Network::Testnet);
+        let mut corrupted = crate::wallet::address::generate_address(&[2u8; 33], Network::Mainnet);
+        corrupted.pop();
+        corrupted.push('z');
+
+        for address in ["", "AUR1Bob", testnet.as_str(), corrupted.as_str()] {
+            assert!(matches!(wallet.add_contact("Bob", address, "pass"), Err(WalletError::InvalidAddress(_))), "{}", address);
+        }
+        assert!(wallet.encrypted_address_book.is_empty());
+        assert_eq!(wallet.get_contact("Bob", "pass").unwrap(), None);
+    }
+
+    #[test]
+    fn test_custom_kdf_params_roundtrip() {
+        let strong = KdfParams { memory_kib: 32 * 1024, iterations: 3, parallelism: 1 };
+        let wallet = SecureWallet::from_mnemonic("strong".to_string(), "pass", "AUR1", &test_mnemonic(), strong).unwrap();
+        let default = SecureWallet::from_mnemonic("default".to_string(), "pass", "AUR1", &test_mnemonic(), KdfParams::default()).unwrap();
+
+        // Les paramètres survivent à la sérialisation du fichier
+        let reloaded: SecureWallet = serde_json::from_str(&serde_json::to_string(&wallet).unwrap()).unwrap();
+        assert_eq!(reloaded.kdf_params, strong);
+
+        let start = Instant::now();
+        let strong_key = reloaded.decrypt_private_key("pass").unwrap();
+        let strong_time = start.elapsed();
+
+        let start = Instant::now();
+        let default_key = default.decrypt_private_key("pass").unwrap();
+        let default_time = start.elapsed();
+
+        assert_eq!(strong_key, default_key);
+        assert_eq!(reloaded.decrypt_seed("pass").unwrap(), test_mnemonic().to_string());
+        assert!(reloaded.decrypt_private_key("wrong").is_err());
+        assert!(strong_time > default_time, "{:?} <= {:?}", strong_time, default_time);
+    }
+}
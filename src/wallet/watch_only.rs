@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use crate::blockchain::{Blockchain, Transaction, Utxo};
+use crate::security::SignatureValidator;
+use crate::utils::config::Network;
+use crate::wallet::address::generate_address;
+
+/// Vue en lecture seule d'un wallet : une adresse (et éventuellement sa clé publique),
+/// sans aucune clé privée. Suffit pour suivre les dépôts d'un stockage à froid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchOnlyWallet {
+    pub name: String,
+    pub address: String,
+    /// Clé publique hex (compressée), si elle est connue
+    pub public_key: Option<String>,
+}
+
+impl WatchOnlyWallet {
+    pub fn new(name: String, address: String) -> Self {
+        WatchOnlyWallet { name, address, public_key: None }
+    }
+
+    /// Importer une clé publique hex ; l'adresse suivie en est dérivée pour `network`
+    pub fn from_public_key(name: String, public_key_hex: &str, network: Network) -> Result<Self, String> {
+        let bytes = hex::decode(public_key_hex).map_err(|e| format!("Invalid public key encoding: {}", e))?;
+        secp256k1::PublicKey::from_slice(&bytes).map_err(|e| format!("Invalid public key: {}", e))?;
+
+        Ok(WatchOnlyWallet {
+            name,
+            address: generate_address(&bytes, network),
+            public_key: Some(public_key_hex.to_string()),
+        })
+    }
+
+    /// Solde confirmé de l'adresse d'après l'ensemble des UTXO de la chaîne
+    pub fn balance(&self, chain: &Blockchain) -> u64 {
+        chain.get_balance(&self.address)
+    }
+
+    pub fn utxos(&self, chain: &Blockchain) -> Vec<Utxo> {
+        chain.get_utxos(&self.address)
+    }
+
+    /// Vérifier une transaction entrante : signatures valides, inputs dépensables sur `chain`
+    /// et au moins un output vers l'adresse. Retourne le montant reçu.
+    pub fn verify_incoming(&self, tx: &Transaction, chain: &Blockchain) -> Result<u64, String> {
+        let received = tx.outputs.iter()
+            .filter(|output| output.address == self.address)
+            .fold(0u64, |acc, output| acc.saturating_add(output.value));
+        if received == 0 {
+            return Err(format!("Transaction {} pays nothing to {}", tx.id, self.address));
+        }
+
+        SignatureValidator::verify_transaction(tx).map_err(|e| e.to_string())?;
+        chain.validate_transaction(tx).map_err(|e| e.to_string())?;
+        Ok(received)
+    }
+
+    /// Un wallet en lecture seule ne détient aucune clé : la signature est toujours refusée
+    pub fn sign_transaction_input(&self, _tx: &mut Transaction, _index: usize) -> Result<(), String> {
+        Err(format!("Watch-only wallet {} cannot sign transactions", self.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::genesis::create_genesis_block_with_params;
+    use crate::blockchain::{TxInput, TxOutput};
+    use crate::utils::config::ConsensusParams;
+    use crate::wallet::keys::KeyPair;
+    use crate::wallet::{KdfParams, SecureWallet};
+
+    #[test]
+    fn test_watch_only_export_tracks_balance_but_cannot_sign() {
+        let fast = KdfParams { memory_kib: 8, iterations: 1, parallelism: 1 };
+        let cold = SecureWallet::from_mnemonic(
+            "cold".to_string(),
+            "pass",
+            "AUR1",
+            &bip39::Mnemonic::from_entropy(&[9u8; 32]).unwrap(),
+            fast,
+        ).unwrap();
+        let watch = cold.export_watch_only();
+        assert_eq!(watch.address, cold.address);
+        assert!(watch.public_key.is_none());
+
+        let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+        let mut chain = Blockchain::with_params(params.clone());
+        chain.chain.push(create_genesis_block_with_params(&params));
+        assert_eq!(watch.balance(&chain), 0);
+
+        chain.mine_pending_transactions(watch.address.clone());
+        let payer = KeyPair::generate();
+        chain.mine_pending_transactions(crate::wallet::address::address_from_keypair(&payer, Network::Mainnet));
+        let reward = chain.chain[1].transactions[0].total_output();
+        assert_eq!(watch.balance(&chain), reward);
+        assert_eq!(watch.utxos(&chain).len(), 1);
+
+        // Dépôt signé par un tiers vers l'adresse suivie
+        let funding = chain.chain[2].transactions[0].clone();
+        let mut deposit = Transaction::new(
+            vec![TxInput { prev_tx_id: funding.id.clone(), output_index: 0, signature: String::new(), public_key: String::new() }],
+            vec![TxOutput { value: 1_000_000, address: watch.address.clone() }],
+        );
+        payer.sign_transaction_input(&mut deposit, 0).unwrap();
+        assert_eq!(watch.verify_incoming(&deposit, &chain), Ok(1_000_000));
+
+        let mut forged = deposit.clone();
+        forged.outputs[0].value = 2_000_000;
+        assert!(watch.verify_incoming(&forged, &chain).is_err());
+
+        let err = watch.sign_transaction_input(&mut deposit, 0).unwrap_err();
+        assert!(err.contains("cannot sign"));
+
+        // Import depuis une clé publique seule
+        let keys = KeyPair::generate();
+        let imported = WatchOnlyWallet::from_public_key("audit".to_string(), &keys.public_key_hex(), Network::Mainnet).unwrap();
+        assert!(crate::wallet::address::validate_address(&imported.address, Network::Mainnet));
+        assert!(WatchOnlyWallet::from_public_key("bad".to_string(), "zz", Network::Mainnet).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use auriumchain::blockchain::Blockchain;
+use auriumchain::blockchain::genesis::create_genesis_block_with_params;
+use auriumchain::utils::config::ConsensusParams;
+use std::process::{Command, Output};
+
+fn chain_tool(data_file: &str, command: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_chain_tool"))
+        .args(["--data-file", data_file, command])
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_verify_detects_corruption_and_repair_truncates() {
+    let params = ConsensusParams { difficulty: 1, ..ConsensusParams::default() };
+    let mut chain = Blockchain::with_params(params.clone());
+    chain.chain.push(create_genesis_block_with_params(&params));
+    for miner in ["AUR1Alice", "AUR1Bob", "AUR1Carol", "AUR1Dave"] {
+        chain.mine_pending_transactions(miner.to_string());
+    }
+    let path = std::env::temp_dir()
+        .join(format!("aur_chain_tool_{}.json", std::process::id()))
+        .to_string_lossy()
+        .into_owned();
+    chain.save_to_file(&path).unwrap();
+
+    let verified = chain_tool(&path, "verify");
+    assert!(verified.status.success(), "{}", String::from_utf8_lossy(&verified.stdout));
+
+    // Contenu modifié sans refaire la preuve de travail
+    let mut blocks: Vec<serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    blocks[3]["merkle_root"] = serde_json::json!("f".repeat(64));
+    std::fs::write(&path, serde_json::to_string(&blocks).unwrap()).unwrap();
+
+    let verified = chain_tool(&path, "verify");
+    let stdout = String::from_utf8_lossy(&verified.stdout);
+    assert!(!verified.status.success());
+    assert!(stdout.contains("Invalid block at height 3"), "{}", stdout);
+
+    let repaired = chain_tool(&path, "repair");
+    assert!(repaired.status.success(), "{}", String::from_utf8_lossy(&repaired.stderr));
+    let reloaded = Blockchain::load_from_file_with_params(&path, params).unwrap();
+    assert_eq!(reloaded.chain.len(), 3);
+    assert_eq!(reloaded.chain[2].hash, chain.chain[2].hash);
+    assert!(chain_tool(&path, "verify").status.success());
+
+    let backup = format!("{}.bak", path);
+    let stored: Vec<serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(&backup).unwrap()).unwrap();
+    assert_eq!(stored.len(), 5);
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(&backup);
+}
@@ -1,101 +1,91 @@
-use auriumchain::blockchain::{Blockchain, Block, Transaction, TxInput, TxOutput};
-use auriumchain::blockchain::genesis::calculate_block_reward;
+use auriumchain::blockchain::{Blockchain, Block, BlockOutcome, Transaction};
+use auriumchain::blockchain::genesis::{calculate_block_reward, create_genesis_block, create_genesis_block_with_params};
+use auriumchain::utils::config::{ConsensusParams, GenesisConfig, Network};
+use auriumchain::wallet::address::generate_address;
 use chrono::Utc;
 
+/// Chaîne démarrée au genesis, difficulté réduite pour miner vite, coinbases disponibles aussitôt
+fn test_chain() -> Blockchain {
+    let params = ConsensusParams { difficulty: 1, coinbase_maturity: 0, ..ConsensusParams::default() };
+    let mut blockchain = Blockchain::with_params(params.clone());
+    blockchain.chain.push(create_genesis_block_with_params(&params));
+    blockchain
+}
+
+fn address(name: &str) -> String {
+    generate_address(name.as_bytes(), Network::Mainnet)
+}
+
 /// TEST 1: Tenter de miner un bloc avec récompense EXCESSIVE
 #[test]
 fn test_reject_excessive_mining_reward() {
-    let mut blockchain = Blockchain::new();
-    let genesis = blockchain.get_latest_block().clone();
-    
-    let malicious_coinbase = Transaction {
-        id: "hack_attempt_1".to_string(),
-        inputs: vec![],
-        outputs: vec![TxOutput {
-            value: 1_000_000_00000000,
-            address: "HACKER".to_string(),
-        }],
-        timestamp: Utc::now().timestamp(),
-        signature: String::new(),
-    };
+    let mut blockchain = test_chain();
+    let genesis = blockchain.get_latest_block().unwrap().clone();
+    
+    let malicious_coinbase = Transaction::coinbase(address("HACKER"), 1_000_000_00000000, Utc::now().timestamp());
     
     let mut bad_block = Block::new(
         1,
         vec![malicious_coinbase],
         genesis.hash.clone(),
-        4,
-        "HACKER".to_string()
+        1,
+        address("HACKER")
     );
     
     bad_block.mine();
-    let result = blockchain.add_block(bad_block);
+    let err = blockchain.check_new_block(&bad_block).unwrap_err();
+    assert!(err.to_string().contains("Excessive mining reward"), "{}", err);
     
-    assert!(result.is_err(), "❌ CRITICAL: Excessive reward was ACCEPTED!");
+    assert_eq!(blockchain.receive_block(bad_block), BlockOutcome::Rejected, "❌ CRITICAL: Excessive reward was ACCEPTED!");
+    assert_eq!(blockchain.chain.len(), 1);
     println!("✅ Test 1 PASSED: Excessive reward rejected");
 }
 
 /// TEST 2: Bloc avec Proof of Work INVALIDE
 #[test]
 fn test_reject_invalid_pow() {
-    let mut blockchain = Blockchain::new();
-    let genesis = blockchain.get_latest_block().clone();
-    
-    let coinbase = Transaction {
-        id: "coinbase".to_string(),
-        inputs: vec![],
-        outputs: vec![TxOutput {
-            value: calculate_block_reward(1),
-            address: "Miner".to_string(),
-        }],
-        timestamp: Utc::now().timestamp(),
-        signature: String::new(),
-    };
+    let mut blockchain = test_chain();
+    let genesis = blockchain.get_latest_block().unwrap().clone();
+    
+    let coinbase = Transaction::coinbase(address("Miner"), calculate_block_reward(1), Utc::now().timestamp());
     
     let mut bad_block = Block::new(
         1,
         vec![coinbase],
         genesis.hash.clone(),
-        4,
-        "Miner".to_string()
+        1,
+        address("Miner")
     );
     
     bad_block.hash = "1234567890abcdef".to_string();
     bad_block.nonce = 999;
     
-    let result = blockchain.add_block(bad_block);
-    assert!(result.is_err(), "❌ CRITICAL: Invalid PoW accepted!");
+    assert_eq!(blockchain.receive_block(bad_block), BlockOutcome::Rejected, "❌ CRITICAL: Invalid PoW accepted!");
+    assert_eq!(blockchain.chain.len(), 1);
     println!("✅ Test 2 PASSED: Invalid PoW rejected");
 }
 
 /// TEST 3: Bloc avec previous_hash INCORRECT
 #[test]
 fn test_reject_wrong_previous_hash() {
-    let mut blockchain = Blockchain::new();
-    let genesis = blockchain.get_latest_block().clone();
-    
-    let coinbase = Transaction {
-        id: "coinbase".to_string(),
-        inputs: vec![],
-        outputs: vec![TxOutput {
-            value: calculate_block_reward(1),
-            address: "Miner".to_string(),
-        }],
-        timestamp: Utc::now().timestamp(),
-        signature: String::new(),
-    };
+    let mut blockchain = test_chain();
+    
+    let coinbase = Transaction::coinbase(address("Miner"), calculate_block_reward(1), Utc::now().timestamp());
     
     let mut bad_block = Block::new(
         1,
         vec![coinbase],
         "0000WRONG_HASH".to_string(),
-        4,
-        "Miner".to_string()
+        1,
+        address("Miner")
     );
     
     bad_block.mine();
-    let result = blockchain.add_block(bad_block);
+    assert!(blockchain.check_new_block(&bad_block).is_err(), "❌ CRITICAL: Wrong previous hash accepted!");
     
-    assert!(result.is_err(), "❌ CRITICAL: Wrong previous hash accepted!");
+    // Parent inconnu : au mieux mis de côté comme orphelin, jamais ajouté à la chaîne
+    assert_ne!(blockchain.receive_block(bad_block), BlockOutcome::Connected(1));
+    assert_eq!(blockchain.chain.len(), 1);
     println!("✅ Test 3 PASSED: Wrong previous hash rejected");
 }
 
@@ -103,15 +93,16 @@ fn test_reject_wrong_previous_hash() {
 #[test]
 fn test_genesis_immutability() {
     let mut blockchain = Blockchain::new();
+    blockchain.chain.push(create_genesis_block());
     let original_genesis_hash = blockchain.chain[0].hash.clone();
+    assert!(blockchain.verify_genesis(&GenesisConfig::default()).is_ok());
     
     blockchain.chain[0].nonce += 1;
     blockchain.chain[0].hash = blockchain.chain[0].calculate_hash();
     
-    assert!(!blockchain.is_valid(), "❌ CRITICAL: Genesis block was modified!");
+    assert!(blockchain.verify_genesis(&GenesisConfig::default()).is_err(), "❌ CRITICAL: Genesis block was modified!");
     
-    blockchain = Blockchain::new();
-    assert_eq!(blockchain.chain[0].hash, original_genesis_hash);
+    assert_eq!(create_genesis_block().hash, original_genesis_hash);
     
     println!("✅ Test 4 PASSED: Genesis immutability verified");
 }
@@ -119,45 +110,35 @@ fn test_genesis_immutability() {
 /// TEST 5: Index de bloc INCORRECT
 #[test]
 fn test_reject_wrong_index() {
-    let mut blockchain = Blockchain::new();
-    let genesis = blockchain.get_latest_block().clone();
-    
-    let coinbase = Transaction {
-        id: "coinbase".to_string(),
-        inputs: vec![],
-        outputs: vec![TxOutput {
-            value: calculate_block_reward(1),
-            address: "Miner".to_string(),
-        }],
-        timestamp: Utc::now().timestamp(),
-        signature: String::new(),
-    };
+    let mut blockchain = test_chain();
+    let genesis = blockchain.get_latest_block().unwrap().clone();
+    
+    let coinbase = Transaction::coinbase(address("Miner"), calculate_block_reward(1), Utc::now().timestamp());
     
     let mut bad_block = Block::new(
         99,
         vec![coinbase],
         genesis.hash.clone(),
-        4,
-        "Miner".to_string()
+        1,
+        address("Miner")
     );
     
     bad_block.mine();
-    let result = blockchain.add_block(bad_block);
-    
-    assert!(result.is_err(), "❌ CRITICAL: Wrong index accepted!");
+    assert_eq!(blockchain.receive_block(bad_block), BlockOutcome::Rejected, "❌ CRITICAL: Wrong index accepted!");
+    assert_eq!(blockchain.chain.len(), 1);
     println!("✅ Test 5 PASSED: Wrong index rejected");
 }
 
 /// TEST 6: Chaîne complète doit rester valide après plusieurs blocs
 #[test]
 fn test_blockchain_validity_after_multiple_blocks() {
-    let mut blockchain = Blockchain::new();
+    let mut blockchain = test_chain();
     
     for i in 1..=5 {
-        blockchain.mine_pending_transactions(format!("Miner_{}", i));
+        blockchain.mine_pending_transactions(address(&format!("Miner_{}", i)));
     }
     
-    assert!(blockchain.is_valid(), "❌ CRITICAL: Blockchain became invalid!");
+    assert!(blockchain.is_chain_valid(), "❌ CRITICAL: Blockchain became invalid!");
     assert_eq!(blockchain.chain.len(), 6); // Genesis + 5 blocs
     
     println!("✅ Test 6 PASSED: Blockchain stays valid after multiple blocks");
@@ -166,14 +147,15 @@ fn test_blockchain_validity_after_multiple_blocks() {
 /// TEST 7: Balance calculation correcte
 #[test]
 fn test_balance_calculation() {
-    let mut blockchain = Blockchain::new();
+    let mut blockchain = test_chain();
+    let (miner1, miner2) = (address("Miner1"), address("Miner2"));
     
-    blockchain.mine_pending_transactions("Miner1".to_string());
-    blockchain.mine_pending_transactions("Miner1".to_string());
-    blockchain.mine_pending_transactions("Miner2".to_string());
+    blockchain.mine_pending_transactions(miner1.clone());
+    blockchain.mine_pending_transactions(miner1.clone());
+    blockchain.mine_pending_transactions(miner2.clone());
     
-    let balance1 = blockchain.get_balance("Miner1");
-    let balance2 = blockchain.get_balance("Miner2");
+    let balance1 = blockchain.get_balance(&miner1);
+    let balance2 = blockchain.get_balance(&miner2);
     
     let expected1 = calculate_block_reward(1) + calculate_block_reward(2);
     let expected2 = calculate_block_reward(3);
@@ -187,15 +169,15 @@ fn test_balance_calculation() {
 /// TEST 8: Hash du Genesis doit TOUJOURS être identique
 #[test]
 fn test_deterministic_genesis() {
-    let chain1 = Blockchain::new();
-    let chain2 = Blockchain::new();
-    let chain3 = Blockchain::new();
+    let genesis1 = create_genesis_block();
+    let genesis2 = create_genesis_block();
+    let genesis3 = create_genesis_block();
     
-    assert_eq!(chain1.chain[0].hash, chain2.chain[0].hash);
-    assert_eq!(chain2.chain[0].hash, chain3.chain[0].hash);
+    assert_eq!(genesis1.hash, genesis2.hash);
+    assert_eq!(genesis2.hash, genesis3.hash);
     
-    let expected_hash = "0000521165d99d6bcd916e3ac5ecc5897084ddd0572b5de740cc55972de500d9";
-    assert_eq!(chain1.chain[0].hash, expected_hash, "❌ CRITICAL: Genesis hash changed!");
+    let expected_hash = "0000e1c308cbcc657b493c1a08d6c6a7bbea5b0827ab6ca5008b9fff78ad8c3c";
+    assert_eq!(genesis1.hash, expected_hash, "❌ CRITICAL: Genesis hash changed!");
     
     println!("✅ Test 8 PASSED: Genesis is deterministic");
 }
@@ -203,14 +185,14 @@ fn test_deterministic_genesis() {
 /// TEST 9: Difficulté doit être respectée
 #[test]
 fn test_difficulty_respected() {
-    let mut blockchain = Blockchain::new();
+    let mut blockchain = test_chain();
     
-    blockchain.mine_pending_transactions("Miner1".to_string());
+    blockchain.mine_pending_transactions(address("Miner1"));
     
-    let latest = blockchain.get_latest_block();
-    let target = "0".repeat(blockchain.difficulty as usize);
+    let latest = blockchain.get_latest_block().unwrap();
     
-    assert!(latest.hash.starts_with(&target), "❌ CRITICAL: Block doesn't meet difficulty!");
+    assert!(latest.meets_difficulty(), "❌ CRITICAL: Block doesn't meet difficulty!");
+    assert!(latest.verify_consensus_pow(&blockchain.params).is_ok());
     
     println!("✅ Test 9 PASSED: Difficulty respected");
 }
@@ -218,14 +200,14 @@ fn test_difficulty_respected() {
 /// TEST 10: Stress test - 20 blocs consécutifs
 #[test]
 fn test_stress_20_blocks() {
-    let mut blockchain = Blockchain::new();
+    let mut blockchain = test_chain();
     
     for i in 1..=20 {
-        blockchain.mine_pending_transactions(format!("Miner_{}", i % 3));
+        blockchain.mine_pending_transactions(address(&format!("Miner_{}", i % 3)));
     }
     
     assert_eq!(blockchain.chain.len(), 21); // Genesis + 20
-    assert!(blockchain.is_valid(), "❌ CRITICAL: Chain invalid after stress test!");
+    assert!(blockchain.is_chain_valid(), "❌ CRITICAL: Chain invalid after stress test!");
     
     println!("✅ Test 10 PASSED: Stress test with 20 blocks succeeded");
 }